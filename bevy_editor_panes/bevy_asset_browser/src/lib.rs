@@ -43,8 +43,11 @@ impl Plugin for AssetBrowserPanePlugin {
             absolute_path
         };
 
-        app.add_plugins(ScrollBoxPlugin)
-            .insert_resource(DefaultSourceFilePath(default_source_absolute_file_path))
+        if !app.is_plugin_added::<ScrollBoxPlugin>() {
+            app.add_plugins(ScrollBoxPlugin);
+        }
+
+        app.insert_resource(DefaultSourceFilePath(default_source_absolute_file_path))
             .insert_resource(AssetBrowserLocation::default())
             .insert_resource(DirectoryContent::default())
             .add_systems(Startup, io::task::fetch_directory_content)
@@ -0,0 +1,384 @@
+//! A pane listing the assets loaded in the world, per asset type.
+//!
+//! Every asset type registered with [`ReflectAsset`] is listed with its number of assets. Expanding
+//! an asset type shows its assets with their path, number of live handles and load state, along
+//! with a short summary for common asset types such as images, meshes and standard materials.
+//!
+//! Handles are only counted for the assets tracked by the [`AssetServer`], such as loaded assets,
+//! which helps tracking down their leaked handles. The assets added with [`Assets::add`], such as
+//! procedural meshes and materials, are shown as untracked, as Bevy doesn't expose the number of
+//! their handles.
+//!
+//! The list is refreshed periodically, as assets change without any change detection the pane
+//! could rely on.
+
+use std::{any::TypeId, sync::Arc, time::Duration};
+
+use bevy::{
+    asset::{ReflectAsset, UntypedAssetId, UntypedHandle},
+    platform::collections::HashSet,
+    prelude::*,
+    time::common_conditions::on_timer,
+};
+use bevy_editor_styles::Theme;
+use bevy_pane_layout::prelude::{PaneAppExt, PaneStructure};
+use bevy_scroll_box::{ScrollBoxPlugin, spawn_scroll_box};
+
+/// How often the asset list is refreshed.
+const REFRESH_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Plugin for the editor asset inspector pane.
+pub struct AssetInspectorPlugin;
+
+impl Plugin for AssetInspectorPlugin {
+    fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<ScrollBoxPlugin>() {
+            app.add_plugins(ScrollBoxPlugin);
+        }
+
+        app.register_pane("Asset Inspector", setup_pane)
+            .init_resource::<ExpandedAssetTypes>()
+            .add_systems(
+                Update,
+                update_asset_inspector.run_if(
+                    on_timer(REFRESH_INTERVAL)
+                        .or(resource_changed::<ExpandedAssetTypes>)
+                        .or(any_match_filter::<Added<AssetInspectorRoot>>),
+                ),
+            );
+    }
+}
+
+/// The asset types whose assets are listed in the asset inspector. Other asset types only show
+/// their number of assets.
+#[derive(Resource, Default)]
+pub struct ExpandedAssetTypes(HashSet<TypeId>);
+
+impl ExpandedAssetTypes {
+    /// Check whether the assets of an asset type are listed.
+    pub fn is_expanded(&self, asset_type: TypeId) -> bool {
+        self.0.contains(&asset_type)
+    }
+
+    /// Toggle an asset type between listing its assets and only showing their number.
+    pub fn toggle(&mut self, asset_type: TypeId) {
+        if !self.0.remove(&asset_type) {
+            self.0.insert(asset_type);
+        }
+    }
+}
+
+/// Root UI node of the asset inspector. The asset list is built as its children.
+#[derive(Component)]
+struct AssetInspectorRoot;
+
+/// The header of an asset type in the asset inspector, expanding it when clicked.
+#[derive(Component)]
+struct AssetTypeHeader(TypeId);
+
+/// The assets of one asset type, as shown in the asset inspector.
+#[derive(PartialEq)]
+struct AssetTypeInfo {
+    type_id: TypeId,
+    name: &'static str,
+    count: usize,
+    /// The assets, only collected for expanded asset types.
+    assets: Vec<AssetInfo>,
+}
+
+/// One asset, as shown in the asset inspector.
+#[derive(PartialEq)]
+struct AssetInfo {
+    id: UntypedAssetId,
+    /// The path the asset was loaded from, if any.
+    path: Option<String>,
+    /// The number of live strong handles to the asset, if it is tracked by the [`AssetServer`].
+    handles: Option<usize>,
+    /// The load state of the asset and of its dependencies, if it is tracked by the
+    /// [`AssetServer`].
+    load_state: Option<(String, String)>,
+    /// A summary of the asset for known asset types, e.g. the dimensions of an image.
+    summary: Option<String>,
+}
+
+fn setup_pane(pane: In<PaneStructure>, theme: Res<Theme>, mut commands: Commands) {
+    commands.entity(pane.content).insert(Node {
+        flex_direction: FlexDirection::Column,
+        flex_grow: 1.0,
+        ..default()
+    });
+
+    spawn_scroll_box(
+        &mut commands,
+        &theme,
+        Overflow::scroll_y(),
+        Some(|commands: &mut Commands, content: Entity| {
+            commands.spawn((
+                AssetInspectorRoot,
+                Node {
+                    flex_direction: FlexDirection::Column,
+                    width: Val::Percent(100.0),
+                    padding: UiRect::all(Val::Px(8.0)),
+                    row_gap: Val::Px(2.0),
+                    ..default()
+                },
+                ChildOf(content),
+            ));
+        }),
+    )
+    .insert(ChildOf(pane.content));
+}
+
+fn update_asset_inspector(
+    roots: Query<Entity, With<AssetInspectorRoot>>,
+    new_roots: Query<(), Added<AssetInspectorRoot>>,
+    expanded: Res<ExpandedAssetTypes>,
+    theme: Res<Theme>,
+    world: &World,
+    mut shown: Local<Vec<AssetTypeInfo>>,
+    mut commands: Commands,
+) {
+    let asset_types = collect_asset_types(world, &expanded);
+    // Avoid rebuilding the list, and resetting hover states, when nothing changed.
+    if asset_types == *shown && new_roots.is_empty() {
+        return;
+    }
+
+    for root in &roots {
+        commands.entity(root).despawn_children();
+        for asset_type in &asset_types {
+            spawn_asset_type(&mut commands, &theme, asset_type, &expanded, root);
+        }
+    }
+    *shown = asset_types;
+}
+
+/// Collect the asset types which have assets, by name.
+fn collect_asset_types(world: &World, expanded: &ExpandedAssetTypes) -> Vec<AssetTypeInfo> {
+    let type_registry = world.resource::<AppTypeRegistry>().read();
+    let asset_server = world.get_resource::<AssetServer>();
+
+    let mut asset_types: Vec<AssetTypeInfo> = type_registry
+        .iter()
+        .filter_map(|registration| {
+            let reflect_asset = registration.data::<ReflectAsset>()?;
+            // The asset type may be registered without its plugin having been added.
+            let assets_resource = world
+                .components()
+                .get_resource_id(reflect_asset.assets_resource_type_id())?;
+            if !world.contains_resource_by_id(assets_resource) {
+                return None;
+            }
+            let count = reflect_asset.len(world);
+            if count == 0 {
+                return None;
+            }
+
+            let type_id = registration.type_id();
+            let mut assets: Vec<AssetInfo> = if expanded.is_expanded(type_id) {
+                reflect_asset
+                    .ids(world)
+                    .map(|id| asset_info(world, asset_server, reflect_asset, id))
+                    .collect()
+            } else {
+                Vec::new()
+            };
+            assets.sort_by_cached_key(AssetInfo::label);
+
+            Some(AssetTypeInfo {
+                type_id,
+                name: registration.type_info().type_path_table().short_path(),
+                count,
+                assets,
+            })
+        })
+        .collect();
+    asset_types.sort_unstable_by_key(|asset_type| asset_type.name);
+    asset_types
+}
+
+fn asset_info(
+    world: &World,
+    asset_server: Option<&AssetServer>,
+    reflect_asset: &ReflectAsset,
+    id: UntypedAssetId,
+) -> AssetInfo {
+    let path = asset_server
+        .and_then(|asset_server| asset_server.get_path(id))
+        .map(|path| path.to_string());
+    let handles = asset_server
+        .and_then(|asset_server| asset_server.get_id_handle_untyped(id))
+        .and_then(|handle| match handle {
+            // Don't count the handle that was just created to look at the others.
+            UntypedHandle::Strong(handle) => Some(Arc::strong_count(&handle) - 1),
+            _ => None,
+        });
+    let load_state = asset_server
+        .and_then(|asset_server| asset_server.get_load_states(id))
+        .map(|(load_state, dependency_state, _)| {
+            (format!("{load_state:?}"), format!("{dependency_state:?}"))
+        });
+    let summary = reflect_asset.get(world, id).and_then(asset_summary);
+
+    AssetInfo {
+        id,
+        path,
+        handles,
+        load_state,
+        summary,
+    }
+}
+
+impl AssetInfo {
+    /// The path of the asset, or its ID if it wasn't loaded from a file.
+    fn label(&self) -> String {
+        self.path.clone().unwrap_or_else(|| self.id.to_string())
+    }
+
+    /// The handle count and load state of the asset.
+    fn details(&self) -> String {
+        let handles = match self.handles {
+            Some(1) => "1 handle".to_string(),
+            Some(handles) => format!("{handles} handles"),
+            // Assets added with `Assets::add` aren't known to the asset server
+            None => "handle count unknown, not from the asset server".to_string(),
+        };
+        match &self.load_state {
+            Some((load_state, dependency_state)) => {
+                format!("{handles} · {load_state} · dependencies {dependency_state}")
+            }
+            None => handles,
+        }
+    }
+}
+
+/// A short summary of an asset of a known type.
+fn asset_summary(asset: &dyn Reflect) -> Option<String> {
+    if let Some(image) = asset.downcast_ref::<Image>() {
+        return Some(format!(
+            "{}×{} {:?}",
+            image.width(),
+            image.height(),
+            image.texture_descriptor.format
+        ));
+    }
+    if let Some(mesh) = asset.downcast_ref::<Mesh>() {
+        let indices = mesh.indices().map_or(0, |indices| indices.len());
+        return Some(format!(
+            "{} vertices, {indices} indices, {:?}",
+            mesh.count_vertices(),
+            mesh.primitive_topology()
+        ));
+    }
+    if let Some(material) = asset.downcast_ref::<StandardMaterial>() {
+        let textured = if material.base_color_texture.is_some() {
+            ", textured"
+        } else {
+            ""
+        };
+        return Some(format!(
+            "base color {}{textured}, metallic {:.2}, roughness {:.2}, {:?}",
+            material.base_color.to_srgba().to_hex(),
+            material.metallic,
+            material.perceptual_roughness,
+            material.alpha_mode
+        ));
+    }
+    None
+}
+
+fn spawn_asset_type(
+    commands: &mut Commands,
+    theme: &Theme,
+    asset_type: &AssetTypeInfo,
+    expanded: &ExpandedAssetTypes,
+    parent: Entity,
+) {
+    let is_expanded = expanded.is_expanded(asset_type.type_id);
+    let text_font = |font_size| TextFont {
+        font: theme.text.font.clone(),
+        font_size,
+        ..default()
+    };
+
+    let header = commands
+        .spawn((
+            AssetTypeHeader(asset_type.type_id),
+            Node {
+                flex_direction: FlexDirection::Row,
+                align_items: AlignItems::Center,
+                column_gap: Val::Px(5.0),
+                padding: UiRect::axes(Val::Px(6.0), Val::Px(4.0)),
+                ..default()
+            },
+            theme.pane.header_background_color,
+            theme.general.border_radius,
+            ChildOf(parent),
+        ))
+        .observe(
+            |trigger: On<Pointer<Click>>,
+             headers: Query<&AssetTypeHeader>,
+             mut expanded: ResMut<ExpandedAssetTypes>| {
+                if let Ok(header) = headers.get(trigger.target()) {
+                    expanded.toggle(header.0);
+                }
+            },
+        )
+        .id();
+
+    commands.spawn((
+        Text::new(if is_expanded { "▼" } else { "▶" }),
+        text_font(10.0),
+        TextColor(theme.text.low_priority),
+        Pickable::IGNORE,
+        ChildOf(header),
+    ));
+    commands.spawn((
+        Text::new(asset_type.name),
+        text_font(12.0),
+        TextColor(theme.text.text_color),
+        Pickable::IGNORE,
+        ChildOf(header),
+    ));
+    commands.spawn((
+        Text::new(asset_type.count.to_string()),
+        text_font(12.0),
+        TextColor(theme.text.low_priority),
+        Pickable::IGNORE,
+        ChildOf(header),
+    ));
+
+    for asset in &asset_type.assets {
+        let row = commands
+            .spawn((
+                Node {
+                    flex_direction: FlexDirection::Column,
+                    padding: UiRect::new(Val::Px(20.0), Val::Px(6.0), Val::Px(2.0), Val::Px(2.0)),
+                    ..default()
+                },
+                ChildOf(parent),
+            ))
+            .id();
+
+        commands.spawn((
+            Text::new(asset.label()),
+            text_font(12.0),
+            TextColor(theme.text.text_color),
+            ChildOf(row),
+        ));
+        commands.spawn((
+            Text::new(asset.details()),
+            text_font(11.0),
+            TextColor(theme.text.low_priority),
+            ChildOf(row),
+        ));
+        if let Some(summary) = &asset.summary {
+            commands.spawn((
+                Text::new(summary),
+                text_font(11.0),
+                TextColor(theme.text.low_priority),
+                ChildOf(row),
+            ));
+        }
+    }
+}
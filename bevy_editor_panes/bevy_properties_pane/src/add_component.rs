@@ -0,0 +1,319 @@
+//! The "Add Component" button at the bottom of the properties pane.
+//!
+//! It opens a popup searching the reflected components which have a default value.
+
+use std::any::TypeId;
+
+use bevy::{
+    ecs::template::template,
+    feathers::cursor::EntityCursor,
+    input_focus::InputFocus,
+    prelude::*,
+    reflect::TypeRegistry,
+    scene2::{Scene, bsn, on},
+    window::SystemCursorIcon,
+};
+use bevy_editor_styles::Theme;
+use bevy_text_editing::{EditableTextLine, TextChanged};
+
+use crate::component_actions::add_component;
+
+/// Height of the component search popup.
+const POPUP_HEIGHT: f32 = 320.0;
+
+/// Maximum number of components listed by the popup, the search narrows it down.
+const MAX_RESULTS: usize = 50;
+
+/// Opens the component search popup, adding components to these entities.
+#[derive(Component, Clone)]
+struct AddComponentButton(Vec<Entity>);
+
+/// The component search popup, adding components to these entities.
+#[derive(Component)]
+struct AddComponentPopup(Vec<Entity>);
+
+/// The list of components matching the search of the popup.
+#[derive(Component)]
+struct AddComponentResults;
+
+/// An option of the popup, adding a component of this type.
+#[derive(Component)]
+struct AddComponentOption(TypeId);
+
+pub(crate) fn add_component_button(entities: Vec<Entity>) -> impl Scene {
+    bsn! {
+        Node {
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            padding: UiRect::all(Val::Px(6.0)),
+            border: UiRect::all(Val::Px(1.0))
+        }
+        // CSS: #2A2A2E - Component background
+        BackgroundColor(Color::srgb(0.165, 0.165, 0.180))
+        // CSS: #414142 - Border color
+        BorderColor::all(Color::srgb(0.255, 0.255, 0.259))
+        BorderRadius::all(Val::Px(5.0))
+        template(move |_| Ok(AddComponentButton(entities.clone())))
+        on(open_popup)
+        [
+            Text("Add Component")
+            TextFont::from_font_size(12.0)
+            // CSS: #DCDCDC - Component name
+            TextColor(Color::srgb(0.863, 0.863, 0.863))
+            Pickable::IGNORE,
+        ]
+    }
+}
+
+fn open_popup(
+    mut trigger: On<Pointer<Click>>,
+    buttons: Query<&AddComponentButton>,
+    type_registry: Res<AppTypeRegistry>,
+    theme: Res<Theme>,
+    mut input_focus: ResMut<InputFocus>,
+    mut commands: Commands,
+) {
+    if trigger.button != PointerButton::Primary {
+        return;
+    }
+    let Ok(AddComponentButton(entities)) = buttons.get(trigger.target()) else {
+        return;
+    };
+    trigger.propagate(false);
+
+    // Fullscreen overlay closing the popup when clicking outside of it
+    let overlay = commands
+        .spawn((
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                ..default()
+            },
+            ZIndex(10),
+        ))
+        .observe(|trigger: On<Pointer<Press>>, mut commands: Commands| {
+            commands.entity(trigger.target()).despawn();
+        })
+        .id();
+
+    // The button sits at the bottom of the pane, so the popup opens above the pointer.
+    let position = trigger.pointer_location.position;
+    let popup = commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                top: Val::Px((position.y - POPUP_HEIGHT).max(0.0)),
+                left: Val::Px(position.x),
+                width: Val::Px(260.0),
+                height: Val::Px(POPUP_HEIGHT),
+                flex_direction: FlexDirection::Column,
+                padding: UiRect::all(Val::Px(3.0)),
+                row_gap: Val::Px(3.0),
+                ..default()
+            },
+            BoxShadow::from(ShadowStyle {
+                blur_radius: Val::Px(3.0),
+                x_offset: Val::ZERO,
+                y_offset: Val::ZERO,
+                color: Color::BLACK.with_alpha(0.8),
+                ..default()
+            }),
+            theme.context_menu.background_color,
+            theme.general.border_radius,
+            AddComponentPopup(entities.clone()),
+            ChildOf(overlay),
+        ))
+        .observe(|mut trigger: On<Pointer<Press>>| {
+            trigger.propagate(false);
+        })
+        .id();
+
+    let search = commands
+        .spawn((
+            EditableTextLine::new(""),
+            Node {
+                height: Val::Px(20.0),
+                padding: UiRect::horizontal(Val::Px(4.0)),
+                ..default()
+            },
+            // CSS: #383838 - Input background
+            BackgroundColor(Color::srgb(0.220, 0.220, 0.220)),
+            BorderRadius::all(Val::Px(3.0)),
+            TextFont::from_font_size(12.0),
+            ChildOf(popup),
+        ))
+        .observe(search_components)
+        .id();
+    input_focus.set(search);
+
+    let results = commands
+        .spawn((
+            Node {
+                flex_direction: FlexDirection::Column,
+                overflow: Overflow::clip_y(),
+                ..default()
+            },
+            AddComponentResults,
+            ChildOf(popup),
+        ))
+        .id();
+    spawn_options(&mut commands, &theme, &type_registry.read(), "", results);
+}
+
+fn search_components(
+    mut trigger: On<TextChanged>,
+    child_of_query: Query<&ChildOf>,
+    children_query: Query<&Children>,
+    results_query: Query<(), With<AddComponentResults>>,
+    type_registry: Res<AppTypeRegistry>,
+    theme: Res<Theme>,
+    mut commands: Commands,
+) {
+    trigger.propagate(false);
+
+    let Ok(&ChildOf(popup)) = child_of_query.get(trigger.target()) else {
+        return;
+    };
+    let Some(results) = children_query
+        .iter_descendants(popup)
+        .find(|&entity| results_query.contains(entity))
+    else {
+        return;
+    };
+
+    commands.entity(results).despawn_related::<Children>();
+    spawn_options(
+        &mut commands,
+        &theme,
+        &type_registry.read(),
+        &trigger.new_text,
+        results,
+    );
+}
+
+/// Spawn an option for every component whose name contains the search, ignoring case.
+fn spawn_options(
+    commands: &mut Commands,
+    theme: &Theme,
+    type_registry: &TypeRegistry,
+    search: &str,
+    parent: Entity,
+) {
+    let search = search.trim().to_lowercase();
+    let mut components: Vec<(&str, TypeId)> = type_registry
+        .iter()
+        .filter(|registration| {
+            registration.data::<ReflectComponent>().is_some()
+                && registration.data::<ReflectDefault>().is_some()
+        })
+        .map(|registration| {
+            let name = registration.type_info().type_path_table().short_path();
+            (name, registration.type_id())
+        })
+        .filter(|(name, _)| name.to_lowercase().contains(&search))
+        .collect();
+    components.sort_unstable_by_key(|&(name, _)| name);
+
+    if components.is_empty() {
+        commands.spawn((
+            Node {
+                padding: UiRect::all(Val::Px(5.0)),
+                ..default()
+            },
+            Text::new("No matching components"),
+            TextFont {
+                font: theme.text.font.clone(),
+                font_size: 12.0,
+                ..default()
+            },
+            TextColor(theme.text.low_priority),
+            ChildOf(parent),
+        ));
+    }
+
+    for (name, type_id) in components.into_iter().take(MAX_RESULTS) {
+        spawn_option(commands, theme, name, type_id).insert(ChildOf(parent));
+    }
+}
+
+fn spawn_option<'a>(
+    commands: &'a mut Commands,
+    theme: &Theme,
+    name: &str,
+    type_id: TypeId,
+) -> EntityCommands<'a> {
+    let option = commands
+        .spawn((
+            Node {
+                padding: UiRect::all(Val::Px(5.0)),
+                flex_shrink: 0.0,
+                ..default()
+            },
+            BackgroundColor(Color::NONE),
+            theme.context_menu.option_border_radius,
+            EntityCursor::System(SystemCursorIcon::Pointer),
+            AddComponentOption(type_id),
+        ))
+        .observe(
+            |trigger: On<Pointer<Over>>,
+             theme: Res<Theme>,
+             mut query: Query<&mut BackgroundColor>| {
+                if let Ok(mut background_color) = query.get_mut(trigger.target()) {
+                    *background_color = theme.context_menu.hover_color;
+                }
+            },
+        )
+        .observe(
+            |trigger: On<Pointer<Out>>, mut query: Query<&mut BackgroundColor>| {
+                if let Ok(mut background_color) = query.get_mut(trigger.target()) {
+                    background_color.0 = Color::NONE;
+                }
+            },
+        )
+        .observe(add_selected_component)
+        .id();
+
+    commands.spawn((
+        Text::new(name),
+        TextFont {
+            font: theme.text.font.clone(),
+            font_size: 12.0,
+            ..default()
+        },
+        Pickable::IGNORE,
+        ChildOf(option),
+    ));
+
+    commands.entity(option)
+}
+
+fn add_selected_component(
+    mut trigger: On<Pointer<Click>>,
+    options: Query<&AddComponentOption>,
+    popups: Query<&AddComponentPopup>,
+    child_of_query: Query<&ChildOf>,
+    mut commands: Commands,
+) {
+    if trigger.button != PointerButton::Primary {
+        return;
+    }
+    trigger.propagate(false);
+
+    let Ok(&AddComponentOption(type_id)) = options.get(trigger.target()) else {
+        return;
+    };
+    let Some(entities) = child_of_query
+        .iter_ancestors(trigger.target())
+        .find_map(|entity| popups.get(entity).ok())
+        .map(|popup| popup.0.clone())
+    else {
+        return;
+    };
+
+    // Close the popup along with its overlay
+    if let Some(overlay) = child_of_query.iter_ancestors(trigger.target()).last() {
+        commands.entity(overlay).despawn();
+    }
+
+    commands.queue(move |world: &mut World| add_component(world, &entities, type_id));
+}
@@ -0,0 +1,144 @@
+//! Actions on whole components of the edited entities, offered by the "⋯" menu of each component.
+//!
+//! Every action is recorded as a single undoable change, however many entities are edited. The
+//! edited entities are ignored by automatic undo for a few frames, so the actions aren't recorded
+//! twice.
+
+use std::{any::TypeId, sync::Arc};
+
+use bevy::prelude::*;
+use bevy_context_menu::{ContextMenu, ContextMenuOption};
+use bevy_undo::{DynamicAddedComponent, DynamicRemovedComponent, EditorChange, OneFrameUndoIgnore};
+
+use crate::fields::{
+    BindingTarget, FieldBinding, FieldEdit, default_value, edit_field, record_changes,
+};
+
+/// Component values copied with "Copy values", to be pasted onto components of the same type.
+#[derive(Resource, Default)]
+pub(crate) struct ComponentClipboard(Option<(TypeId, Box<dyn PartialReflect>)>);
+
+/// The menu of a component, `binding` pointing at the whole component.
+pub(crate) fn component_menu(binding: Option<FieldBinding>) -> ContextMenu {
+    let Some(binding) = binding else {
+        return ContextMenu::new([]);
+    };
+
+    let action = |label: &str, action: fn(&mut World, &FieldBinding)| {
+        let binding = binding.clone();
+        ContextMenuOption::new(label, move |mut commands, _| {
+            let binding = binding.clone();
+            commands.queue(move |world: &mut World| action(world, &binding));
+        })
+    };
+
+    ContextMenu::new([
+        action("Remove component", remove_component),
+        action("Reset to default", reset_component),
+        action("Copy values", copy_component),
+        action("Paste values", paste_component),
+    ])
+}
+
+fn remove_component(world: &mut World, binding: &FieldBinding) {
+    let BindingTarget::Components(entities) = &binding.target else {
+        return;
+    };
+    let type_registry = world.resource::<AppTypeRegistry>().clone();
+    let type_registry = type_registry.read();
+    let Some(reflect_component) = type_registry.get_type_data::<ReflectComponent>(binding.type_id)
+    else {
+        return;
+    };
+
+    let changes: Vec<Arc<dyn EditorChange + Send + Sync>> = entities
+        .iter()
+        .filter_map(|&entity| {
+            let mut entity_mut = world.get_entity_mut(entity).ok()?;
+            let old_value = reflect_component
+                .reflect(entity_mut.as_readonly())?
+                .to_dynamic();
+            reflect_component.remove(&mut entity_mut);
+            entity_mut.insert(OneFrameUndoIgnore::default());
+
+            Some(Arc::new(DynamicRemovedComponent {
+                entity,
+                component_type: binding.type_id,
+                old_value,
+            }) as _)
+        })
+        .collect();
+    record_changes(world, changes);
+}
+
+fn reset_component(world: &mut World, binding: &FieldBinding) {
+    let type_registry = world.resource::<AppTypeRegistry>().clone();
+    let Some(value) = default_value(binding.type_id, &type_registry.read()) else {
+        warn!("Can't reset a component without a registered default value");
+        return;
+    };
+    edit_field(world, binding, FieldEdit::Set(value));
+}
+
+fn copy_component(world: &mut World, binding: &FieldBinding) {
+    let type_registry = world.resource::<AppTypeRegistry>().clone();
+    let type_registry = type_registry.read();
+    let BindingTarget::Components(entities) = &binding.target else {
+        return;
+    };
+    let value = entities.first().and_then(|&entity| {
+        let reflect_component = type_registry.get_type_data::<ReflectComponent>(binding.type_id)?;
+        let component = reflect_component.reflect(world.get_entity(entity).ok()?)?;
+        Some(component.to_dynamic())
+    });
+
+    if let Some(value) = value {
+        world.resource_mut::<ComponentClipboard>().0 = Some((binding.type_id, value));
+    }
+}
+
+fn paste_component(world: &mut World, binding: &FieldBinding) {
+    let value = match &world.resource::<ComponentClipboard>().0 {
+        Some((component, value)) if *component == binding.type_id => value.to_dynamic(),
+        Some((_, value)) => {
+            warn!(
+                "Can't paste the values of a {} onto another component",
+                value.reflect_short_type_path()
+            );
+            return;
+        }
+        None => return,
+    };
+    edit_field(world, binding, FieldEdit::Set(value));
+}
+
+/// Add a component with its default value to the entities which don't have one yet.
+pub(crate) fn add_component(world: &mut World, entities: &[Entity], component: TypeId) {
+    let type_registry = world.resource::<AppTypeRegistry>().clone();
+    let type_registry = type_registry.read();
+    let Some(reflect_component) = type_registry.get_type_data::<ReflectComponent>(component) else {
+        return;
+    };
+    let Some(value) = default_value(component, &type_registry) else {
+        return;
+    };
+
+    let changes: Vec<Arc<dyn EditorChange + Send + Sync>> = entities
+        .iter()
+        .filter_map(|&entity| {
+            let mut entity_mut = world.get_entity_mut(entity).ok()?;
+            if reflect_component.contains(entity_mut.as_readonly()) {
+                return None;
+            }
+            reflect_component.insert(&mut entity_mut, value.as_ref(), &type_registry);
+            entity_mut.insert(OneFrameUndoIgnore::default());
+
+            Some(Arc::new(DynamicAddedComponent {
+                entity,
+                component_type: component,
+                new_value: value.to_dynamic(),
+            }) as _)
+        })
+        .collect();
+    record_changes(world, changes);
+}
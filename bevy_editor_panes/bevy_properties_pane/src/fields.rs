@@ -0,0 +1,993 @@
+//! Editors for the fields of reflected components and resources.
+//!
+//! Every editor widget carries a [`FieldBinding`] pointing at the field it edits, on one or more
+//! entities or on a resource. Edits are written back through [`ReflectComponent`] to every bound
+//! entity, or through [`ReflectResource`], and recorded as a single undoable change, while the
+//! `sync_*` systems keep the widgets up to date with changes made elsewhere, e.g. by gizmos.
+//! Fields whose value differs between the bound entities show [`MIXED`] instead.
+
+use std::{any::TypeId, sync::Arc};
+
+use bevy::{
+    ecs::{system::SystemParam, template::template},
+    platform::collections::{HashMap, HashSet},
+    prelude::*,
+    reflect::{
+        DynamicEnum, DynamicStruct, DynamicTuple, DynamicVariant, Enum, GetPath, List, ReflectMut,
+        TypeInfo, TypeRegistry, VariantInfo,
+    },
+    scene2::{Scene, bsn, on},
+};
+use bevy_context_menu::{ContextMenu, ContextMenuOption, OpenContextMenu};
+use bevy_editor_core::utils::{BoxedScene, IntoBoxedScene};
+use bevy_field_forms::{
+    drag_input::{DragInput, Draggable},
+    input_field::{InputField, ValueChanged},
+    validate_highlight::SimpleBorderHighlight,
+};
+use bevy_text_editing::{EditableTextLine, HasFocus, SetText, TextChanged};
+use bevy_undo::{
+    DynamicComponentChange, DynamicResourceChange, EditorChange, ManyChanges, NewChange,
+    OneFrameUndoIgnore,
+};
+
+// CSS: #2A2A2E - Input background
+const INPUT_BACKGROUND: Color = Color::srgb(0.165, 0.165, 0.180);
+// CSS: #3B82F6 - Checked checkbox
+const CHECKBOX_CHECKED: Color = Color::srgb(0.231, 0.510, 0.965);
+// CSS: #C4C4C4 - Button symbols
+const BUTTON_SYMBOL: Color = Color::srgb(0.769, 0.769, 0.769);
+
+/// What editors show for a field whose value differs between the edited entities.
+pub(crate) const MIXED: &str = "—";
+
+/// The tolerance below which rotation angles shown in degrees are considered unchanged.
+const ANGLE_EPSILON: f32 = 1e-3;
+
+/// Points an editor widget at a field of a reflected component on every edited entity, or at a
+/// field of a reflected resource.
+#[derive(Component, Clone, PartialEq, Eq, Hash)]
+pub(crate) struct FieldBinding {
+    /// What the edited value belongs to.
+    pub target: BindingTarget,
+    /// The type of the component or resource.
+    pub type_id: TypeId,
+    /// Reflect path of the field within the component or resource, e.g. `.translation.x`.
+    pub path: String,
+    /// How the widget shows the value of the field.
+    pub kind: FieldKind,
+}
+
+/// What the value an editor widget is bound to belongs to.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub(crate) enum BindingTarget {
+    /// A component of these entities, the primary selected entity first.
+    Components(Vec<Entity>),
+    /// A resource.
+    Resource,
+}
+
+/// How an editor widget shows the value of the field it is bound to.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) enum FieldKind {
+    /// The widget shows the value of the field as is.
+    Value,
+    /// The field is a [`Quat`], of which the widget shows one XYZ Euler angle in degrees.
+    EulerAngle(usize),
+}
+
+impl FieldBinding {
+    /// Bind to a whole component of some entities.
+    pub fn component(entities: Vec<Entity>, component: TypeId) -> Self {
+        Self {
+            target: BindingTarget::Components(entities),
+            type_id: component,
+            path: String::new(),
+            kind: FieldKind::Value,
+        }
+    }
+
+    /// Bind to a whole resource.
+    pub fn resource(resource: TypeId) -> Self {
+        Self {
+            target: BindingTarget::Resource,
+            type_id: resource,
+            path: String::new(),
+            kind: FieldKind::Value,
+        }
+    }
+
+    /// Bind to a field nested in the currently bound one, `path` being relative to it.
+    pub fn join(&self, path: &str) -> Self {
+        Self {
+            path: format!("{}{path}", self.path),
+            ..self.clone()
+        }
+    }
+
+    /// Show one Euler angle of the currently bound rotation.
+    fn euler_angle(&self, axis: usize) -> Self {
+        Self {
+            kind: FieldKind::EulerAngle(axis),
+            ..self.clone()
+        }
+    }
+
+    /// Name of the bound field in messages, e.g. `Transform.translation.x`.
+    fn field_name(&self, type_registry: &TypeRegistry) -> String {
+        let type_name = type_registry
+            .get_type_info(self.type_id)
+            .map_or("<unknown>", |type_info| {
+                type_info.type_path_table().short_path()
+            });
+        format!("{type_name}{}", self.path)
+    }
+
+    /// Read the current value of the bound field.
+    fn read<'a>(
+        &self,
+        data: &'a BoundData,
+        type_registry: &TypeRegistry,
+    ) -> Option<BoundValue<'a>> {
+        let values: Vec<Option<&dyn PartialReflect>> = match &self.target {
+            BindingTarget::Components(entities) => {
+                let reflect_component =
+                    type_registry.get_type_data::<ReflectComponent>(self.type_id)?;
+                entities
+                    .iter()
+                    .map(|&entity| {
+                        let component =
+                            reflect_component.reflect(data.entities.get(entity).ok()?)?;
+                        component.reflect_path(self.path.as_str()).ok()
+                    })
+                    .collect()
+            }
+            BindingTarget::Resource => {
+                let resource: &dyn PartialReflect = &**data.resources.0.get(&self.type_id)?;
+                vec![resource.reflect_path(self.path.as_str()).ok()]
+            }
+        };
+
+        let mut values = values.into_iter();
+        let first = values.next()??;
+        for value in values {
+            if !same_value(first, value?) {
+                return Some(BoundValue::Mixed);
+            }
+        }
+        Some(BoundValue::Common(first))
+    }
+}
+
+/// The value of a bound field.
+enum BoundValue<'a> {
+    /// All bound entities have the same value.
+    Common(&'a dyn PartialReflect),
+    /// The value differs between the bound entities.
+    Mixed,
+}
+
+/// Returns `true` if two reflected values are equal, comparing their debug representation if the
+/// type doesn't support reflected equality.
+pub(crate) fn same_value(a: &dyn PartialReflect, b: &dyn PartialReflect) -> bool {
+    a.reflect_partial_eq(b)
+        .unwrap_or_else(|| format!("{a:?}") == format!("{b:?}"))
+}
+
+/// Whether a numeric field currently shows [`MIXED`] instead of its value.
+#[derive(Component, Default, Clone)]
+struct Mixed(bool);
+
+/// Read access to the values editor widgets are bound to.
+#[derive(SystemParam)]
+pub(crate) struct BoundData<'w, 's> {
+    /// The entities whose components are being edited, as opposed to the editor widgets and the
+    /// marks of the checkboxes, which [`sync_bool_fields`] writes to.
+    entities: Query<'w, 's, EntityRef<'static>, (Without<FieldBinding>, Without<MixedMark>)>,
+    resources: Res<'w, BoundResources>,
+}
+
+/// Copies of the resources editor widgets are bound to.
+///
+/// Systems can't read resources whose type is only known at runtime next to other parameters, so
+/// [`copy_bound_resources`] copies them before the `sync_*` systems run.
+#[derive(Resource, Default)]
+pub(crate) struct BoundResources(HashMap<TypeId, Box<dyn PartialReflect>>);
+
+pub(crate) fn copy_bound_resources(world: &mut World, fields: &mut QueryState<&FieldBinding>) {
+    let resource_types: HashSet<TypeId> = fields
+        .iter(world)
+        .filter(|binding| binding.target == BindingTarget::Resource)
+        .map(|binding| binding.type_id)
+        .collect();
+
+    let type_registry = world.resource::<AppTypeRegistry>().clone();
+    let type_registry = type_registry.read();
+    let resources = resource_types
+        .into_iter()
+        .filter_map(|type_id| {
+            let reflect_resource = type_registry.get_type_data::<ReflectResource>(type_id)?;
+            let resource = reflect_resource.reflect(&*world).ok()?;
+            // Editors downcast to concrete types such as `Quat`, which dynamic copies are not.
+            let copy = resource
+                .reflect_clone()
+                .map(Reflect::into_partial_reflect)
+                .unwrap_or_else(|_| resource.to_dynamic());
+            Some((type_id, copy))
+        })
+        .collect();
+    world.resource_mut::<BoundResources>().0 = resources;
+}
+
+/// A write to the field a widget is bound to.
+pub(crate) enum FieldEdit {
+    /// Replace the value of the field.
+    Set(Box<dyn PartialReflect>),
+    /// Set one XYZ Euler angle of a [`Quat`] field, in degrees.
+    EulerAngle(usize, f32),
+    /// Change the items of a list or array field.
+    List(ListEdit),
+    /// Switch an enum field to the variant with this name, with default values for its fields.
+    Variant(String),
+}
+
+/// A change to the items of a list or array field.
+#[derive(Clone, Copy)]
+pub(crate) enum ListEdit {
+    /// Append an item with the default value to a list.
+    Push,
+    /// Remove the item at an index from a list.
+    Remove(usize),
+    /// Swap two items of a list or array.
+    Swap(usize, usize),
+}
+
+/// Apply an edit to the bound field, of every bound entity, and record it as a single undoable
+/// change.
+pub(crate) fn edit_field(world: &mut World, binding: &FieldBinding, edit: FieldEdit) {
+    let type_registry = world.resource::<AppTypeRegistry>().clone();
+    let type_registry = type_registry.read();
+
+    let changes: Vec<Arc<dyn EditorChange + Send + Sync>> = match &binding.target {
+        BindingTarget::Components(entities) => {
+            let Some(reflect_component) =
+                type_registry.get_type_data::<ReflectComponent>(binding.type_id)
+            else {
+                return;
+            };
+            entities
+                .iter()
+                .filter_map(|&entity| {
+                    edit_component(
+                        world,
+                        entity,
+                        binding,
+                        &edit,
+                        reflect_component,
+                        &type_registry,
+                    )
+                })
+                .map(|change| Arc::new(change) as _)
+                .collect()
+        }
+        BindingTarget::Resource => {
+            let Some(reflect_resource) =
+                type_registry.get_type_data::<ReflectResource>(binding.type_id)
+            else {
+                return;
+            };
+            edit_resource(world, binding, &edit, reflect_resource, &type_registry)
+                .map(|change| Arc::new(change) as _)
+                .into_iter()
+                .collect()
+        }
+    };
+    record_changes(world, changes);
+}
+
+/// Record changes made to the edited entities together as a single undoable change.
+pub(crate) fn record_changes(
+    world: &mut World,
+    mut changes: Vec<Arc<dyn EditorChange + Send + Sync>>,
+) {
+    let change = match changes.len() {
+        0 => return,
+        1 => changes.remove(0),
+        _ => Arc::new(ManyChanges::new(changes)),
+    };
+    world.write_event(NewChange { change });
+}
+
+/// Apply an edit to a component field of one entity, returning the resulting change.
+fn edit_component(
+    world: &mut World,
+    entity: Entity,
+    binding: &FieldBinding,
+    edit: &FieldEdit,
+    reflect_component: &ReflectComponent,
+    type_registry: &TypeRegistry,
+) -> Option<DynamicComponentChange> {
+    let mut entity_mut = world.get_entity_mut(entity).ok()?;
+    let component = reflect_component.reflect_mut(&mut entity_mut)?;
+
+    let (old_value, new_value) = match edit_value(component, binding, edit, type_registry) {
+        Ok(change) => change?,
+        Err(error) => {
+            warn!(
+                "Failed to edit {} of {entity}: {error}",
+                binding.field_name(type_registry)
+            );
+            return None;
+        }
+    };
+    // The edit is recorded as it is, it shouldn't be recorded again by automatic undo.
+    entity_mut.insert(OneFrameUndoIgnore::default());
+
+    Some(DynamicComponentChange {
+        entity,
+        component_type: binding.type_id,
+        old_value,
+        new_value,
+    })
+}
+
+/// Apply an edit to a resource field, returning the resulting change.
+fn edit_resource(
+    world: &mut World,
+    binding: &FieldBinding,
+    edit: &FieldEdit,
+    reflect_resource: &ReflectResource,
+    type_registry: &TypeRegistry,
+) -> Option<DynamicResourceChange> {
+    let resource = reflect_resource.reflect_mut(world).ok()?;
+
+    match edit_value(resource, binding, edit, type_registry) {
+        Ok(change) => change.map(|(old_value, new_value)| DynamicResourceChange {
+            resource_type: binding.type_id,
+            old_value,
+            new_value,
+        }),
+        Err(error) => {
+            warn!(
+                "Failed to edit {}: {error}",
+                binding.field_name(type_registry)
+            );
+            None
+        }
+    }
+}
+
+/// Apply an edit to a field of a component or resource value, returning the old and new value if
+/// it changed.
+fn edit_value(
+    mut value: Mut<dyn Reflect>,
+    binding: &FieldBinding,
+    edit: &FieldEdit,
+    type_registry: &TypeRegistry,
+) -> Result<Option<(Box<dyn PartialReflect>, Box<dyn PartialReflect>)>, String> {
+    let old_value = value.to_dynamic();
+    // Only trigger change detection once the edit succeeded.
+    let field = value
+        .bypass_change_detection()
+        .reflect_path_mut(binding.path.as_str())
+        .map_err(|error| error.to_string())?;
+    apply_edit(field, edit, type_registry)?;
+    // Picking the current variant of an enum, for example, changes nothing.
+    if value.reflect_partial_eq(old_value.as_ref()) == Some(true) {
+        return Ok(None);
+    }
+
+    value.set_changed();
+    Ok(Some((old_value, value.to_dynamic())))
+}
+
+fn apply_edit(
+    field: &mut dyn PartialReflect,
+    edit: &FieldEdit,
+    type_registry: &TypeRegistry,
+) -> Result<(), String> {
+    match edit {
+        FieldEdit::Set(value) => field.try_apply(value.as_ref()).map_err(|e| e.to_string()),
+        FieldEdit::EulerAngle(axis, degrees) => match field.try_downcast_mut::<Quat>() {
+            Some(rotation) => {
+                let mut angles: [f32; 3] = rotation.to_euler(EulerRot::XYZ).into();
+                angles[*axis] = degrees.to_radians();
+                *rotation = Quat::from_euler(EulerRot::XYZ, angles[0], angles[1], angles[2]);
+                Ok(())
+            }
+            None => Err("field is not a Quat".to_string()),
+        },
+        FieldEdit::List(edit) => edit_list(field, *edit, type_registry),
+        FieldEdit::Variant(name) => {
+            let variant = default_variant(field, name, type_registry)?;
+            field.try_apply(&variant).map_err(|e| e.to_string())
+        }
+    }
+}
+
+fn edit_list(
+    field: &mut dyn PartialReflect,
+    edit: ListEdit,
+    type_registry: &TypeRegistry,
+) -> Result<(), String> {
+    fn as_list(field: &mut dyn PartialReflect) -> Result<&mut dyn List, String> {
+        match field.reflect_mut() {
+            ReflectMut::List(list) => Ok(list),
+            _ => Err("field is not a list".to_string()),
+        }
+    }
+
+    match edit {
+        ListEdit::Push => {
+            let list = as_list(field)?;
+            let item_type = list
+                .get_represented_list_info()
+                .ok_or("list type is not registered")?
+                .item_ty();
+            let item = default_value(item_type.id(), type_registry)
+                .ok_or_else(|| format!("{} has no registered default", item_type.path()))?;
+            list.push(item);
+        }
+        ListEdit::Remove(index) => {
+            let list = as_list(field)?;
+            if index >= list.len() {
+                return Err(format!("no item at index {index}"));
+            }
+            list.remove(index);
+        }
+        ListEdit::Swap(a, b) => {
+            let item = |index: usize| format!("[{index}]");
+            let first = field
+                .reflect_path(item(a).as_str())
+                .map_err(|e| e.to_string())?
+                .to_dynamic();
+            let second = field
+                .reflect_path(item(b).as_str())
+                .map_err(|e| e.to_string())?
+                .to_dynamic();
+            field
+                .reflect_path_mut(item(a).as_str())
+                .map_err(|e| e.to_string())?
+                .try_apply(second.as_ref())
+                .map_err(|e| e.to_string())?;
+            field
+                .reflect_path_mut(item(b).as_str())
+                .map_err(|e| e.to_string())?
+                .try_apply(first.as_ref())
+                .map_err(|e| e.to_string())?;
+        }
+    }
+    Ok(())
+}
+
+/// Returns the registered default value of a type.
+pub(crate) fn default_value(
+    type_id: TypeId,
+    type_registry: &TypeRegistry,
+) -> Option<Box<dyn PartialReflect>> {
+    let reflect_default = type_registry.get_type_data::<ReflectDefault>(type_id)?;
+    Some(reflect_default.default().into_partial_reflect())
+}
+
+/// Build the variant of an enum field with this name, with default values for its fields.
+fn default_variant(
+    field: &dyn PartialReflect,
+    name: &str,
+    type_registry: &TypeRegistry,
+) -> Result<DynamicEnum, String> {
+    let Some(TypeInfo::Enum(enum_info)) = field.get_represented_type_info() else {
+        return Err("field is not an enum".to_string());
+    };
+    let variant_info = enum_info
+        .variant(name)
+        .ok_or_else(|| format!("no variant named {name}"))?;
+    let default = |type_id: TypeId, type_path: &str| {
+        default_value(type_id, type_registry)
+            .ok_or_else(|| format!("{type_path} has no registered default"))
+    };
+
+    let variant = match variant_info {
+        VariantInfo::Unit(_) => DynamicVariant::Unit,
+        VariantInfo::Tuple(info) => {
+            let mut tuple = DynamicTuple::default();
+            for field in info.iter() {
+                tuple.insert_boxed(default(field.type_id(), field.type_path())?);
+            }
+            DynamicVariant::Tuple(tuple)
+        }
+        VariantInfo::Struct(info) => {
+            let mut fields = DynamicStruct::default();
+            for field in info.iter() {
+                fields.insert_boxed(field.name(), default(field.type_id(), field.type_path())?);
+            }
+            DynamicVariant::Struct(fields)
+        }
+    };
+    Ok(DynamicEnum::new(name.to_string(), variant))
+}
+
+/// Returns an editor for the field a binding points at, if there is one for the type of its value.
+pub(crate) fn field_editor(
+    binding: FieldBinding,
+    value: &dyn PartialReflect,
+) -> Option<BoxedScene> {
+    macro_rules! numeric_editors {
+        ($($t:ty),*) => {
+            $(
+                if let Some(value) = value.try_downcast_ref::<$t>() {
+                    return Some(numeric_field::<$t>(binding, *value).boxed_scene());
+                }
+            )*
+        };
+    }
+    numeric_editors!(f32, f64, i8, i16, i32, i64, i128, u8, u16, u32, u64, u128);
+
+    if let Some(value) = value.try_downcast_ref::<bool>() {
+        return Some(bool_field(binding, *value).boxed_scene());
+    }
+    if let Some(value) = value.try_downcast_ref::<String>() {
+        return Some(string_field(binding, value.clone()).boxed_scene());
+    }
+    if let Some(value) = value.try_downcast_ref::<Vec2>() {
+        return Some(vector_field(&binding, &["x", "y"], &value.to_array()).boxed_scene());
+    }
+    if let Some(value) = value.try_downcast_ref::<Vec3>() {
+        return Some(vector_field(&binding, &["x", "y", "z"], &value.to_array()).boxed_scene());
+    }
+    if let Some(value) = value.try_downcast_ref::<Vec4>() {
+        return Some(
+            vector_field(&binding, &["x", "y", "z", "w"], &value.to_array()).boxed_scene(),
+        );
+    }
+    if let Some(value) = value.try_downcast_ref::<Quat>() {
+        let angles: [f32; 3] = value.to_euler(EulerRot::XYZ).into();
+        let fields = ["x", "y", "z"]
+            .into_iter()
+            .enumerate()
+            .map(|(axis, label)| {
+                labeled_field(
+                    label,
+                    numeric_field(binding.euler_angle(axis), angles[axis].to_degrees()),
+                )
+            })
+            .collect::<Vec<_>>();
+        return Some(field_group(fields).boxed_scene());
+    }
+
+    None
+}
+
+/// A group of fields, laid out in a row.
+fn field_group(fields: Vec<impl Scene>) -> impl Scene {
+    bsn! {
+        Node {
+            flex_direction: FlexDirection::Row,
+            flex_grow: 1.0,
+            column_gap: Val::Px(4.0)
+        } [ {fields} ]
+    }
+}
+
+/// A field with a short label in front of it, such as the axis of a vector component.
+fn labeled_field(label: &'static str, field: impl Scene) -> impl Scene {
+    bsn! {
+        Node {
+            flex_direction: FlexDirection::Row,
+            flex_grow: 1.0,
+            flex_basis: Val::Px(0.0),
+            align_items: AlignItems::Center,
+            column_gap: Val::Px(3.0)
+        } [
+            Text(label)
+            TextFont::from_font_size(11.0)
+            // CSS: #838385 - Axis labels
+            TextColor(Color::srgb(0.514, 0.514, 0.522)),
+
+            ({field}),
+        ]
+    }
+}
+
+fn vector_field(binding: &FieldBinding, axes: &[&'static str], values: &[f32]) -> impl Scene {
+    let fields = axes
+        .iter()
+        .zip(values)
+        .map(|(axis, value)| {
+            labeled_field(
+                *axis,
+                numeric_field(binding.join(&format!(".{axis}")), *value),
+            )
+        })
+        .collect::<Vec<_>>();
+    field_group(fields)
+}
+
+fn numeric_field<T: Draggable + Reflect>(binding: FieldBinding, value: T) -> impl Scene {
+    bsn! {
+        Node {
+            flex_grow: 1.0,
+            min_width: Val::Px(40.0),
+            height: Val::Px(20.0),
+            padding: UiRect::horizontal(Val::Px(4.0)),
+            border: UiRect::all(Val::Px(1.0))
+        }
+        BackgroundColor(INPUT_BACKGROUND)
+        BorderRadius::all(Val::Px(3.0))
+        TextFont::from_font_size(12.0)
+        template(move |_| Ok(binding.clone()))
+        template(move |_| Ok(InputField::new(value)))
+        template(|_| Ok(DragInput::<T>::default()))
+        template(|_| Ok(SimpleBorderHighlight::default()))
+        Mixed
+        on(on_numeric_value_changed::<T>)
+    }
+}
+
+fn on_numeric_value_changed<T: Draggable + Reflect>(
+    trigger: On<ValueChanged<T>>,
+    fields: Query<&FieldBinding>,
+    mut commands: Commands,
+) {
+    let Ok(binding) = fields.get(trigger.target()) else {
+        return;
+    };
+
+    let edit = match binding.kind {
+        FieldKind::Value => FieldEdit::Set(Box::new(trigger.0)),
+        FieldKind::EulerAngle(axis) => FieldEdit::EulerAngle(axis, trigger.0.into_f32()),
+    };
+    let binding = binding.clone();
+    commands.queue(move |world: &mut World| edit_field(world, &binding, edit));
+}
+
+/// Marks a checkbox editing a `bool` field.
+#[derive(Component, Default, Clone)]
+struct BoolField;
+
+/// Marks the text of a checkbox, showing [`MIXED`] if the value of its field is mixed.
+#[derive(Component, Default, Clone)]
+struct MixedMark;
+
+fn checkbox_color(checked: bool) -> BackgroundColor {
+    BackgroundColor(if checked {
+        CHECKBOX_CHECKED
+    } else {
+        INPUT_BACKGROUND
+    })
+}
+
+fn bool_field(binding: FieldBinding, value: bool) -> impl Scene {
+    bsn! {
+        BoolField
+        Node {
+            width: Val::Px(16.0),
+            height: Val::Px(16.0),
+            border: UiRect::all(Val::Px(1.0)),
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center
+        }
+        // CSS: #7F7F7F - Checkbox border
+        BorderColor::all(Color::srgb(0.5, 0.5, 0.5))
+        BorderRadius::all(Val::Px(3.0))
+        template(move |_| Ok(binding.clone()))
+        template(move |_| Ok(checkbox_color(value)))
+        on(|mut trigger: On<Pointer<Click>>,
+            fields: Query<&FieldBinding>,
+            data: BoundData,
+            type_registry: Res<AppTypeRegistry>,
+            mut commands: Commands| {
+            if trigger.button != PointerButton::Primary {
+                return;
+            }
+            trigger.propagate(false);
+
+            let Ok(binding) = fields.get(trigger.target()) else {
+                return;
+            };
+            // Clicking a mixed checkbox checks it for every entity.
+            let checked = match binding.read(&data, &type_registry.read()) {
+                Some(BoundValue::Common(value)) => value.try_downcast_ref::<bool>() == Some(&true),
+                _ => false,
+            };
+            let binding = binding.clone();
+            let edit = FieldEdit::Set(Box::new(!checked));
+            commands.queue(move |world: &mut World| edit_field(world, &binding, edit));
+        })
+        [
+            MixedMark
+            Text("")
+            TextFont::from_font_size(10.0)
+            TextColor(BUTTON_SYMBOL),
+        ]
+    }
+}
+
+/// Marks a text line editing a `String` field.
+#[derive(Component, Default, Clone)]
+struct StringField;
+
+fn string_field(binding: FieldBinding, value: String) -> impl Scene {
+    bsn! {
+        StringField
+        Node {
+            flex_grow: 1.0,
+            height: Val::Px(20.0),
+            padding: UiRect::horizontal(Val::Px(4.0))
+        }
+        BackgroundColor(INPUT_BACKGROUND)
+        BorderRadius::all(Val::Px(3.0))
+        TextFont::from_font_size(12.0)
+        template(move |_| Ok(binding.clone()))
+        template(move |_| Ok(EditableTextLine::new(value.clone())))
+        on(|mut trigger: On<TextChanged>, fields: Query<&FieldBinding>, mut commands: Commands| {
+            let Ok(binding) = fields.get(trigger.target()) else {
+                return;
+            };
+            trigger.propagate(false);
+
+            let binding = binding.clone();
+            let edit = FieldEdit::Set(Box::new(trigger.new_text.clone()));
+            commands.queue(move |world: &mut World| edit_field(world, &binding, edit));
+        })
+    }
+}
+
+/// A dropdown picking the variant of an enum field, showing [`MIXED`] if the variant differs
+/// between the edited entities.
+pub(crate) fn variant_field(binding: FieldBinding, value: &dyn Enum, mixed: bool) -> impl Scene {
+    let variant = if mixed {
+        MIXED.to_string()
+    } else {
+        value.variant_name().to_string()
+    };
+    let variants: Vec<String> = value
+        .get_represented_enum_info()
+        .map(|info| {
+            info.variant_names()
+                .iter()
+                .map(ToString::to_string)
+                .collect()
+        })
+        .unwrap_or_default();
+
+    bsn! {
+        Node {
+            flex_grow: 1.0,
+            height: Val::Px(20.0),
+            padding: UiRect::horizontal(Val::Px(4.0)),
+            justify_content: JustifyContent::SpaceBetween,
+            align_items: AlignItems::Center
+        }
+        BackgroundColor(INPUT_BACKGROUND)
+        BorderRadius::all(Val::Px(3.0))
+        template(move |_| Ok(variant_menu(&binding, &variants)))
+        on(open_menu)
+        [
+            Text({variant.clone()})
+            TextFont::from_font_size(12.0)
+            // CSS: #C2C2C2 - Field values
+            TextColor(Color::srgb(0.761, 0.761, 0.761)),
+
+            Text("▾")
+            TextFont::from_font_size(12.0)
+            TextColor(BUTTON_SYMBOL),
+        ]
+    }
+}
+
+/// Opens the [`ContextMenu`] of the clicked entity under the pointer.
+pub(crate) fn open_menu(mut trigger: On<Pointer<Click>>, mut commands: Commands) {
+    if trigger.button != PointerButton::Primary {
+        return;
+    }
+    trigger.propagate(false);
+
+    let position = trigger.pointer_location.position;
+    commands.trigger_targets(OpenContextMenu { position }, trigger.target());
+}
+
+fn variant_menu(binding: &FieldBinding, variants: &[String]) -> ContextMenu {
+    ContextMenu::new(variants.iter().map(|variant| {
+        let binding = binding.clone();
+        let variant = variant.clone();
+        ContextMenuOption::new(variant.clone(), move |mut commands, _| {
+            let binding = binding.clone();
+            let edit = FieldEdit::Variant(variant.clone());
+            commands.queue(move |world: &mut World| edit_field(world, &binding, edit));
+        })
+    }))
+}
+
+/// A change to the items of the list or array a button is bound to.
+#[derive(Component, Clone)]
+struct ListButton {
+    binding: FieldBinding,
+    edit: ListEdit,
+}
+
+/// A small button adding, removing or moving items of a list or array field.
+pub(crate) fn list_button(binding: FieldBinding, edit: ListEdit) -> impl Scene {
+    let symbol = match edit {
+        ListEdit::Push => "+",
+        ListEdit::Remove(_) => "✕",
+        ListEdit::Swap(a, b) if b < a => "▲",
+        ListEdit::Swap(..) => "▼",
+    };
+
+    bsn! {
+        Node {
+            width: Val::Px(18.0),
+            height: Val::Px(18.0),
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center
+        }
+        BackgroundColor(INPUT_BACKGROUND)
+        BorderRadius::all(Val::Px(3.0))
+        template(move |_| Ok(ListButton { binding: binding.clone(), edit }))
+        on(|mut trigger: On<Pointer<Click>>, buttons: Query<&ListButton>, mut commands: Commands| {
+            if trigger.button != PointerButton::Primary {
+                return;
+            }
+            trigger.propagate(false);
+
+            let Ok(ListButton { binding, edit }) = buttons.get(trigger.target()) else {
+                return;
+            };
+            let (binding, edit) = (binding.clone(), FieldEdit::List(*edit));
+            commands.queue(move |world: &mut World| edit_field(world, &binding, edit));
+        })
+        [
+            Text(symbol)
+            TextFont::from_font_size(11.0)
+            TextColor(BUTTON_SYMBOL),
+        ]
+    }
+}
+
+/// Update numeric fields whose value was changed outside of the properties pane.
+pub(crate) fn sync_numeric_fields<T: Draggable + FromReflect>(
+    mut fields: Query<(
+        Entity,
+        &FieldBinding,
+        &mut InputField<T>,
+        &mut Mixed,
+        &HasFocus,
+    )>,
+    data: BoundData,
+    type_registry: Res<AppTypeRegistry>,
+    mut commands: Commands,
+) {
+    let type_registry = type_registry.read();
+    for (entity, binding, mut field, mut mixed, has_focus) in &mut fields {
+        // Don't fight the user while they are typing.
+        if has_focus.0 {
+            continue;
+        }
+        let value = match binding.read(&data, &type_registry) {
+            None => continue,
+            Some(BoundValue::Mixed) => {
+                if !mixed.0 {
+                    mixed.0 = true;
+                    commands.trigger_targets(SetText(MIXED.to_string()), entity);
+                }
+                continue;
+            }
+            Some(BoundValue::Common(value)) => value,
+        };
+
+        let value = match binding.kind {
+            FieldKind::Value => T::from_reflect(value),
+            FieldKind::EulerAngle(axis) => value.try_downcast_ref::<Quat>().map(|rotation| {
+                let angles: [f32; 3] = rotation.to_euler(EulerRot::XYZ).into();
+                T::from_f32(angles[axis].to_degrees())
+            }),
+        };
+        let Some(value) = value else {
+            continue;
+        };
+
+        if mixed.0 {
+            // The text has to be replaced even if the value of the field didn't change.
+            mixed.0 = false;
+            commands.trigger_targets(SetText(value.to_string()), entity);
+            field.value = value;
+            continue;
+        }
+        let changed = match binding.kind {
+            FieldKind::Value => field.value != value,
+            // Converting back and forth between angles and quaternions is lossy.
+            FieldKind::EulerAngle(_) => {
+                (field.value.into_f32() - value.into_f32()).abs() > ANGLE_EPSILON
+            }
+        };
+        if changed {
+            field.value = value;
+        }
+    }
+}
+
+/// Update checkboxes whose value was changed outside of the properties pane.
+pub(crate) fn sync_bool_fields(
+    mut fields: Query<(&FieldBinding, &mut BackgroundColor, &Children), With<BoolField>>,
+    mut marks: Query<&mut Text, With<MixedMark>>,
+    data: BoundData,
+    type_registry: Res<AppTypeRegistry>,
+) {
+    let type_registry = type_registry.read();
+    for (binding, mut background_color, children) in &mut fields {
+        let (checked, mark) = match binding.read(&data, &type_registry) {
+            Some(BoundValue::Common(value)) => match value.try_downcast_ref::<bool>() {
+                Some(&checked) => (checked, ""),
+                None => continue,
+            },
+            Some(BoundValue::Mixed) => (false, MIXED),
+            None => continue,
+        };
+
+        let color = checkbox_color(checked);
+        if *background_color != color {
+            *background_color = color;
+        }
+        for child in children.iter() {
+            if let Ok(mut text) = marks.get_mut(child)
+                && text.0 != mark
+            {
+                text.0 = mark.to_string();
+            }
+        }
+    }
+}
+
+/// Update string fields whose value was changed outside of the properties pane.
+pub(crate) fn sync_string_fields(
+    fields: Query<(Entity, &FieldBinding, &EditableTextLine, &HasFocus), With<StringField>>,
+    data: BoundData,
+    type_registry: Res<AppTypeRegistry>,
+    mut commands: Commands,
+) {
+    let type_registry = type_registry.read();
+    for (entity, binding, text_line, has_focus) in &fields {
+        if has_focus.0 {
+            continue;
+        }
+        let text = match binding.read(&data, &type_registry) {
+            Some(BoundValue::Common(value)) => match value.try_downcast_ref::<String>() {
+                Some(value) => value.as_str(),
+                None => continue,
+            },
+            Some(BoundValue::Mixed) => MIXED,
+            None => continue,
+        };
+
+        if text_line.text != text {
+            commands.trigger_targets(SetText(text.to_string()), entity);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sync_systems_have_no_conflicts() {
+        let mut world = World::new();
+        world.init_resource::<AppTypeRegistry>();
+        world.init_resource::<BoundResources>();
+
+        let mut schedule = Schedule::default();
+        schedule.add_systems(
+            (
+                copy_bound_resources,
+                (
+                    sync_numeric_fields::<f32>,
+                    sync_bool_fields,
+                    sync_string_fields,
+                ),
+            )
+                .chain(),
+        );
+        // Systems with conflicting queries panic when they are initialized.
+        schedule.run(&mut world);
+    }
+}
@@ -0,0 +1,541 @@
+//! Recursive layout of reflected values.
+//!
+//! Leaf values, such as numbers, strings, vectors and fieldless enums, get an editor from
+//! [`fields`](crate::fields). Compound values get one row per field, item or entry, and compound
+//! values nested in them are shown in sections which can be collapsed by clicking their header.
+//! Entries of maps and sets can't be addressed by a reflect path, so they are only displayed.
+//!
+//! Resources are laid out the same way as the components of a single entity.
+//!
+//! When several entities are inspected, their values are laid out side by side: values whose
+//! variant or length differs between the entities are shown as [`MIXED`] instead of their fields
+//! or items.
+
+use std::hash::{DefaultHasher, Hash, Hasher};
+
+use bevy::{
+    ecs::template::template,
+    platform::collections::HashSet,
+    prelude::*,
+    reflect::{ReflectRef, VariantField},
+    scene2::{Scene, bsn, on},
+};
+use bevy_editor_core::{
+    prelude::*,
+    utils::{BoxedScene, IntoBoxedScene},
+};
+
+use crate::fields::{
+    FieldBinding, ListEdit, MIXED, field_editor, list_button, same_value, variant_field,
+};
+
+/// The sections of the properties pane which were collapsed, by the binding of their value.
+#[derive(Resource, Default)]
+pub(crate) struct CollapsedSections(HashSet<FieldBinding>);
+
+impl CollapsedSections {
+    /// Returns `true` if the section of this value is collapsed.
+    pub fn contains(&self, binding: &FieldBinding) -> bool {
+        self.0.contains(binding)
+    }
+
+    /// Collapse or expand the section of this value, returning `true` if it is now collapsed.
+    fn toggle(&mut self, binding: &FieldBinding) -> bool {
+        if self.0.remove(binding) {
+            false
+        } else {
+            self.0.insert(binding.clone());
+            true
+        }
+    }
+}
+
+/// Marks the header of a collapsible section, which toggles the section when clicked.
+///
+/// Sections of values which can't be bound to, such as components without a Rust type, can't be
+/// collapsed.
+#[derive(Component, Clone)]
+pub(crate) struct SectionHeader(pub Option<FieldBinding>);
+
+/// Marks the chevron showing whether a section is collapsed.
+#[derive(Component, Default, Clone)]
+pub(crate) struct SectionChevron;
+
+/// The chevron of a section.
+pub(crate) fn chevron(collapsed: bool) -> &'static str {
+    if collapsed { "▶" } else { "▼" }
+}
+
+/// How the body of a section is displayed.
+pub(crate) fn section_display(collapsed: bool) -> Display {
+    if collapsed {
+        Display::None
+    } else {
+        Display::Flex
+    }
+}
+
+/// Collapse or expand a section, hiding or showing the siblings of its header.
+pub(crate) fn toggle_section(
+    mut trigger: On<Pointer<Click>>,
+    headers: Query<(&SectionHeader, &ChildOf)>,
+    children: Query<&Children>,
+    mut nodes: Query<&mut Node>,
+    mut chevrons: Query<&mut Text, With<SectionChevron>>,
+    mut collapsed: ResMut<CollapsedSections>,
+) {
+    if trigger.button != PointerButton::Primary {
+        return;
+    }
+    let header = trigger.target();
+    let Ok((SectionHeader(Some(binding)), child_of)) = headers.get(header) else {
+        return;
+    };
+    trigger.propagate(false);
+
+    let is_collapsed = collapsed.toggle(binding);
+    for sibling in children
+        .get(child_of.parent())
+        .into_iter()
+        .flat_map(|children| children.iter())
+        .filter(|sibling| *sibling != header)
+    {
+        if let Ok(mut node) = nodes.get_mut(sibling) {
+            node.display = section_display(is_collapsed);
+        }
+    }
+    for descendant in children.iter_descendants(header) {
+        if let Ok(mut text) = chevrons.get_mut(descendant) {
+            text.0 = chevron(is_collapsed).to_string();
+        }
+    }
+}
+
+/// A hash of what the layout of the properties pane depends on: the inspected entities, their
+/// components, the variants of their enums and the lengths of their collections.
+///
+/// The pane is rebuilt whenever it changes, e.g. after an item was added to a list, including
+/// when that is undone.
+#[derive(Resource, Default, PartialEq)]
+pub(crate) struct InspectedStructure(u64);
+
+pub(crate) fn update_inspected_structure(world: &mut World) {
+    let mut hasher = DefaultHasher::new();
+
+    let type_registry = world.resource::<AppTypeRegistry>().read();
+    for entity in world.resource::<EditorSelection>().iter() {
+        entity.hash(&mut hasher);
+        let Ok(components) = world.inspect_entity(entity) else {
+            continue;
+        };
+        for component_info in components {
+            component_info.id().hash(&mut hasher);
+
+            let reflect = component_info
+                .type_id()
+                .and_then(|type_id| type_registry.get_type_data::<ReflectComponent>(type_id))
+                .and_then(|reflect_component| reflect_component.reflect(world.entity(entity)));
+            if let Some(reflect) = reflect {
+                hash_structure(reflect.as_partial_reflect(), &mut hasher);
+            }
+        }
+    }
+    drop(type_registry);
+
+    let structure = InspectedStructure(hasher.finish());
+    world
+        .resource_mut::<InspectedStructure>()
+        .set_if_neq(structure);
+}
+
+pub(crate) fn hash_structure(value: &dyn PartialReflect, hasher: &mut impl Hasher) {
+    match value.reflect_ref() {
+        ReflectRef::Struct(value) => {
+            for field in value.iter_fields() {
+                hash_structure(field, hasher);
+            }
+        }
+        ReflectRef::TupleStruct(value) => {
+            for field in value.iter_fields() {
+                hash_structure(field, hasher);
+            }
+        }
+        ReflectRef::Tuple(value) => {
+            for field in value.iter_fields() {
+                hash_structure(field, hasher);
+            }
+        }
+        ReflectRef::List(value) => {
+            value.len().hash(hasher);
+            for item in value.iter() {
+                hash_structure(item, hasher);
+            }
+        }
+        ReflectRef::Array(value) => {
+            for item in value.iter() {
+                hash_structure(item, hasher);
+            }
+        }
+        ReflectRef::Map(value) => value.len().hash(hasher),
+        ReflectRef::Set(value) => value.len().hash(hasher),
+        ReflectRef::Enum(value) => {
+            value.variant_name().hash(hasher);
+            for field in value.iter_fields() {
+                hash_structure(field.value(), hasher);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// The fields of a component, given its value on every inspected entity, or of a resource.
+pub(crate) fn value_inspector(
+    binding: FieldBinding,
+    values: &[&dyn PartialReflect],
+    collapsed: &CollapsedSections,
+) -> impl Scene {
+    let display = section_display(collapsed.contains(&binding));
+    let rows = value_rows(&binding, values, collapsed);
+
+    bsn! {
+        template(move |_| Ok(Node {
+            display,
+            flex_direction: FlexDirection::Column,
+            padding: UiRect::all(Val::Px(7.0)),
+            row_gap: Val::Px(4.0),
+            ..default()
+        }))
+        [ {rows} ]
+    }
+}
+
+/// One row per field, item or entry of a value, laid out after the value of the primary entity.
+fn value_rows(
+    binding: &FieldBinding,
+    values: &[&dyn PartialReflect],
+    collapsed: &CollapsedSections,
+) -> Vec<BoxedScene> {
+    let no_actions = Vec::new;
+    let row = |label: String, access: String, actions: Vec<BoxedScene>| {
+        child_row(label, &access, binding, values, actions, collapsed)
+    };
+
+    match values[0].reflect_ref() {
+        ReflectRef::Struct(value) => (0..value.field_len())
+            .map(|i| {
+                let name = value.name_at(i).unwrap_or_default();
+                row(name.to_string(), format!(".{name}"), no_actions())
+            })
+            .collect(),
+        ReflectRef::TupleStruct(value) => (0..value.field_len())
+            .map(|i| row(i.to_string(), format!(".{i}"), no_actions()))
+            .collect(),
+        ReflectRef::Tuple(value) => (0..value.field_len())
+            .map(|i| row(i.to_string(), format!(".{i}"), no_actions()))
+            .collect(),
+        ReflectRef::Enum(value) => {
+            let mixed = variants_differ(values);
+            let variant = variant_field(binding.clone(), value, mixed).boxed_scene();
+            let mut rows = vec![labeled_row("variant".to_string(), variant, no_actions())];
+            if !mixed {
+                rows.extend(
+                    value
+                        .iter_fields()
+                        .enumerate()
+                        .map(|(i, field)| match field {
+                            VariantField::Struct(name, _) => {
+                                row(name.to_string(), format!(".{name}"), no_actions())
+                            }
+                            VariantField::Tuple(_) => {
+                                row(i.to_string(), format!(".{i}"), no_actions())
+                            }
+                        }),
+                );
+            }
+            rows
+        }
+        ReflectRef::List(value) => {
+            let len = value.len();
+            if values.iter().any(|value| list_len(*value) != Some(len)) {
+                return vec![labeled_row("items".to_string(), mixed_text(), no_actions())];
+            }
+
+            let mut rows = (0..len)
+                .map(|i| {
+                    let mut actions = item_actions(binding, i, len);
+                    actions.push(list_button(binding.clone(), ListEdit::Remove(i)).boxed_scene());
+                    row(format!("[{i}]"), format!("[{i}]"), actions)
+                })
+                .collect::<Vec<_>>();
+            let add = vec![list_button(binding.clone(), ListEdit::Push).boxed_scene()];
+            rows.push(labeled_row(
+                format!("{len} items"),
+                bsn! {}.boxed_scene(),
+                add,
+            ));
+            rows
+        }
+        ReflectRef::Array(value) => {
+            let len = value.len();
+            (0..len)
+                .map(|i| {
+                    row(
+                        format!("[{i}]"),
+                        format!("[{i}]"),
+                        item_actions(binding, i, len),
+                    )
+                })
+                .collect()
+        }
+        ReflectRef::Map(_) | ReflectRef::Set(_) if !all_same(values) => {
+            vec![labeled_row(
+                "entries".to_string(),
+                mixed_text(),
+                no_actions(),
+            )]
+        }
+        ReflectRef::Map(value) => value
+            .iter()
+            .map(|(key, value)| {
+                labeled_row(
+                    format!("{key:?}"),
+                    value_text(&[value]).boxed_scene(),
+                    no_actions(),
+                )
+            })
+            .collect(),
+        ReflectRef::Set(value) => value
+            .iter()
+            .map(|value| {
+                labeled_row(
+                    String::new(),
+                    value_text(&[value]).boxed_scene(),
+                    no_actions(),
+                )
+            })
+            .collect(),
+        _ => {
+            let editor = field_editor(binding.clone(), values[0])
+                .unwrap_or_else(|| value_text(values).boxed_scene());
+            vec![labeled_row("value".to_string(), editor, no_actions())]
+        }
+    }
+}
+
+/// A row for the field or item at `access` relative to the bound value.
+fn child_row(
+    label: String,
+    access: &str,
+    binding: &FieldBinding,
+    values: &[&dyn PartialReflect],
+    actions: Vec<BoxedScene>,
+    collapsed: &CollapsedSections,
+) -> BoxedScene {
+    let child_values: Option<Vec<_>> = values
+        .iter()
+        .map(|value| value.reflect_path(access).ok())
+        .collect();
+
+    match child_values {
+        Some(child_values) => {
+            let binding = binding.join(access);
+            field_row(label, binding, &child_values, actions, collapsed)
+        }
+        None => labeled_row(label, mixed_text(), actions),
+    }
+}
+
+/// Buttons moving the item at `index` of a list or array up and down.
+fn item_actions(binding: &FieldBinding, index: usize, len: usize) -> Vec<BoxedScene> {
+    let mut actions = Vec::new();
+    if index > 0 {
+        actions.push(list_button(binding.clone(), ListEdit::Swap(index, index - 1)).boxed_scene());
+    }
+    if index + 1 < len {
+        actions.push(list_button(binding.clone(), ListEdit::Swap(index, index + 1)).boxed_scene());
+    }
+    actions
+}
+
+/// A row for a field or item: an editor for leaf values, a collapsible section otherwise.
+fn field_row(
+    label: String,
+    binding: FieldBinding,
+    values: &[&dyn PartialReflect],
+    actions: Vec<BoxedScene>,
+    collapsed: &CollapsedSections,
+) -> BoxedScene {
+    if let Some(editor) = field_editor(binding.clone(), values[0]) {
+        return labeled_row(label, editor, actions);
+    }
+
+    match values[0].reflect_ref() {
+        ReflectRef::Enum(value) if value.field_len() == 0 || variants_differ(values) => {
+            let mixed = variants_differ(values);
+            labeled_row(
+                label,
+                variant_field(binding, value, mixed).boxed_scene(),
+                actions,
+            )
+        }
+        ReflectRef::Struct(_)
+        | ReflectRef::TupleStruct(_)
+        | ReflectRef::Tuple(_)
+        | ReflectRef::List(_)
+        | ReflectRef::Array(_)
+        | ReflectRef::Map(_)
+        | ReflectRef::Set(_)
+        | ReflectRef::Enum(_) => section(label, binding, values, actions, collapsed).boxed_scene(),
+        _ => labeled_row(label, value_text(values).boxed_scene(), actions),
+    }
+}
+
+fn variant_name(value: &dyn PartialReflect) -> Option<&str> {
+    match value.reflect_ref() {
+        ReflectRef::Enum(value) => Some(value.variant_name()),
+        _ => None,
+    }
+}
+
+fn list_len(value: &dyn PartialReflect) -> Option<usize> {
+    match value.reflect_ref() {
+        ReflectRef::List(value) => Some(value.len()),
+        _ => None,
+    }
+}
+
+/// Returns `true` if the enum values aren't all of the same variant.
+fn variants_differ(values: &[&dyn PartialReflect]) -> bool {
+    values
+        .iter()
+        .any(|value| variant_name(*value) != variant_name(values[0]))
+}
+
+/// Returns `true` if all values are equal.
+fn all_same(values: &[&dyn PartialReflect]) -> bool {
+    values.iter().all(|value| same_value(*value, values[0]))
+}
+
+fn labeled_row(label: String, editor: BoxedScene, actions: Vec<BoxedScene>) -> BoxedScene {
+    bsn! {
+        Node {
+            flex_direction: FlexDirection::Row,
+            margin: UiRect::vertical(Val::Px(2.0)),
+            padding: UiRect::all(Val::Px(5.0)),
+            justify_content: JustifyContent::SpaceBetween,
+            align_items: AlignItems::Center,
+            min_height: Val::Px(22.0)
+        }
+        // CSS: #36373B - Field background
+        BackgroundColor(Color::srgb(0.212, 0.216, 0.231))
+        BorderRadius::all(Val::Px(3.0))
+        [
+            Text({label.clone()})
+            TextFont::from_font_size(12.0)
+            // CSS: #DADADA - Field labels
+            TextColor(Color::srgb(0.855, 0.855, 0.855)),
+
+            Node {
+                flex_direction: FlexDirection::Row,
+                justify_content: JustifyContent::FlexEnd,
+                align_items: AlignItems::Center,
+                column_gap: Val::Px(4.0),
+                width: Val::Percent(65.0)
+            } [
+                ({editor}),
+                Node {
+                    flex_direction: FlexDirection::Row,
+                    column_gap: Val::Px(2.0)
+                } [ {actions} ],
+            ],
+        ]
+    }
+    .boxed_scene()
+}
+
+/// A collapsible section showing the rows of a compound value.
+fn section(
+    label: String,
+    binding: FieldBinding,
+    values: &[&dyn PartialReflect],
+    actions: Vec<BoxedScene>,
+    collapsed: &CollapsedSections,
+) -> impl Scene {
+    let is_collapsed = collapsed.contains(&binding);
+    let chevron = chevron(is_collapsed);
+    let display = section_display(is_collapsed);
+    let rows = value_rows(&binding, values, collapsed);
+
+    bsn! {
+        Node {
+            flex_direction: FlexDirection::Column,
+            row_gap: Val::Px(4.0)
+        } [
+            Node {
+                flex_direction: FlexDirection::Row,
+                align_items: AlignItems::Center,
+                column_gap: Val::Px(5.0),
+                margin: UiRect::top(Val::Px(2.0)),
+                padding: UiRect::all(Val::Px(5.0)),
+                min_height: Val::Px(22.0)
+            }
+            // CSS: #36373B - Field background
+            BackgroundColor(Color::srgb(0.212, 0.216, 0.231))
+            BorderRadius::all(Val::Px(3.0))
+            template(move |_| Ok(SectionHeader(Some(binding.clone()))))
+            on(toggle_section)
+            [
+                SectionChevron
+                Text(chevron)
+                TextFont::from_font_size(10.0)
+                // CSS: #C4C4C4 - Chevron color
+                TextColor(Color::srgb(0.769, 0.769, 0.769)),
+
+                Node {
+                    flex_grow: 1.0
+                }
+                Text({label.clone()})
+                TextFont::from_font_size(12.0)
+                // CSS: #DADADA - Field labels
+                TextColor(Color::srgb(0.855, 0.855, 0.855)),
+
+                Node {
+                    flex_direction: FlexDirection::Row,
+                    column_gap: Val::Px(2.0)
+                } [ {actions} ],
+            ],
+            // Nested rows
+            template(move |_| Ok(Node {
+                display,
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(4.0),
+                padding: UiRect::left(Val::Px(10.0)),
+                ..default()
+            }))
+            [ {rows} ],
+        ]
+    }
+}
+
+/// A value which can't be edited, shown as its debug representation if it is the same for every
+/// inspected entity.
+fn value_text(values: &[&dyn PartialReflect]) -> impl Scene {
+    if all_same(values) {
+        text(format!("{:?}", values[0]))
+    } else {
+        text(MIXED.to_string())
+    }
+}
+
+fn mixed_text() -> BoxedScene {
+    text(MIXED.to_string()).boxed_scene()
+}
+
+fn text(value_string: String) -> impl Scene {
+    bsn! {
+        Text({value_string.clone()})
+        TextFont::from_font_size(12.0)
+        // CSS: #C2C2C2 - Field values
+        TextColor(Color::srgb(0.761, 0.761, 0.761))
+    }
+}
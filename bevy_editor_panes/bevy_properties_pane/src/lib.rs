@@ -1,28 +1,114 @@
 //! An interactive, reflection-based inspector for Bevy ECS data in running applications.
 //!
 //! Data can be viewed and modified in real-time, with changes being reflected in the application.
+//! Every modification is recorded as an undoable change.
+//!
+//! The properties pane inspects the components of the selected entities, and the resources pane
+//! the reflected resources of the world.
+
+mod add_component;
+mod component_actions;
+mod fields;
+mod inspector;
+mod resources;
 
+use add_component::add_component_button;
 use bevy::{
+    ecs::template::template,
     feathers::theme::ThemedText,
+    input_focus::tab_navigation::TabGroup,
     prelude::*,
-    reflect::*,
-    scene2::{CommandsSpawnScene, Scene, SceneList, bsn},
+    scene2::{CommandsSpawnScene, Scene, SceneList, bsn, on},
 };
 use bevy_editor_core::{prelude::*, selection::common_conditions::primary_selection_changed};
 use bevy_editor_styles::Theme;
+use bevy_field_forms::FieldFormsPlugin;
 use bevy_pane_layout::prelude::*;
+use bevy_undo::UndoPlugin;
+use component_actions::{ComponentClipboard, component_menu};
+use fields::{
+    BoundResources, FieldBinding, copy_bound_resources, open_menu, sync_bool_fields,
+    sync_numeric_fields, sync_string_fields,
+};
+use inspector::{
+    CollapsedSections, InspectedStructure, SectionChevron, SectionHeader, chevron, toggle_section,
+    update_inspected_structure, value_inspector,
+};
+use resources::{
+    InspectedResource, ResourceFilter, ResourceList, ResourcesStructure, setup_resources_pane,
+    update_resources_pane, update_resources_structure,
+};
 
-/// Plugin for the editor properties pane.
+/// Plugin for the editor properties and resources panes.
 pub struct PropertiesPanePlugin;
 
 impl Plugin for PropertiesPanePlugin {
     fn build(&self, app: &mut App) {
-        app.register_pane("Properties", setup_pane).add_systems(
-            Update,
-            (update_properties_pane.run_if(
-                primary_selection_changed.or(any_match_filter::<Added<PropertiesPaneBody>>),
-            ),),
-        );
+        if !app.is_plugin_added::<FieldFormsPlugin>() {
+            app.add_plugins(FieldFormsPlugin);
+        }
+        if !app.is_plugin_added::<UndoPlugin>() {
+            app.add_plugins(UndoPlugin);
+        }
+
+        app.register_pane("Properties", setup_pane)
+            .register_pane("Resources", setup_resources_pane)
+            .init_resource::<ComponentClipboard>()
+            .init_resource::<CollapsedSections>()
+            .init_resource::<InspectedStructure>()
+            .init_resource::<InspectedResource>()
+            .init_resource::<ResourceFilter>()
+            .init_resource::<ResourcesStructure>()
+            .init_resource::<BoundResources>()
+            .add_systems(
+                Update,
+                (
+                    update_inspected_structure,
+                    update_properties_pane.run_if(
+                        primary_selection_changed
+                            .or(any_match_filter::<Added<PropertiesPaneBody>>)
+                            .or(resource_changed::<InspectedStructure>),
+                    ),
+                )
+                    .chain(),
+            )
+            .add_systems(
+                Update,
+                (
+                    update_resources_structure,
+                    update_resources_pane.run_if(
+                        any_match_filter::<Added<ResourceList>>
+                            .or(resource_changed::<ResourceFilter>)
+                            .or(resource_changed::<InspectedResource>)
+                            .or(resource_changed::<ResourcesStructure>),
+                    ),
+                )
+                    .chain()
+                    .run_if(any_with_component::<ResourceList>),
+            )
+            .add_systems(
+                Update,
+                (
+                    copy_bound_resources,
+                    (
+                        sync_numeric_fields::<f32>,
+                        sync_numeric_fields::<f64>,
+                        sync_numeric_fields::<i8>,
+                        sync_numeric_fields::<i16>,
+                        sync_numeric_fields::<i32>,
+                        sync_numeric_fields::<i64>,
+                        sync_numeric_fields::<i128>,
+                        sync_numeric_fields::<u8>,
+                        sync_numeric_fields::<u16>,
+                        sync_numeric_fields::<u32>,
+                        sync_numeric_fields::<u64>,
+                        sync_numeric_fields::<u128>,
+                        sync_bool_fields,
+                        sync_string_fields,
+                    ),
+                )
+                    .chain(),
+            );
     }
 }
 
@@ -63,17 +149,12 @@ fn update_properties_pane(
 }
 
 fn properties_pane(selection: &EditorSelection, theme: &Theme, world: &World) -> impl Scene {
-    match selection.primary() {
-        Some(selection) => bsn! {
-            Node {
-                flex_direction: FlexDirection::Column,
-                padding: UiRect::all(Val::Px(8.0)),
-                row_gap: Val::Px(6.0)
-            } [
-            {component_list(selection, theme, world)}
-        ]}
-        .boxed_scene(),
-        None => bsn! {
+    // The primary selected entity comes first, its values are shown when several are selected.
+    let mut entities: Vec<Entity> = selection.iter().collect();
+    entities.reverse();
+
+    if entities.is_empty() {
+        bsn! {
             Node {
                 flex_direction: FlexDirection::Column,
                 justify_content: JustifyContent::Center,
@@ -85,15 +166,51 @@ fn properties_pane(selection: &EditorSelection, theme: &Theme, world: &World) ->
                 TextColor(Color::srgb(0.514, 0.514, 0.522)),
             ]
         }
-        .boxed_scene(),
+        .boxed_scene()
+    } else {
+        bsn! {
+            Node {
+                flex_direction: FlexDirection::Column,
+                padding: UiRect::all(Val::Px(8.0)),
+                row_gap: Val::Px(6.0)
+            }
+            template(|_| Ok(TabGroup::default()))
+            [
+            {component_list(&entities, theme, world)}
+        ]}
+        .boxed_scene()
     }
 }
 
-fn component_list(entity: Entity, theme: &Theme, world: &World) -> impl SceneList {
+/// The components shared by all inspected entities, followed by the button adding more.
+fn component_list(entities: &[Entity], _theme: &Theme, world: &World) -> impl SceneList {
     let type_registry = world.resource::<AppTypeRegistry>().read();
-    world
-        .inspect_entity(entity)
+    let collapsed = world.resource::<CollapsedSections>();
+
+    let summary = (entities.len() > 1).then(|| {
+        let summary = format!(
+            "{} entities selected, showing shared components",
+            entities.len()
+        );
+        bsn! {
+            Text({summary.clone()})
+            TextFont::from_font_size(12.0)
+            // CSS: #838385 - Secondary text
+            TextColor(Color::srgb(0.514, 0.514, 0.522))
+        }
+        .boxed_scene()
+    });
+
+    let components = world
+        .inspect_entity(entities[0])
         .unwrap()
+        .filter(|component_info| {
+            entities[1..].iter().all(|&entity| {
+                world
+                    .get_entity(entity)
+                    .is_ok_and(|entity| entity.contains_id(component_info.id()))
+            })
+        })
         .map(|component_info| {
             let type_info = component_info
                 .type_id()
@@ -103,13 +220,26 @@ fn component_list(entity: Entity, theme: &Theme, world: &World) -> impl SceneLis
                 |type_info| type_info.type_path_table().short_path().to_string(),
             );
 
-            // Get the reflected component value from the world
-            let reflect: Option<&dyn Reflect> = component_info.type_id().and_then(|type_id| {
-                let registration = type_registry.get(type_id)?;
-                let reflect_component = registration.data::<ReflectComponent>()?;
-                let entity_ref = world.get_entity(entity);
-                reflect_component.reflect(entity_ref.unwrap())
-            });
+            // Get the reflected component values from the world
+            let reflect: Option<Vec<&dyn PartialReflect>> =
+                component_info.type_id().and_then(|type_id| {
+                    let registration = type_registry.get(type_id)?;
+                    let reflect_component = registration.data::<ReflectComponent>()?;
+                    entities
+                        .iter()
+                        .map(|&entity| {
+                            let entity_ref = world.get_entity(entity).ok()?;
+                            Some(reflect_component.reflect(entity_ref)?.as_partial_reflect())
+                        })
+                        .collect()
+                });
+
+            let binding = component_info
+                .type_id()
+                .map(|type_id| FieldBinding::component(entities.to_vec(), type_id));
+            let header_binding = binding.clone();
+            let menu_binding = binding.clone();
+            let chevron = chevron(binding.as_ref().is_some_and(|b| collapsed.contains(b)));
 
             bsn! {
                 Node {
@@ -135,13 +265,16 @@ fn component_list(entity: Entity, theme: &Theme, world: &World) -> impl SceneLis
                     // CSS: #36373B - Header background
                     BackgroundColor(Color::srgb(0.212, 0.216, 0.231))
                     BorderRadius::top(Val::Px(5.0))
+                    template(move |_| Ok(SectionHeader(header_binding.clone())))
+                    on(toggle_section)
                     [
                         Node {
                             flex_direction: FlexDirection::Row,
                             align_items: AlignItems::Center,
                             column_gap: Val::Px(5.0)
                         } [
-                            Text("▼")
+                            SectionChevron
+                            Text(chevron)
                             TextFont::from_font_size(12.0)
                             // CSS: #C4C4C4 - Chevron color
                             TextColor(Color::srgb(0.769, 0.769, 0.769)),
@@ -155,12 +288,16 @@ fn component_list(entity: Entity, theme: &Theme, world: &World) -> impl SceneLis
                         Text("⋯")
                         TextFont::from_font_size(12.0)
                         // CSS: #C4C4C4 - Menu dots
-                        TextColor(Color::srgb(0.769, 0.769, 0.769)),
+                        TextColor(Color::srgb(0.769, 0.769, 0.769))
+                        template(move |_| Ok(component_menu(menu_binding.clone())))
+                        on(open_menu),
                     ],
                     // Component fields
-                    ({ match reflect {
-                        Some(reflect) => component(type_info, reflect, theme).boxed_scene(),
-                        None => bsn! {
+                    ({ match (binding.clone(), &reflect) {
+                        (Some(binding), Some(values)) => {
+                            value_inspector(binding, values, collapsed).boxed_scene()
+                        }
+                        _ => bsn! {
                             Node {
                                 flex_direction: FlexDirection::Row,
                                 padding: UiRect::all(Val::Px(8.0))
@@ -173,104 +310,14 @@ fn component_list(entity: Entity, theme: &Theme, world: &World) -> impl SceneLis
                     }}),
                 ]
             }
-        })
-        .collect::<Vec<_>>()
-}
-
-fn component(type_info: Option<&TypeInfo>, reflect: &dyn Reflect, theme: &Theme) -> impl Scene {
-    match type_info {
-        Some(TypeInfo::Struct(info)) => reflected_struct(info, reflect, theme).boxed_scene(),
-        Some(TypeInfo::TupleStruct(info)) => reflected_tuple_struct(info, theme).boxed_scene(),
-        Some(TypeInfo::Enum(info)) => reflected_enum(info, theme).boxed_scene(),
-        _ => bsn! {}.boxed_scene(),
-    }
-}
-fn reflected_struct(struct_info: &StructInfo, reflect: &dyn Reflect, _theme: &Theme) -> impl Scene {
-    let fields = struct_info
-        .iter()
-        .enumerate()
-        .map(|(i, field)| {
-            let field_reflect = reflect
-                .reflect_ref()
-                .as_struct()
-                .ok()
-                .and_then(|s| s.field_at(i));
-
-            let field_name = field.name();
-
-            let value_string = field_reflect
-                .map(|v| format!("{v:?}"))
-                .unwrap_or_else(|| "<unavailable>".to_string());
-
-            bsn! {
-                Node {
-                    flex_direction: FlexDirection::Row,
-                    margin: UiRect::vertical(Val::Px(2.0)),
-                    padding: UiRect::all(Val::Px(5.0)),
-                    justify_content: JustifyContent::SpaceBetween,
-                    align_items: AlignItems::Center,
-                    min_height: Val::Px(22.0)
-                }
-                // CSS: #36373B - Field background
-                BackgroundColor(Color::srgb(0.212, 0.216, 0.231))
-                BorderRadius::all(Val::Px(3.0))
-                [
-                    Text(field_name)
-                    TextFont::from_font_size(12.0)
-                    // CSS: #DADADA - Field labels
-                    TextColor(Color::srgb(0.855, 0.855, 0.855)),
-
-                    Text({value_string.clone()})
-                    TextFont::from_font_size(12.0)
-                    // CSS: #C2C2C2 - Field values
-                    TextColor(Color::srgb(0.761, 0.761, 0.761)),
-                ]
-            }
-        })
-        .collect::<Vec<_>>();
+            .boxed_scene()
+        });
 
-    bsn! {
-        Node {
-            flex_direction: FlexDirection::Column,
-            padding: UiRect::all(Val::Px(7.0)),
-            row_gap: Val::Px(4.0)
-        } [ {fields} ]
-    }
-}
-
-fn reflected_tuple_struct(tuple_struct_info: &TupleStructInfo, _theme: &Theme) -> impl Scene {
-    let fields = tuple_struct_info
-        .iter()
-        .map(|_field| {
-            bsn! {
-                Text("TODO")
-                TextFont::from_font_size(10.0)
-            }
-        })
-        .collect::<Vec<_>>();
+    let add_button = add_component_button(entities.to_vec()).boxed_scene();
 
-    bsn! {
-        Node {
-            flex_direction: FlexDirection::Column
-        } [ {fields} ]
-    }
-}
-
-fn reflected_enum(enum_info: &EnumInfo, _theme: &Theme) -> impl Scene {
-    let variants = enum_info
-        .iter()
-        .map(|variant| {
-            let name = variant.name();
-            bsn! {
-                Text(name)
-                TextFont::from_font_size(10.0)
-            }
-        })
-        .collect::<Vec<_>>();
-
-    bsn! {
-        Node {
-            flex_direction: FlexDirection::Column,
-        } [ {variants} ]
-    }
+    summary
+        .into_iter()
+        .chain(components)
+        .chain([add_button])
+        .collect::<Vec<_>>()
 }
@@ -0,0 +1,305 @@
+//! The resources pane, listing the reflected resources of the world and inspecting the selected
+//! one with the same editors as the properties pane.
+//!
+//! Only resources registered with `#[reflect(Resource)]` are listed.
+
+use std::{
+    any::TypeId,
+    hash::{DefaultHasher, Hash, Hasher},
+};
+
+use bevy::{
+    ecs::template::template,
+    feathers::theme::ThemedText,
+    input_focus::tab_navigation::TabGroup,
+    prelude::*,
+    reflect::TypeRegistry,
+    scene2::{CommandsSpawnScene, Scene, bsn, on},
+};
+use bevy_editor_core::utils::IntoBoxedScene;
+use bevy_pane_layout::prelude::*;
+use bevy_text_editing::{EditableTextLine, TextChanged};
+
+use crate::{
+    fields::FieldBinding,
+    inspector::{
+        CollapsedSections, SectionChevron, SectionHeader, chevron, hash_structure, toggle_section,
+        value_inspector,
+    },
+};
+
+/// The resource inspected in the resources pane.
+#[derive(Resource, Default)]
+pub(crate) struct InspectedResource(Option<TypeId>);
+
+/// Text filtering the resources listed in the resources pane by name.
+#[derive(Resource, Default)]
+pub(crate) struct ResourceFilter(String);
+
+/// A hash of what the layout of the resources pane depends on: the reflected resources in the
+/// world and the structure of the inspected one, like
+/// [`InspectedStructure`](crate::inspector::InspectedStructure) for the properties pane.
+#[derive(Resource, Default, PartialEq)]
+pub(crate) struct ResourcesStructure(u64);
+
+/// The list of resources in the resources pane.
+#[derive(Component, Default, Clone)]
+pub(crate) struct ResourceList;
+
+/// Holds the fields of the inspected resource in the resources pane.
+#[derive(Component, Default, Clone)]
+struct ResourceFields;
+
+/// A row of the resource list, inspecting its resource when clicked.
+#[derive(Component, Clone)]
+struct ResourceRow(TypeId);
+
+pub(crate) fn setup_resources_pane(
+    pane: In<PaneStructure>,
+    filter: Res<ResourceFilter>,
+    mut commands: Commands,
+) {
+    // Remove the existing structure
+    commands.entity(pane.area).despawn();
+
+    let filter = filter.0.clone();
+    commands
+        .spawn_scene(bsn! {
+            :editor_pane [
+                :editor_pane_header [
+                    (Text("Resources") ThemedText),
+                ],
+                :editor_pane_body [
+                    Node {
+                        flex_grow: 1.0,
+                        flex_direction: FlexDirection::Column,
+                        padding: UiRect::all(Val::Px(8.0)),
+                        row_gap: Val::Px(6.0)
+                    }
+                    template(|_| Ok(TabGroup::default()))
+                    [
+                        Node {
+                            flex_direction: FlexDirection::Row,
+                            flex_shrink: 0.0,
+                            align_items: AlignItems::Center,
+                            column_gap: Val::Px(6.0)
+                        } [
+                            Text("Filter")
+                            TextFont::from_font_size(12.0)
+                            // CSS: #838385 - Secondary text
+                            TextColor(Color::srgb(0.514, 0.514, 0.522)),
+
+                            Node {
+                                flex_grow: 1.0,
+                                height: Val::Px(20.0),
+                                padding: UiRect::horizontal(Val::Px(4.0))
+                            }
+                            // CSS: #2A2A2E - Input background
+                            BackgroundColor(Color::srgb(0.165, 0.165, 0.180))
+                            BorderRadius::all(Val::Px(3.0))
+                            TextFont::from_font_size(12.0)
+                            template(move |_| Ok(EditableTextLine::new(filter.clone())))
+                            on(|mut trigger: On<TextChanged>, mut filter: ResMut<ResourceFilter>| {
+                                trigger.propagate(false);
+                                if filter.0 != trigger.new_text {
+                                    filter.0 = trigger.new_text.clone();
+                                }
+                            }),
+                        ],
+
+                        ResourceList
+                        Node {
+                            flex_direction: FlexDirection::Column,
+                            flex_shrink: 0.0,
+                            max_height: Val::Percent(40.0),
+                            overflow: Overflow::clip_y()
+                        },
+
+                        ResourceFields
+                        Node {
+                            flex_direction: FlexDirection::Column
+                        },
+                    ]
+                ]
+            ]
+        })
+        .insert(ChildOf(pane.root));
+}
+
+pub(crate) fn update_resources_structure(world: &mut World) {
+    let mut hasher = DefaultHasher::new();
+
+    let type_registry = world.resource::<AppTypeRegistry>().read();
+    for (_, type_id) in reflected_resources(world, &type_registry) {
+        type_id.hash(&mut hasher);
+    }
+    let inspected = world
+        .resource::<InspectedResource>()
+        .0
+        .and_then(|type_id| type_registry.get_type_data::<ReflectResource>(type_id))
+        .and_then(|reflect_resource| reflect_resource.reflect(&*world).ok());
+    if let Some(resource) = inspected {
+        hash_structure(resource.as_partial_reflect(), &mut hasher);
+    }
+    drop(type_registry);
+
+    let structure = ResourcesStructure(hasher.finish());
+    world
+        .resource_mut::<ResourcesStructure>()
+        .set_if_neq(structure);
+}
+
+pub(crate) fn update_resources_pane(
+    lists: Query<Entity, With<ResourceList>>,
+    fields: Query<Entity, With<ResourceFields>>,
+    world: &World,
+    mut commands: Commands,
+) {
+    let type_registry = world.resource::<AppTypeRegistry>().read();
+    let inspected = world.resource::<InspectedResource>().0;
+    let filter = world.resource::<ResourceFilter>().0.trim().to_lowercase();
+    let resources = reflected_resources(world, &type_registry);
+
+    for list in &lists {
+        commands.entity(list).despawn_children();
+        for &(name, type_id) in &resources {
+            if name.to_lowercase().contains(&filter) {
+                commands
+                    .spawn_scene(resource_row(name, type_id, inspected == Some(type_id)))
+                    .insert(ChildOf(list));
+            }
+        }
+    }
+
+    let inspected = inspected.and_then(|type_id| {
+        let name = resources
+            .iter()
+            .find_map(|&(name, id)| (id == type_id).then_some(name))?;
+        let reflect_resource = type_registry.get_type_data::<ReflectResource>(type_id)?;
+        let resource = reflect_resource.reflect(world).ok()?;
+        Some((name, type_id, resource.as_partial_reflect()))
+    });
+    let collapsed = world.resource::<CollapsedSections>();
+
+    for fields in &fields {
+        commands.entity(fields).despawn_children();
+        let scene = match inspected {
+            Some((name, type_id, resource)) => {
+                resource_inspector(name, type_id, resource, collapsed).boxed_scene()
+            }
+            None => bsn! {
+                Text("Select a resource to inspect")
+                TextFont::from_font_size(12.0)
+                // CSS: #838385 - Secondary text
+                TextColor(Color::srgb(0.514, 0.514, 0.522))
+            }
+            .boxed_scene(),
+        };
+        commands.spawn_scene(scene).insert(ChildOf(fields));
+    }
+}
+
+/// The reflected resources in the world, by name.
+fn reflected_resources(world: &World, type_registry: &TypeRegistry) -> Vec<(&'static str, TypeId)> {
+    let mut resources: Vec<(&str, TypeId)> = type_registry
+        .iter()
+        .filter(|registration| {
+            registration
+                .data::<ReflectResource>()
+                .is_some_and(|reflect_resource| reflect_resource.reflect(world).is_ok())
+        })
+        .map(|registration| {
+            let name = registration.type_info().type_path_table().short_path();
+            (name, registration.type_id())
+        })
+        .collect();
+    resources.sort_unstable_by_key(|&(name, _)| name);
+    resources
+}
+
+fn resource_row(name: &'static str, type_id: TypeId, selected: bool) -> impl Scene {
+    let background = if selected {
+        // CSS: #36373B - Header background
+        Color::srgb(0.212, 0.216, 0.231)
+    } else {
+        Color::NONE
+    };
+
+    bsn! {
+        Node {
+            flex_shrink: 0.0,
+            padding: UiRect::axes(Val::Px(8.0), Val::Px(4.0))
+        }
+        BackgroundColor(background)
+        BorderRadius::all(Val::Px(3.0))
+        template(move |_| Ok(ResourceRow(type_id)))
+        on(|trigger: On<Pointer<Click>>,
+            rows: Query<&ResourceRow>,
+            mut inspected: ResMut<InspectedResource>| {
+            let Ok(row) = rows.get(trigger.target()) else {
+                return;
+            };
+            if inspected.0 != Some(row.0) {
+                inspected.0 = Some(row.0);
+            }
+        })
+        [
+            Text(name)
+            TextFont::from_font_size(12.0)
+            // CSS: #DCDCDC - Resource name
+            TextColor(Color::srgb(0.863, 0.863, 0.863))
+            Pickable::IGNORE,
+        ]
+    }
+}
+
+fn resource_inspector(
+    name: &'static str,
+    type_id: TypeId,
+    resource: &dyn PartialReflect,
+    collapsed: &CollapsedSections,
+) -> impl Scene {
+    let binding = FieldBinding::resource(type_id);
+    let header_binding = Some(binding.clone());
+    let chevron = chevron(collapsed.contains(&binding));
+    let fields = value_inspector(binding, &[resource], collapsed);
+
+    bsn! {
+        Node {
+            flex_direction: FlexDirection::Column,
+            border: UiRect::all(Val::Px(1.0))
+        }
+        // CSS: #2A2A2E - Component background
+        BackgroundColor(Color::srgb(0.165, 0.165, 0.180))
+        // CSS: #414142 - Border color
+        BorderColor::all(Color::srgb(0.255, 0.255, 0.259))
+        BorderRadius::all(Val::Px(5.0))
+        [
+            Node {
+                flex_direction: FlexDirection::Row,
+                align_items: AlignItems::Center,
+                column_gap: Val::Px(5.0),
+                padding: UiRect::all(Val::Px(8.0)),
+                height: Val::Px(26.0)
+            }
+            // CSS: #36373B - Header background
+            BackgroundColor(Color::srgb(0.212, 0.216, 0.231))
+            BorderRadius::top(Val::Px(5.0))
+            template(move |_| Ok(SectionHeader(header_binding.clone())))
+            on(toggle_section)
+            [
+                SectionChevron
+                Text(chevron)
+                TextFont::from_font_size(12.0)
+                // CSS: #C4C4C4 - Chevron color
+                TextColor(Color::srgb(0.769, 0.769, 0.769)),
+
+                Text(name)
+                TextFont::from_font_size(12.0)
+                // CSS: #DCDCDC - Resource name
+                TextColor(Color::srgb(0.863, 0.863, 0.863)),
+            ],
+            ({fields}),
+        ]
+    }
+}
@@ -0,0 +1,142 @@
+//! Reparenting and reordering entities by dragging their rows in the scene tree.
+
+use bevy::prelude::*;
+use bevy_undo::{HierarchyChange, NewChange, OneFrameUndoIgnore};
+
+use crate::{SceneTreeExpansion, SceneTreeSettings, row_drop_area::RowDropArea};
+
+/// The entity whose row is being dragged, and where it would currently be dropped.
+#[derive(Resource, Default, PartialEq)]
+pub(crate) struct SceneTreeDrag {
+    /// The scene entity being dragged.
+    pub dragged: Option<Entity>,
+    /// The scene entity whose row the dragged entity is over, and the area of that row.
+    pub target: Option<(Entity, RowDropArea)>,
+}
+
+impl SceneTreeDrag {
+    /// Returns the drop area of the row of `entity`, if the dragged entity is over it.
+    pub fn drop_area(&self, entity: Entity) -> Option<RowDropArea> {
+        self.target
+            .and_then(|(target, area)| (target == entity).then_some(area))
+    }
+}
+
+/// Forget the dragged entity once any drag ends, whether or not it was dropped onto the tree.
+pub(crate) fn clear_drag(_trigger: On<Pointer<DragEnd>>, mut drag: ResMut<SceneTreeDrag>) {
+    // Avoid triggering change detection, which rebuilds the tree.
+    if *drag != SceneTreeDrag::default() {
+        *drag = SceneTreeDrag::default();
+    }
+}
+
+/// Returns `true` if `entity` can be moved relative to the row of `target` without creating a
+/// cycle in the hierarchy.
+pub(crate) fn can_drop_onto(entity: Entity, target: Entity, parents: &Query<&ChildOf>) -> bool {
+    entity != target
+        && !parents
+            .iter_ancestors(target)
+            .any(|parent| parent == entity)
+}
+
+/// Move `entity` next to or into the entity of a tree row, or to the root of the hierarchy if
+/// there is no target row. The move is recorded as a single undoable change.
+#[expect(clippy::too_many_arguments)]
+pub(crate) fn move_entity(
+    In((entity, target)): In<(Entity, Option<(Entity, RowDropArea)>)>,
+    settings: Res<SceneTreeSettings>,
+    parents: Query<&ChildOf>,
+    children: Query<&Children>,
+    transforms: Query<(&Transform, &GlobalTransform)>,
+    global_transforms: Query<&GlobalTransform>,
+    mut expansion: ResMut<SceneTreeExpansion>,
+    mut new_changes: EventWriter<NewChange>,
+    mut commands: Commands,
+) {
+    let index_of = |parent: Entity, child: Entity| {
+        children
+            .get(parent)
+            .ok()
+            .and_then(|children| children.iter().position(|c| c == child))
+    };
+
+    let old_parent = parents.get(entity).ok().map(ChildOf::parent);
+    let old_index = old_parent
+        .and_then(|parent| index_of(parent, entity))
+        .unwrap_or(0);
+
+    // Where the entity would be inserted, while it is still at its old place.
+    let (new_parent, index) = match target {
+        None => (None, 0),
+        Some((target, _)) if !can_drop_onto(entity, target, &parents) => return,
+        Some((target, RowDropArea::Onto)) => (
+            Some(target),
+            children.get(target).map_or(0, |children| children.len()),
+        ),
+        Some((target, area)) => {
+            let parent = parents.get(target).ok().map(ChildOf::parent);
+            let index = parent
+                .and_then(|parent| index_of(parent, target))
+                .unwrap_or(0);
+            let index = if area == RowDropArea::Below {
+                index + 1
+            } else {
+                index
+            };
+            (parent, index)
+        }
+    };
+
+    // Moving an entity among its siblings takes it out of the list of children first.
+    let new_index = if new_parent.is_some() && new_parent == old_parent && old_index < index {
+        index - 1
+    } else {
+        index
+    };
+
+    if new_parent == old_parent && (new_parent.is_none() || new_index == old_index) {
+        return;
+    }
+
+    let (old_transform, new_transform) = match transforms.get(entity) {
+        Ok((transform, global_transform)) if settings.preserve_global_transform => {
+            let new_transform =
+                match new_parent.and_then(|parent| global_transforms.get(parent).ok()) {
+                    Some(parent_transform) => global_transform.reparented_to(parent_transform),
+                    None => global_transform.compute_transform(),
+                };
+            (Some(*transform), Some(new_transform))
+        }
+        _ => (None, None),
+    };
+
+    match new_parent {
+        Some(parent) => {
+            commands
+                .entity(parent)
+                .insert_children(new_index, &[entity]);
+            // Keep the moved entity visible in the tree.
+            expansion.set_expanded(parent, true);
+        }
+        None => {
+            commands.entity(entity).remove::<ChildOf>();
+        }
+    }
+    if let Some(transform) = new_transform {
+        commands.entity(entity).insert(transform);
+    }
+    // The move is recorded below, it shouldn't be recorded again by automatic undo.
+    commands
+        .entity(entity)
+        .insert(OneFrameUndoIgnore::default());
+
+    new_changes.write(NewChange::new(HierarchyChange {
+        entity,
+        old_parent,
+        old_index,
+        new_parent,
+        new_index,
+        old_transform,
+        new_transform,
+    }));
+}
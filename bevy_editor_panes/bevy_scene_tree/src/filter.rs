@@ -0,0 +1,162 @@
+//! Scene tree filtering
+//!
+//! A filter is a whitespace separated list of terms, all of which an entity has to match to be
+//! shown in the tree, together with its ancestors:
+//! - `has <Component>` or `has:<Component>` matches entities with a component of that type, by its
+//!   short type name, e.g. `has PointLight` or `has Mesh3d`.
+//! - Any other term matches the name of the entity. Terms containing `*` or `?` are glob patterns
+//!   which have to match the whole name, other terms match any part of the name.
+//!
+//! All matching is case-insensitive.
+
+use bevy::prelude::*;
+
+/// The filter applied to the rows of the scene tree.
+#[derive(Resource, Default)]
+pub struct SceneTreeFilter {
+    text: String,
+    name_patterns: Vec<String>,
+    component_names: Vec<String>,
+}
+
+impl SceneTreeFilter {
+    /// Create a filter from its text, see the [module docs](self) for the syntax.
+    pub fn new(text: impl Into<String>) -> Self {
+        let mut filter = Self::default();
+        filter.set_text(text);
+        filter
+    }
+
+    /// The text the filter was created from.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Replace the filter with one parsed from `text`.
+    pub fn set_text(&mut self, text: impl Into<String>) {
+        self.text = text.into();
+        self.name_patterns.clear();
+        self.component_names.clear();
+
+        let mut terms = self.text.split_whitespace().map(str::to_lowercase);
+        while let Some(term) = terms.next() {
+            if let Some(component) = term.strip_prefix("has:") {
+                if !component.is_empty() {
+                    self.component_names.push(component.to_string());
+                }
+            } else if term == "has" {
+                // A trailing `has` is still being typed, ignore it.
+                if let Some(component) = terms.next() {
+                    self.component_names.push(component);
+                }
+            } else {
+                self.name_patterns.push(term);
+            }
+        }
+    }
+
+    /// Returns `true` if the filter lets every entity through.
+    pub fn is_empty(&self) -> bool {
+        self.name_patterns.is_empty() && self.component_names.is_empty()
+    }
+
+    /// The lowercase short type names of the components an entity needs to match the filter.
+    pub fn component_names(&self) -> &[String] {
+        &self.component_names
+    }
+
+    /// Returns `true` if an entity with this name matches the name terms of the filter.
+    pub fn matches_name(&self, name: &str) -> bool {
+        let name = name.to_lowercase();
+        self.name_patterns.iter().all(|pattern| {
+            if pattern.contains(['*', '?']) {
+                glob_match(pattern, &name)
+            } else {
+                name.contains(pattern.as_str())
+            }
+        })
+    }
+}
+
+/// Match `text` against a glob `pattern`, where `*` matches any number of characters and `?`
+/// matches a single character.
+fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+
+    let (mut p, mut t) = (0, 0);
+    // Position of the last `*` in the pattern, and the text position it was matched at.
+    let mut backtrack = None;
+
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p, t));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match backtrack {
+                // Let the last `*` match one more character and try again.
+                Some((star, star_t)) => {
+                    backtrack = Some((star, star_t + 1));
+                    p = star + 1;
+                    t = star_t + 1;
+                }
+                None => return false,
+            },
+        }
+    }
+
+    pattern[p..].iter().all(|c| *c == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn glob_match_tests() {
+        let cases = [
+            ("*", "", true),
+            ("*", "anything", true),
+            ("lamp*", "lamp_01", true),
+            ("lamp*", "desk_lamp", false),
+            ("*lamp", "desk_lamp", true),
+            ("*lamp*", "desk_lamp_01", true),
+            ("lamp_??", "lamp_01", true),
+            ("lamp_??", "lamp_1", false),
+            ("l*p_*1", "lamp_01", true),
+            ("l*p_*1", "lamp_02", false),
+            ("a*b*c", "aXbYbZc", true),
+            ("", "", true),
+            ("", "a", false),
+        ];
+
+        for (pattern, text, expected) in cases {
+            assert_eq!(
+                glob_match(pattern, text),
+                expected,
+                "{pattern:?} ~ {text:?} -> {expected}"
+            );
+        }
+    }
+
+    #[test]
+    fn filter_parsing() {
+        let filter = SceneTreeFilter::new("  Lamp has PointLight has:Mesh3d  ");
+        assert_eq!(filter.component_names(), ["pointlight", "mesh3d"]);
+        assert!(filter.matches_name("Desk lamp"));
+        assert!(!filter.matches_name("Desk"));
+
+        let filter = SceneTreeFilter::new("chair_* has");
+        assert!(filter.component_names().is_empty());
+        assert!(filter.matches_name("Chair_01"));
+        assert!(!filter.matches_name("Old chair_01"));
+
+        assert!(SceneTreeFilter::new("   ").is_empty());
+        assert!(SceneTreeFilter::new(" ").matches_name("anything"));
+    }
+}
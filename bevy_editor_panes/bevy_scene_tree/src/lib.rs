@@ -1,57 +1,470 @@
 //! An interactive, collapsible tree view for hierarchical ECS data in Bevy.
+//!
+//! The tree is only rebuilt when the scene hierarchy, entity names, the selection or the tree's
+//! scroll position change, and only the rows currently visible in the pane are materialized.
+//!
+//! Entities can be reparented and reordered by dragging their rows onto other rows, or onto the
+//! empty space of the tree to make them root entities. Every move can be undone.
+//!
+//! The filter bar above the tree narrows it down to entities matching a [`SceneTreeFilter`].
 
-use bevy::{app::Plugin, color::palettes::tailwind, prelude::*};
+mod drag_and_drop;
+mod filter;
+mod row_drop_area;
+
+use bevy::{
+    app::Plugin,
+    color::palettes::tailwind,
+    ecs::{
+        entity::{Entities, EntityHashSet},
+        system::SystemParam,
+    },
+    input_focus::tab_navigation::TabGroup,
+    prelude::*,
+};
 use bevy_editor_core::selection::EditorSelection;
+use bevy_editor_styles::Theme;
 use bevy_i_cant_believe_its_not_bsn::{Template, TemplateEntityCommandsExt, on, template};
 use bevy_pane_layout::prelude::{PaneAppExt, PaneStructure};
+use bevy_scroll_box::{ScrollBox, ScrollBoxPlugin, spawn_scroll_box};
+use bevy_text_editing::{EditableTextLine, EditableTextLinePlugin, TextChanged};
+use bevy_undo::UndoPlugin;
+use drag_and_drop::{SceneTreeDrag, can_drop_onto, clear_drag, move_entity};
+pub use filter::SceneTreeFilter;
+use row_drop_area::{RowDropArea, get_row_drop_area};
+
+/// Height of a single row of the tree, in logical pixels.
+const ROW_HEIGHT: f32 = 20.0;
+/// Padding around the rows of the tree, in logical pixels.
+const TREE_PADDING: f32 = 8.0;
 
 /// Plugin for the editor scene tree pane.
 pub struct SceneTreePlugin;
 
 impl Plugin for SceneTreePlugin {
     fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<ScrollBoxPlugin>() {
+            app.add_plugins(ScrollBoxPlugin);
+        }
+        if !app.is_plugin_added::<UndoPlugin>() {
+            app.add_plugins(UndoPlugin);
+        }
+        if !app.is_plugin_added::<EditableTextLinePlugin>() {
+            app.add_plugins(EditableTextLinePlugin);
+        }
+
         app.register_pane("Scene Tree", setup_pane)
-            .add_systems(PostUpdate, update_scene_tree);
+            .init_resource::<SceneTreeExpansion>()
+            .init_resource::<SceneTreeSettings>()
+            .init_resource::<SceneTreeFilter>()
+            .init_resource::<SceneTreeDrag>()
+            .add_observer(clear_drag)
+            .add_systems(
+                PostUpdate,
+                (remove_despawned_from_expansion, update_scene_tree).chain(),
+            );
+    }
+}
+
+/// Settings of the scene tree pane.
+#[derive(Resource)]
+pub struct SceneTreeSettings {
+    /// Whether entities reparented by dragging them in the tree keep their global transform.
+    /// Otherwise they keep their local transform, which is then relative to the new parent.
+    pub preserve_global_transform: bool,
+}
+
+impl Default for SceneTreeSettings {
+    fn default() -> Self {
+        Self {
+            preserve_global_transform: true,
+        }
     }
 }
 
-/// Root UI node of the scene tree.
+/// Root UI node of the scene tree. The tree rows are built as its children.
 #[derive(Component)]
 struct SceneTreeRoot;
 
-fn setup_pane(pane: In<PaneStructure>, mut commands: Commands) {
+/// The expand/collapse state of the scene tree, keyed by the scene entity each row represents.
+///
+/// This lives outside of the tree UI so that it survives the rows being rebuilt.
+/// Entities are collapsed unless they have been expanded.
+#[derive(Resource, Default)]
+pub struct SceneTreeExpansion(EntityHashSet);
+
+impl SceneTreeExpansion {
+    /// Check whether the row of an entity is expanded, showing its children.
+    pub fn is_expanded(&self, entity: Entity) -> bool {
+        self.0.contains(&entity)
+    }
+
+    /// Expand or collapse the row of an entity.
+    pub fn set_expanded(&mut self, entity: Entity, expanded: bool) {
+        if expanded {
+            self.0.insert(entity);
+        } else {
+            self.0.remove(&entity);
+        }
+    }
+
+    /// Toggle the row of an entity between expanded and collapsed.
+    pub fn toggle(&mut self, entity: Entity) {
+        let expanded = self.is_expanded(entity);
+        self.set_expanded(entity, !expanded);
+    }
+}
+
+fn setup_pane(
+    pane: In<PaneStructure>,
+    theme: Res<Theme>,
+    filter: Res<SceneTreeFilter>,
+    mut commands: Commands,
+) {
+    commands.entity(pane.content).insert((
+        Node {
+            flex_direction: FlexDirection::Column,
+            flex_grow: 1.0,
+            ..Default::default()
+        },
+        BackgroundColor(tailwind::NEUTRAL_600.into()),
+    ));
+
+    // Filter bar
     commands
-        .entity(pane.content)
-        .insert((
-            SceneTreeRoot,
+        .spawn((
             Node {
-                flex_direction: FlexDirection::Column,
-                flex_grow: 1.0,
-                column_gap: Val::Px(2.0),
-                padding: UiRect::all(Val::Px(8.0)),
+                flex_direction: FlexDirection::Row,
+                flex_shrink: 0.0,
+                align_items: AlignItems::Center,
+                column_gap: Val::Px(6.0),
+                margin: UiRect::new(
+                    Val::Px(TREE_PADDING),
+                    Val::Px(TREE_PADDING),
+                    Val::Px(TREE_PADDING),
+                    Val::Px(0.0),
+                ),
                 ..Default::default()
             },
-            BackgroundColor(tailwind::NEUTRAL_600.into()),
+            TabGroup::default(),
+            ChildOf(pane.content),
         ))
-        .observe(
-            |mut trigger: On<Pointer<Click>>, mut selection: ResMut<EditorSelection>| {
-                selection.clear();
-                trigger.propagate(false);
-            },
-        );
+        .with_children(|parent| {
+            parent.spawn((
+                Text::new("Filter"),
+                TextFont::from_font_size(12.0),
+                TextColor(tailwind::NEUTRAL_400.into()),
+                Pickable::IGNORE,
+            ));
+            parent
+                .spawn((
+                    EditableTextLine::new(filter.text()),
+                    Node {
+                        flex_grow: 1.0,
+                        height: Val::Px(ROW_HEIGHT + 4.0),
+                        padding: UiRect::horizontal(Val::Px(4.0)),
+                        ..Default::default()
+                    },
+                    BackgroundColor(tailwind::NEUTRAL_700.into()),
+                    BorderRadius::all(Val::Px(4.0)),
+                ))
+                .observe(
+                    |trigger: On<TextChanged>, mut filter: ResMut<SceneTreeFilter>| {
+                        if filter.text() != trigger.new_text {
+                            filter.set_text(trigger.new_text.clone());
+                        }
+                    },
+                );
+        });
+
+    spawn_scroll_box(
+        &mut commands,
+        &theme,
+        Overflow::scroll_y(),
+        Some(|commands: &mut Commands, content: Entity| {
+            commands
+                .entity(content)
+                .observe(
+                    |mut trigger: On<Pointer<Click>>, mut selection: ResMut<EditorSelection>| {
+                        selection.clear();
+                        trigger.propagate(false);
+                    },
+                )
+                // Dragging a row onto the empty space below the rows makes its entity a root.
+                .observe(
+                    |mut trigger: On<Pointer<DragOver>>, mut drag: ResMut<SceneTreeDrag>| {
+                        trigger.propagate(false);
+                        if drag.target.is_some() {
+                            drag.target = None;
+                        }
+                    },
+                )
+                .observe(
+                    |mut trigger: On<Pointer<DragDrop>>,
+                     drag: Res<SceneTreeDrag>,
+                     mut commands: Commands| {
+                        trigger.propagate(false);
+                        if let Some(dragged) = drag.dragged {
+                            commands.run_system_cached_with(move_entity, (dragged, None));
+                        }
+                    },
+                );
+            commands.spawn((
+                SceneTreeRoot,
+                Node {
+                    flex_direction: FlexDirection::Column,
+                    width: Val::Percent(100.0),
+                    padding: UiRect::all(Val::Px(TREE_PADDING)),
+                    ..Default::default()
+                },
+                ChildOf(content),
+            ));
+        }),
+    )
+    .insert(ChildOf(pane.content));
+}
+
+/// Forget the expansion state of entities that no longer exist.
+fn remove_despawned_from_expansion(mut expansion: ResMut<SceneTreeExpansion>, entities: &Entities) {
+    // Avoid triggering change detection every frame.
+    if expansion.0.iter().any(|entity| !entities.contains(*entity)) {
+        expansion.0.retain(|entity| entities.contains(*entity));
+    }
+}
+
+/// Detects changes to anything the scene tree displays.
+///
+/// Hierarchy changes on UI nodes and observers are ignored, otherwise rebuilding the tree rows
+/// would itself cause the tree to be rebuilt on the next frame.
+#[derive(SystemParam)]
+struct SceneTreeChanges<'w, 's> {
+    changed_names: Query<'w, 's, (), Changed<Name>>,
+    changed_parents: Query<'w, 's, (), (Changed<ChildOf>, Without<Node>, Without<Observer>)>,
+    changed_children: Query<'w, 's, (), (Changed<Children>, Without<Node>)>,
+    removed_names: RemovedComponents<'w, 's, Name>,
+    removed_parents: RemovedComponents<'w, 's, ChildOf>,
+    removed_children: RemovedComponents<'w, 's, Children>,
+    ignored: Query<'w, 's, (), Or<(With<Node>, With<Observer>)>>,
+    selection: Res<'w, EditorSelection>,
+    expansion: Res<'w, SceneTreeExpansion>,
+    drag: Res<'w, SceneTreeDrag>,
+    filter: Res<'w, SceneTreeFilter>,
+}
+
+impl SceneTreeChanges<'_, '_> {
+    /// Returns `true` if anything changed since the last call.
+    fn any(&mut self) -> bool {
+        // Always drain every removal reader, so that old removals are not reported again.
+        let removed_names = self.removed_names.read().count() > 0;
+        let removed_hierarchy = self
+            .removed_parents
+            .read()
+            .chain(self.removed_children.read())
+            .filter(|entity| !self.ignored.contains(*entity))
+            .count()
+            > 0;
+
+        removed_names
+            || removed_hierarchy
+            || !self.changed_names.is_empty()
+            || !self.changed_parents.is_empty()
+            || !self.changed_children.is_empty()
+            || self.selection.is_changed()
+            || self.expansion.is_changed()
+            || self.drag.is_changed()
+            || self.filter.is_changed()
+    }
+}
+
+/// Read-only access to the scene hierarchy shown in the tree.
+#[derive(SystemParam)]
+struct SceneHierarchy<'w, 's> {
+    roots: Query<'w, 's, Entity, Without<ChildOf>>,
+    names: Query<'w, 's, &'static Name>,
+    children: Query<'w, 's, &'static Children>,
+    parents: Query<'w, 's, &'static ChildOf>,
+    entities: Query<'w, 's, EntityRef<'static>>,
+    type_registry: Res<'w, AppTypeRegistry>,
+}
+
+/// A row of the tree, before it is turned into UI.
+struct SceneTreeRow {
+    entity: Entity,
+    level: usize,
+    has_children: bool,
+    expanded: bool,
+}
+
+impl SceneHierarchy<'_, '_> {
+    /// Flatten the expanded parts of the scene hierarchy into a list of rows, in display order.
+    ///
+    /// While the filter is not empty, only the entities matching it and their ancestors are shown,
+    /// regardless of which rows are expanded.
+    fn rows(&self, expansion: &SceneTreeExpansion, filter: &SceneTreeFilter) -> Vec<SceneTreeRow> {
+        let shown = (!filter.is_empty()).then(|| self.filtered_entities(filter));
+
+        let mut rows = Vec::new();
+        for root in &self.roots {
+            if shown.as_ref().is_some_and(|shown| !shown.contains(&root)) {
+                continue;
+            }
+            // Only show hierarchies which contain at least one named entity. This keeps editor
+            // internals such as UI nodes, cameras and observers out of the tree, while still
+            // showing things like unnamed scene roots of loaded GLTF files.
+            if self.names.contains(root)
+                || self
+                    .children
+                    .iter_descendants(root)
+                    .any(|entity| self.names.contains(entity))
+            {
+                self.push_rows(root, 0, expansion, shown.as_ref(), &mut rows);
+            }
+        }
+        rows
+    }
+
+    /// Collect the entities matching the filter, together with all of their ancestors.
+    fn filtered_entities(&self, filter: &SceneTreeFilter) -> EntityHashSet {
+        // Every component term matches all registered components with that short type name.
+        let type_registry = self.type_registry.read();
+        let component_types: Vec<Vec<_>> = filter
+            .component_names()
+            .iter()
+            .map(|component_name| {
+                type_registry
+                    .iter_with_data::<ReflectComponent>()
+                    .map(|(registration, _)| registration)
+                    .filter(|registration| {
+                        registration
+                            .type_info()
+                            .type_path_table()
+                            .short_path()
+                            .eq_ignore_ascii_case(component_name)
+                    })
+                    .map(|registration| registration.type_id())
+                    .collect()
+            })
+            .collect();
+
+        let mut shown = EntityHashSet::default();
+        for entity_ref in &self.entities {
+            let entity = entity_ref.id();
+            let name_matches = match self.names.get(entity) {
+                Ok(name) => filter.matches_name(name),
+                Err(_) => filter.matches_name(&format!("Entity {entity}")),
+            };
+            let components_match = component_types.iter().all(|types| {
+                types
+                    .iter()
+                    .any(|type_id| entity_ref.contains_type_id(*type_id))
+            });
+
+            if name_matches && components_match && shown.insert(entity) {
+                for ancestor in self.parents.iter_ancestors(entity) {
+                    // The rest of the ancestors have been added already.
+                    if !shown.insert(ancestor) {
+                        break;
+                    }
+                }
+            }
+        }
+        shown
+    }
+
+    /// Push the row of an entity and, if it is expanded, the rows of all its shown descendants.
+    fn push_rows(
+        &self,
+        entity: Entity,
+        level: usize,
+        expansion: &SceneTreeExpansion,
+        shown: Option<&EntityHashSet>,
+        rows: &mut Vec<SceneTreeRow>,
+    ) {
+        let is_shown = |child: &Entity| shown.is_none_or(|shown| shown.contains(child));
+        let children = self.children.get(entity).ok();
+        let has_children = children.is_some_and(|children| children.iter().any(|c| is_shown(&c)));
+        let expanded = has_children && (shown.is_some() || expansion.is_expanded(entity));
+
+        rows.push(SceneTreeRow {
+            entity,
+            level,
+            has_children,
+            expanded,
+        });
+
+        if expanded && let Some(children) = children {
+            for child in children.iter().filter(is_shown) {
+                self.push_rows(child, level + 1, expansion, shown, rows);
+            }
+        }
+    }
 }
 
 fn update_scene_tree(
-    scene_trees: Query<Entity, With<SceneTreeRoot>>,
-    scene_entities: Query<(Entity, &Name)>,
-    selection: Res<EditorSelection>,
+    scene_trees: Query<(Entity, Ref<SceneTreeRoot>)>,
+    scroll_boxes: Query<(Ref<ScrollBox>, Ref<ComputedNode>)>,
+    parents: Query<&ChildOf>,
+    hierarchy: SceneHierarchy,
+    mut changes: SceneTreeChanges,
     mut commands: Commands,
 ) {
-    for scene_tree in &scene_trees {
-        let tree_rows: Template = scene_entities
-            .iter()
-            .flat_map(|(entity, name)| scene_tree_row_for_entity(entity, name, &selection, 0))
-            .collect();
+    let scene_changed = changes.any();
+    let mut rows = None;
+
+    for (scene_tree, root) in &scene_trees {
+        let Some((scroll_box, scroll_box_node)) = parents
+            .iter_ancestors(scene_tree)
+            .find_map(|entity| scroll_boxes.get(entity).ok())
+        else {
+            continue;
+        };
+
+        if !(scene_changed
+            || root.is_added()
+            || scroll_box.is_changed()
+            || scroll_box_node.is_changed())
+        {
+            continue;
+        }
+
+        // Flatten the hierarchy at most once per frame, no matter how many trees are shown.
+        let rows = rows.get_or_insert_with(|| hierarchy.rows(&changes.expansion, &changes.filter));
+
+        // Only materialize the rows which are currently scrolled into view.
+        let viewport_height = scroll_box_node.size().y * scroll_box_node.inverse_scale_factor();
+        let visible_rows = (viewport_height / ROW_HEIGHT).ceil() as usize + 1;
+        let first_row =
+            ((scroll_box.scroll_offset().y - TREE_PADDING) / ROW_HEIGHT).max(0.0) as usize;
+        let first_row = first_row.min(rows.len().saturating_sub(visible_rows));
+        let last_row = (first_row + visible_rows).min(rows.len());
+
+        // Stand-ins for the rows above and below the visible ones keep the content height, and
+        // therefore the scroll bar, consistent with the full tree.
+        let mut tree_rows = template! {
+            rows_above: Node {
+                height: Val::Px(first_row as f32 * ROW_HEIGHT),
+                flex_shrink: 0.0,
+                ..default()
+            };
+        };
+        tree_rows.extend(rows[first_row..last_row].iter().flat_map(|row| {
+            scene_tree_row_for_entity(
+                row.entity,
+                hierarchy.names.get(row.entity).ok(),
+                row.has_children,
+                row.expanded,
+                &changes.selection,
+                changes.drag.drop_area(row.entity),
+                row.level,
+            )
+        }));
+        tree_rows.extend(template! {
+            rows_below: Node {
+                height: Val::Px((rows.len() - last_row) as f32 * ROW_HEIGHT),
+                flex_shrink: 0.0,
+                ..default()
+            };
+        });
 
         commands.entity(scene_tree).build_children(tree_rows);
     }
@@ -59,8 +472,11 @@ fn update_scene_tree(
 
 fn scene_tree_row_for_entity(
     entity: Entity,
-    name: &Name,
+    name: Option<&Name>,
+    has_children: bool,
+    expanded: bool,
     selection: &EditorSelection,
+    drop_area: Option<RowDropArea>,
     level: usize,
 ) -> Template {
     let selection_handler =
@@ -80,34 +496,146 @@ fn scene_tree_row_for_entity(
             }
         };
 
+    let expansion_handler =
+        move |mut trigger: On<Pointer<Click>>, mut expansion: ResMut<SceneTreeExpansion>| {
+            if trigger.button != PointerButton::Primary || !has_children {
+                return;
+            }
+
+            trigger.propagate(false);
+            expansion.toggle(entity);
+        };
+
+    let drag_start_handler = move |mut trigger: On<Pointer<DragStart>>,
+                                   mut drag: ResMut<SceneTreeDrag>| {
+        if trigger.button != PointerButton::Primary {
+            return;
+        }
+
+        trigger.propagate(false);
+        drag.dragged = Some(entity);
+    };
+
+    let drag_over_handler = move |mut trigger: On<Pointer<DragOver>>,
+                                  rows: Query<(&ComputedNode, &UiGlobalTransform)>,
+                                  parents: Query<&ChildOf>,
+                                  mut drag: ResMut<SceneTreeDrag>| {
+        trigger.propagate(false);
+        let Some(dragged) = drag.dragged else {
+            return;
+        };
+        let Ok((node, global_transform)) = rows.get(trigger.target()) else {
+            return;
+        };
+
+        // The cursor position is in logical pixels, while UI layout is in physical pixels.
+        let scale = node.inverse_scale_factor();
+        let rect =
+            Rect::from_center_size(global_transform.translation * scale, node.size() * scale);
+        let target = can_drop_onto(dragged, entity, &parents).then(|| {
+            (
+                entity,
+                get_row_drop_area(&rect, &trigger.pointer_location.position),
+            )
+        });
+
+        // Only touch the resource when the target changes, as that rebuilds the tree.
+        if drag.target != target {
+            drag.target = target;
+        }
+    };
+
+    let drop_handler = move |mut trigger: On<Pointer<DragDrop>>,
+                             drag: Res<SceneTreeDrag>,
+                             mut commands: Commands| {
+        trigger.propagate(false);
+        if let (Some(dragged), Some(target)) = (drag.dragged, drag.target) {
+            commands.run_system_cached_with(move_entity, (dragged, Some(target)));
+        }
+    };
+
     let indentation_px = level * 20;
 
+    // A line above or below the row shows where a dragged entity would be inserted as a sibling,
+    // highlighting the whole row shows that it would become a child.
+    let drop_indicator: Color = tailwind::BLUE_400.into();
+    let border_color = BorderColor {
+        top: if drop_area == Some(RowDropArea::Above) {
+            drop_indicator
+        } else {
+            Color::NONE
+        },
+        bottom: if drop_area == Some(RowDropArea::Below) {
+            drop_indicator
+        } else {
+            Color::NONE
+        },
+        ..BorderColor::all(Color::NONE)
+    };
+    let background_color = if drop_area == Some(RowDropArea::Onto) {
+        tailwind::BLUE_900.into()
+    } else if selection.contains(entity) {
+        tailwind::BLUE_600.into()
+    } else {
+        Color::NONE
+    };
+
+    let chevron = match (has_children, expanded) {
+        (false, _) => "",
+        (true, false) => "▶",
+        (true, true) => "▼",
+    };
+
+    let label = name.map_or_else(|| format!("Entity {entity}"), ToString::to_string);
+    let label_color = match (selection.contains(entity), name.is_some()) {
+        (true, _) => Color::WHITE,
+        (false, true) => tailwind::NEUTRAL_200.into(),
+        (false, false) => tailwind::NEUTRAL_400.into(),
+    };
+
     template! {
         {entity}: (
             Node {
-                padding: UiRect::new(Val::Px(4.0 + indentation_px as f32), Val::Px(4.0), Val::Px(2.0), Val::Px(2.0)),
+                height: Val::Px(ROW_HEIGHT),
+                flex_shrink: 0.0,
+                padding: UiRect::new(Val::Px(4.0 + indentation_px as f32), Val::Px(4.0), Val::Px(1.0), Val::Px(1.0)),
+                border: UiRect::vertical(Val::Px(1.0)),
                 align_items: AlignItems::Center,
                 flex_direction: FlexDirection::Row,
                 ..default()
             },
             BorderRadius::all(Val::Px(4.0)),
-            BackgroundColor(if selection.contains(entity) { tailwind::BLUE_600.into() } else { Color::NONE }),
+            BackgroundColor(background_color),
+            border_color,
         ) => [
             on(selection_handler);
-            // Indentation spacer
+            on(drag_start_handler);
+            on(drag_over_handler);
+            on(drop_handler);
+            // Expand/collapse toggle, doubling as indentation spacer for leaf entities
             (
                 Node {
                     width: Val::Px(16.0),
                     height: Val::Px(16.0),
                     margin: UiRect::right(Val::Px(4.0)),
+                    align_items: AlignItems::Center,
+                    justify_content: JustifyContent::Center,
                     ..default()
                 },
-            );
+            ) => [
+                on(expansion_handler);
+                (
+                    Text(chevron.into()),
+                    TextFont::from_font_size(10.0),
+                    TextColor(tailwind::NEUTRAL_300.into()),
+                    Pickable::IGNORE,
+                );
+            ];
             // Entity name
             (
-                Text(name.into()),
+                Text(label),
                 TextFont::from_font_size(12.0),
-                TextColor(if selection.contains(entity) { Color::WHITE } else { tailwind::NEUTRAL_200.into() }),
+                TextColor(label_color),
                 Pickable::IGNORE,
             );
         ];
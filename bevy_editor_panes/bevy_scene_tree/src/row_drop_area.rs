@@ -0,0 +1,74 @@
+//! Scene tree row drop area detection
+//!
+//! Given the rect of a tree row and a cursor position, this module determines where an entity
+//! dragged onto that row should go. There are 3 possible drop areas: Above, Onto and Below.
+//! The top and bottom quarters of the row insert the dragged entity as a sibling before or after
+//! the row's entity, the middle half makes it a child of the row's entity.
+//!
+//! ```txt
+//! ____________
+//! |  Above   |
+//! |----------|
+//! |   Onto   |
+//! |----------|
+//! |  Below   |
+//! |__________|
+//! ```
+
+use bevy::prelude::*;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum RowDropArea {
+    Above,
+    Onto,
+    Below,
+}
+
+// The part of the row height taken by each of the Above and Below areas
+const EDGE_SIZE: f32 = 1. / 4.;
+
+pub(crate) fn get_row_drop_area(rect: &Rect, cursor: &Vec2) -> RowDropArea {
+    let edge = rect.height() * EDGE_SIZE;
+
+    if cursor.y < rect.min.y + edge {
+        RowDropArea::Above
+    } else if cursor.y > rect.max.y - edge {
+        RowDropArea::Below
+    } else {
+        RowDropArea::Onto
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EPSILON: f32 = 0.0001;
+
+    #[test]
+    fn get_row_drop_area_tests() {
+        let row_rect = Rect::from_corners(Vec2::new(0., 20.), Vec2::new(100., 40.));
+
+        let positions = [
+            // Above
+            (Vec2::new(50., 20.), RowDropArea::Above),
+            (Vec2::new(50., 25. - EPSILON), RowDropArea::Above),
+            // Onto
+            (Vec2::new(50., 25. + EPSILON), RowDropArea::Onto),
+            (Vec2::new(50., 30.), RowDropArea::Onto),
+            (Vec2::new(0., 30.), RowDropArea::Onto),
+            (Vec2::new(50., 35. - EPSILON), RowDropArea::Onto),
+            // Below
+            (Vec2::new(50., 35. + EPSILON), RowDropArea::Below),
+            (Vec2::new(50., 40.), RowDropArea::Below),
+        ];
+
+        for (position, expected) in positions.iter() {
+            assert_eq!(
+                get_row_drop_area(&row_rect, position),
+                *expected,
+                "{position:?} -> {expected:?}"
+            );
+        }
+    }
+}
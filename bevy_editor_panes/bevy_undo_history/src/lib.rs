@@ -0,0 +1,209 @@
+//! A pane listing the undo history, from the initial state to the last change that can be redone.
+//!
+//! Every change of the [`ChangeChain`] is listed with its [`EditorChange::debug_text`], which helps
+//! checking what custom changes record. The current state is highlighted, and clicking an entry
+//! undoes or redoes the changes up to it.
+//!
+//! [`EditorChange::debug_text`]: bevy_undo::EditorChange::debug_text
+
+use std::sync::Arc;
+
+use bevy::prelude::*;
+use bevy_editor_styles::Theme;
+use bevy_pane_layout::prelude::{PaneAppExt, PaneStructure};
+use bevy_scroll_box::{ScrollBoxPlugin, spawn_scroll_box};
+use bevy_undo::{ChangeChain, EditorChange, UndoPlugin, UndoRedo};
+
+/// Plugin for the editor undo history pane.
+pub struct UndoHistoryPlugin;
+
+impl Plugin for UndoHistoryPlugin {
+    fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<UndoPlugin>() {
+            app.add_plugins(UndoPlugin);
+        }
+        if !app.is_plugin_added::<ScrollBoxPlugin>() {
+            app.add_plugins(ScrollBoxPlugin);
+        }
+
+        app.register_pane("Undo History", setup_pane).add_systems(
+            Update,
+            update_undo_history.run_if(
+                resource_changed::<ChangeChain>.or(any_match_filter::<Added<UndoHistoryRoot>>),
+            ),
+        );
+    }
+}
+
+/// Root UI node of the undo history. The entries are built as its children.
+#[derive(Component)]
+struct UndoHistoryRoot;
+
+/// An entry of the undo history, moving to the state after the given number of changes when
+/// clicked.
+#[derive(Component)]
+struct HistoryEntry(usize);
+
+/// Tells cheaply whether the [`ChangeChain`] changed: recording, merging, undoing or redoing a
+/// change changes the length or the last change of its stacks.
+#[derive(Default)]
+struct HistoryKey {
+    lengths: (usize, usize),
+    last_changes: [Option<Arc<dyn EditorChange + Send + Sync>>; 2],
+}
+
+impl HistoryKey {
+    fn new(change_chain: &ChangeChain) -> Self {
+        Self {
+            lengths: (
+                change_chain.changes.len(),
+                change_chain.changes_for_redo.len(),
+            ),
+            last_changes: [
+                change_chain.changes.last().cloned(),
+                change_chain.changes_for_redo.last().cloned(),
+            ],
+        }
+    }
+}
+
+impl PartialEq for HistoryKey {
+    fn eq(&self, other: &Self) -> bool {
+        self.lengths == other.lengths
+            && self
+                .last_changes
+                .iter()
+                .zip(&other.last_changes)
+                .all(|(a, b)| match (a, b) {
+                    (Some(a), Some(b)) => Arc::ptr_eq(a, b),
+                    (a, b) => a.is_none() && b.is_none(),
+                })
+    }
+}
+
+/// The undo history, as shown in the pane.
+#[derive(PartialEq, Default)]
+struct History {
+    /// The description of every change, oldest first, including the changes that can be redone.
+    changes: Vec<String>,
+    /// The number of changes applied to the world.
+    applied: usize,
+}
+
+fn setup_pane(pane: In<PaneStructure>, theme: Res<Theme>, mut commands: Commands) {
+    commands.entity(pane.content).insert(Node {
+        flex_direction: FlexDirection::Column,
+        flex_grow: 1.0,
+        ..default()
+    });
+
+    spawn_scroll_box(
+        &mut commands,
+        &theme,
+        Overflow::scroll_y(),
+        Some(|commands: &mut Commands, content: Entity| {
+            commands.spawn((
+                UndoHistoryRoot,
+                Node {
+                    flex_direction: FlexDirection::Column,
+                    width: Val::Percent(100.0),
+                    padding: UiRect::all(Val::Px(8.0)),
+                    row_gap: Val::Px(2.0),
+                    ..default()
+                },
+                ChildOf(content),
+            ));
+        }),
+    )
+    .insert(ChildOf(pane.content));
+}
+
+fn update_undo_history(
+    roots: Query<Entity, With<UndoHistoryRoot>>,
+    new_roots: Query<(), Added<UndoHistoryRoot>>,
+    change_chain: Res<ChangeChain>,
+    theme: Res<Theme>,
+    mut shown: Local<History>,
+    mut shown_key: Local<HistoryKey>,
+    mut commands: Commands,
+) {
+    let key = HistoryKey::new(&change_chain);
+    if key == *shown_key && new_roots.is_empty() {
+        return;
+    }
+    *shown_key = key;
+
+    // The redo stack is popped from its end, so the next change to redo is its last one.
+    let history = History {
+        changes: change_chain
+            .changes
+            .iter()
+            .chain(change_chain.changes_for_redo.iter().rev())
+            .map(|change| change.debug_text())
+            .collect(),
+        applied: change_chain.changes.len(),
+    };
+    // Avoid rebuilding the list, and resetting hover states, when nothing changed.
+    if history == *shown && new_roots.is_empty() {
+        return;
+    }
+
+    for root in &roots {
+        commands.entity(root).despawn_children();
+        spawn_entry(&mut commands, &theme, "Initial state", 0, &history, root);
+        for (index, change) in history.changes.iter().enumerate() {
+            spawn_entry(&mut commands, &theme, change, index + 1, &history, root);
+        }
+    }
+    *shown = history;
+}
+
+/// Spawn the entry moving to the state after `position` changes.
+fn spawn_entry(
+    commands: &mut Commands,
+    theme: &Theme,
+    text: &str,
+    position: usize,
+    history: &History,
+    parent: Entity,
+) {
+    let (background, text_color) = match position.cmp(&history.applied) {
+        std::cmp::Ordering::Less => (BackgroundColor(Color::NONE), theme.text.text_color),
+        std::cmp::Ordering::Equal => (theme.pane.header_background_color, theme.text.high_priority),
+        std::cmp::Ordering::Greater => (BackgroundColor(Color::NONE), theme.text.low_priority),
+    };
+
+    commands
+        .spawn((
+            HistoryEntry(position),
+            Node {
+                padding: UiRect::axes(Val::Px(6.0), Val::Px(3.0)),
+                ..default()
+            },
+            background,
+            theme.general.border_radius,
+            ChildOf(parent),
+        ))
+        .observe(jump_to_entry)
+        .with_child((
+            Text::new(text),
+            TextFont {
+                font: theme.text.font.clone(),
+                font_size: 12.0,
+                ..default()
+            },
+            TextColor(text_color),
+            Pickable::IGNORE,
+        ));
+}
+
+/// Undo or redo the changes up to the clicked entry.
+fn jump_to_entry(
+    trigger: On<Pointer<Click>>,
+    entries: Query<&HistoryEntry>,
+    mut undo_redo: EventWriter<UndoRedo>,
+) {
+    if let Ok(&HistoryEntry(position)) = entries.get(trigger.target()) {
+        undo_redo.write(UndoRedo::JumpTo(position));
+    }
+}
@@ -12,10 +12,19 @@ pub struct ContextMenuPlugin;
 
 impl Plugin for ContextMenuPlugin {
     fn build(&self, app: &mut App) {
-        app.add_observer(on_secondary_button_down_entity_with_context_menu);
+        app.add_observer(on_secondary_button_down_entity_with_context_menu)
+            .add_observer(on_open_context_menu);
     }
 }
 
+/// Open the [`ContextMenu`] of the entity this event is triggered on, e.g. when a menu button is
+/// clicked.
+#[derive(EntityEvent, Clone, Copy)]
+pub struct OpenContextMenu {
+    /// Where the top left corner of the menu is placed, in logical pixels.
+    pub position: Vec2,
+}
+
 fn on_secondary_button_down_entity_with_context_menu(
     mut trigger: On<Pointer<Release>>,
     mut commands: Commands,
@@ -33,8 +42,36 @@ fn on_secondary_button_down_entity_with_context_menu(
 
     trigger.propagate(false);
 
-    let event = trigger.event();
+    open_context_menu(
+        &mut commands,
+        &theme,
+        menu,
+        trigger.event().pointer_location.position,
+        target,
+    );
+}
+
+fn on_open_context_menu(
+    trigger: On<OpenContextMenu>,
+    mut commands: Commands,
+    query: Query<&ContextMenu>,
+    theme: Res<Theme>,
+) {
+    let target = trigger.target();
+    let Ok(menu) = query.get(target) else {
+        return;
+    };
+
+    open_context_menu(&mut commands, &theme, menu, trigger.position, target);
+}
 
+fn open_context_menu(
+    commands: &mut Commands,
+    theme: &Theme,
+    menu: &ContextMenu,
+    position: Vec2,
+    target: Entity,
+) {
     // Prevent all other entities from being picked by placing a node over the entire window.
     let root = commands
         .spawn((
@@ -50,18 +87,12 @@ fn on_secondary_button_down_entity_with_context_menu(
         })
         .id();
 
-    spawn_context_menu(
-        &mut commands,
-        &theme,
-        menu,
-        event.pointer_location.position,
-        target,
-    )
-    .observe(|mut trigger: On<Pointer<Press>>| {
-        // Prevent the context menu root from despawning the context menu when clicking on the menu
-        trigger.propagate(false);
-    })
-    .insert(ChildOf(root));
+    spawn_context_menu(commands, theme, menu, position, target)
+        .observe(|mut trigger: On<Pointer<Press>>| {
+            // Prevent the context menu root from despawning the context menu when clicking on the menu
+            trigger.propagate(false);
+        })
+        .insert(ChildOf(root));
 }
 
 /// Entities with this component will have a context menu.
@@ -3,9 +3,11 @@
 //! This runs along the top of the screen and provides a list of options to the user,
 //! such as "File", "Edit", "View", etc.
 
+use std::time::Duration;
+
 use bevy::prelude::*;
 
-use bevy_editor_styles::Theme;
+use bevy_editor_styles::{Theme, colors::EditorColors};
 
 /// The root node for the Footer bar.
 #[derive(Component)]
@@ -16,10 +18,48 @@ pub struct FooterBarPlugin;
 
 impl Plugin for FooterBarPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Startup, footer_setup.in_set(FooterBarSet));
+        app.add_event::<FooterMessage>()
+            .add_systems(Startup, footer_setup.in_set(FooterBarSet))
+            .add_systems(Update, show_footer_messages);
+    }
+}
+
+/// A message to show in the Footer bar, such as the result of an operation.
+///
+/// The latest message is shown until it expires after a few seconds.
+#[derive(Event, BufferedEvent, Clone, Debug)]
+pub struct FooterMessage {
+    /// The text of the message.
+    pub text: String,
+    /// Whether the message reports an error, which is highlighted.
+    pub is_error: bool,
+}
+
+impl FooterMessage {
+    /// Creates an informative message.
+    pub fn info(text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            is_error: false,
+        }
+    }
+
+    /// Creates a message reporting an error.
+    pub fn error(text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            is_error: true,
+        }
     }
 }
 
+/// The text showing the latest [`FooterMessage`].
+#[derive(Component)]
+struct FooterMessageText;
+
+/// How long a [`FooterMessage`] is shown.
+const MESSAGE_DURATION: Duration = Duration::from_secs(5);
+
 /// System Set to set up the Footer bar.
 #[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
 pub struct FooterBarSet;
@@ -45,13 +85,24 @@ fn footer_setup(
             theme.general.background_color,
         ))
         .with_children(|parent| {
-            parent.spawn(Node {
-                width: Val::Percent(50.0),
-                height: Val::Percent(100.0),
-                justify_content: JustifyContent::FlexStart,
-                align_items: AlignItems::Center,
-                ..Default::default()
-            });
+            parent
+                .spawn(Node {
+                    width: Val::Percent(50.0),
+                    height: Val::Percent(100.0),
+                    justify_content: JustifyContent::FlexStart,
+                    align_items: AlignItems::Center,
+                    ..Default::default()
+                })
+                .with_child((
+                    Text::default(),
+                    TextFont {
+                        font: theme.text.font.clone(),
+                        font_size: 10.,
+                        ..default()
+                    },
+                    TextColor(theme.text.text_color),
+                    FooterMessageText,
+                ));
             parent
                 .spawn(Node {
                     width: Val::Percent(50.0),
@@ -72,3 +123,29 @@ fn footer_setup(
                 ));
         });
 }
+
+/// Show the latest [`FooterMessage`], and clear it once it expired.
+fn show_footer_messages(
+    mut messages: EventReader<FooterMessage>,
+    mut texts: Query<(&mut Text, &mut TextColor), With<FooterMessageText>>,
+    theme: Res<Theme>,
+    time: Res<Time>,
+    mut shown_at: Local<Option<Duration>>,
+) {
+    if let Some(message) = messages.read().last() {
+        for (mut text, mut color) in &mut texts {
+            text.0.clone_from(&message.text);
+            color.0 = if message.is_error {
+                EditorColors::ERROR
+            } else {
+                theme.text.text_color
+            };
+        }
+        *shown_at = Some(time.elapsed());
+    } else if shown_at.is_some_and(|shown_at| time.elapsed() - shown_at >= MESSAGE_DURATION) {
+        for (mut text, _) in &mut texts {
+            text.0.clear();
+        }
+        *shown_at = None;
+    }
+}
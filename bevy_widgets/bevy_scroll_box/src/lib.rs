@@ -31,6 +31,13 @@ impl ScrollBox {
     pub fn scroll_to_top(&mut self) {
         self.position = ScrollPosition::default();
     }
+
+    /// How far the content is currently scrolled, in logical pixels.
+    ///
+    /// Both axes are positive, with [`Vec2::ZERO`] being the top left corner of the content.
+    pub fn scroll_offset(&self) -> Vec2 {
+        Vec2::new(-self.position.x, -self.position.y)
+    }
 }
 
 /// Represents the content within a [`ScrollBox`].
@@ -3,8 +3,8 @@
 //! Toolbars are a common UI element in many applications, providing quick access to frequently used commands,
 //! and typically display small icons with on-hover tooltips.
 
-use bevy::prelude::*;
-use bevy_editor_core::selection::EditorSelection;
+use bevy::{prelude::*, ui::InteractionDisabled};
+use bevy_editor_core::{actions::ActionWorldExt, selection::EditorSelection};
 use bevy_editor_styles::{Theme, colors::EditorColors};
 use bevy_transform_gizmos::{GizmoMode, TransformGizmoSettings};
 
@@ -19,6 +19,7 @@ impl Plugin for ToolbarPlugin {
                 (
                     handle_toolbar_actions,
                     update_button_colors,
+                    update_disabled_buttons,
                     sync_gizmo_mode,
                 ),
             )
@@ -35,6 +36,8 @@ pub struct ToolbarSet;
 pub struct ToolbarNode;
 
 /// Marker for toolbar tool buttons.
+///
+/// Buttons with [`InteractionDisabled`] are dimmed and can't be pressed.
 #[derive(Component)]
 pub struct ToolbarButton {
     /// The tool associated with this button.
@@ -211,7 +214,12 @@ fn setup_toolbar(
 
 fn handle_toolbar_actions(
     mut interactions: Query<
-        (&Interaction, &ToolbarButton, &mut BackgroundColor),
+        (
+            &Interaction,
+            &ToolbarButton,
+            &mut BackgroundColor,
+            Has<InteractionDisabled>,
+        ),
         (Changed<Interaction>, With<Button>),
     >,
     theme: Res<Theme>,
@@ -220,9 +228,25 @@ fn handle_toolbar_actions(
     mut commands: Commands,
     _selection: Res<EditorSelection>,
 ) {
-    for (interaction, toolbar_button, mut background) in &mut interactions {
+    for (interaction, toolbar_button, mut background, disabled) in &mut interactions {
+        if disabled && *interaction != Interaction::None {
+            continue;
+        }
         match *interaction {
             Interaction::Pressed => {
+                // Undo and redo are one-off actions rather than tools
+                let action = match toolbar_button.tool {
+                    EditorTool::Undo => Some("undo"),
+                    EditorTool::Redo => Some("redo"),
+                    _ => None,
+                };
+                if let Some(action) = action {
+                    commands.queue(move |world: &mut World| {
+                        world.run_action(action);
+                    });
+                    continue;
+                }
+
                 active_tool.0 = toolbar_button.tool;
 
                 // Sync toolbar actions with gizmo modes
@@ -294,6 +318,25 @@ fn update_button_colors(
     }
 }
 
+/// Dim the labels of the disabled buttons.
+fn update_disabled_buttons(
+    buttons: Query<(Has<InteractionDisabled>, &Children), With<ToolbarButton>>,
+    mut texts: Query<&mut TextColor>,
+    theme: Res<Theme>,
+) {
+    for (disabled, children) in &buttons {
+        let color = if disabled {
+            theme.text.low_priority
+        } else {
+            theme.text.text_color
+        };
+        let mut texts = texts.iter_many_mut(children);
+        while let Some(mut text_color) = texts.fetch_next() {
+            text_color.set_if_neq(TextColor(color));
+        }
+    }
+}
+
 fn sync_gizmo_mode(
     gizmo_settings: Res<TransformGizmoSettings>,
     mut active_tool: ResMut<ActiveTool>,
@@ -38,12 +38,15 @@ use bevy_transform_gizmos::{GizmoTransformable, TransformGizmoPlugin};
 use bevy_2d_viewport::Viewport2dPanePlugin;
 use bevy_3d_viewport::Viewport3dPanePlugin;
 use bevy_asset_browser::AssetBrowserPanePlugin;
+use bevy_asset_inspector::AssetInspectorPlugin;
 
 use crate::load_gltf::LoadGltfPlugin;
+use crate::undo::EditorUndoPlugin;
 
 mod load_gltf;
 pub mod project;
 mod ui;
+mod undo;
 
 /// The plugin that handle the bare minimum to run the application
 pub struct RuntimePlugin;
@@ -74,6 +77,7 @@ impl Plugin for EditorPlugin {
                 Viewport3dPanePlugin,
                 ui::EditorUIPlugin,
                 AssetBrowserPanePlugin,
+                AssetInspectorPlugin,
                 LoadGltfPlugin,
                 MeshPickingPlugin,
                 TransformGizmoPlugin,
@@ -82,6 +86,7 @@ impl Plugin for EditorPlugin {
                 TabNavigationPlugin,
                 FeathersPlugin,
             ))
+            .add_plugins(EditorUndoPlugin)
             .insert_resource(WinitSettings {
                 focused_mode: UpdateMode::reactive(Duration::from_secs_f64(1.0 / 60.0)),
                 unfocused_mode: UpdateMode::reactive_low_power(Duration::from_secs(1)),
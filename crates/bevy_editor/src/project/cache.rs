@@ -5,6 +5,8 @@ use super::ProjectInfo;
 
 /// The name of the project cache file
 const CACHE_FILE: &str = "projects.ron";
+/// The name of the pane layout file
+const LAYOUT_FILE: &str = "layout.ron";
 /// The name of the Bevy Editor's cache folder
 const CACHE_FOLDER_NAME: &str = "Bevy Editor";
 
@@ -31,6 +33,11 @@ fn get_cache_folder() -> PathBuf {
     path.join(CACHE_FOLDER_NAME)
 }
 
+/// Get the path of the pane layout file in the cache folder
+pub(super) fn layout_file() -> PathBuf {
+    get_cache_folder().join(LAYOUT_FILE)
+}
+
 /// Load the projects from the cache file
 pub(super) fn load_projects() -> io::Result<Vec<ProjectInfo>> {
     let cache_folder = get_cache_folder();
@@ -96,6 +96,11 @@ pub fn set_project_list(projects: Vec<ProjectInfo>) {
     }
 }
 
+/// Get the path of the file the editor pane layout is saved to.
+pub fn pane_layout_file() -> PathBuf {
+    cache::layout_file()
+}
+
 /// Run a project in editor mode.
 pub fn run_project(project: &ProjectInfo) -> std::io::Result<()> {
     // Make sure the project folder exist
@@ -1,12 +1,19 @@
 use bevy::prelude::*;
 
+use bevy_editor_core::prelude::*;
 use bevy_editor_styles::Theme;
-use bevy_footer_bar::{FooterBarNode, FooterBarPlugin, FooterBarSet};
+use bevy_footer_bar::{FooterBarNode, FooterBarPlugin, FooterBarSet, FooterMessage};
 use bevy_menu_bar::{MenuBarNode, MenuBarPlugin, MenuBarSet};
-use bevy_pane_layout::{PaneLayoutPlugin, PaneLayoutSet, RootPaneLayoutNode};
+use bevy_pane_layout::{
+    PaneLayoutPlugin, PaneLayoutSet, RootPaneLayoutNode,
+    layout::{PaneLayout, PaneLayoutFile},
+    presets::{LayoutPreset, LayoutPresets, reset_pane_layout, spawn_workspace_tabs},
+};
 use bevy_properties_pane::PropertiesPanePlugin;
 use bevy_scene_tree::SceneTreePlugin;
 use bevy_toolbar::{ToolbarNode, ToolbarPlugin, ToolbarSet};
+use bevy_undo::UndoRedoFailed;
+use bevy_undo_history::UndoHistoryPlugin;
 
 /// The Bevy Editor UI Plugin.
 pub struct EditorUIPlugin;
@@ -14,6 +21,7 @@ pub struct EditorUIPlugin;
 impl Plugin for EditorUIPlugin {
     fn build(&self, app: &mut App) {
         app.add_systems(Startup, ui_setup.in_set(UISet))
+            .add_systems(Startup, workspace_tabs_setup.after(MenuBarSet))
             .configure_sets(
                 Startup,
                 (PaneLayoutSet, MenuBarSet, ToolbarSet, FooterBarSet).after(UISet),
@@ -25,7 +33,12 @@ impl Plugin for EditorUIPlugin {
                 FooterBarPlugin,
                 SceneTreePlugin,
                 PropertiesPanePlugin,
-            ));
+                UndoHistoryPlugin,
+            ))
+            .insert_resource(PaneLayoutFile(Some(crate::project::pane_layout_file())))
+            .insert_resource(layout_presets())
+            .register_action("reset-layout", "Reset layout", reset_pane_layout)
+            .add_systems(Update, report_undo_redo_failures);
     }
 }
 
@@ -66,3 +79,100 @@ fn ui_setup(mut commands: Commands, theme: Res<Theme>) {
             parent.spawn(FooterBarNode);
         });
 }
+
+/// The workspaces of the editor, each with a layout suited to a task.
+fn layout_presets() -> LayoutPresets {
+    LayoutPresets::new([
+        LayoutPreset::new("Default", PaneLayout::default()),
+        LayoutPreset::new(
+            "Level Design",
+            PaneLayout::horizontal(
+                1.,
+                [
+                    PaneLayout::vertical(
+                        0.2,
+                        [
+                            PaneLayout::pane("Scene Tree", 0.6),
+                            PaneLayout::pane("Asset Browser", 0.4),
+                        ],
+                    ),
+                    PaneLayout::pane("Viewport 3D", 0.6),
+                    PaneLayout::vertical(
+                        0.2,
+                        [
+                            PaneLayout::pane("Properties", 0.7),
+                            PaneLayout::pane("Resources", 0.3),
+                        ],
+                    ),
+                ],
+            ),
+        ),
+        LayoutPreset::new(
+            "Animation",
+            PaneLayout::vertical(
+                1.,
+                [
+                    PaneLayout::horizontal(
+                        0.7,
+                        [
+                            PaneLayout::pane("Scene Tree", 0.2),
+                            PaneLayout::pane("Viewport 3D", 0.55),
+                            PaneLayout::pane("Properties", 0.25),
+                        ],
+                    ),
+                    PaneLayout::horizontal(
+                        0.3,
+                        [
+                            PaneLayout::pane("Asset Browser", 0.6),
+                            PaneLayout::pane("Undo History", 0.4),
+                        ],
+                    ),
+                ],
+            ),
+        ),
+        LayoutPreset::new(
+            "Debug",
+            PaneLayout::horizontal(
+                1.,
+                [
+                    PaneLayout::vertical(
+                        0.25,
+                        [
+                            PaneLayout::pane("Scene Tree", 0.5),
+                            PaneLayout::pane("Resources", 0.5),
+                        ],
+                    ),
+                    PaneLayout::vertical(
+                        0.5,
+                        [
+                            PaneLayout::pane("Viewport 3D", 0.7),
+                            PaneLayout::pane("Undo History", 0.3),
+                        ],
+                    ),
+                    PaneLayout::vertical(
+                        0.25,
+                        [
+                            PaneLayout::pane("Properties", 0.5),
+                            PaneLayout::pane("Asset Inspector", 0.5),
+                        ],
+                    ),
+                ],
+            ),
+        ),
+    ])
+}
+
+/// Add the workspace tabs to the menu bar, after its menus.
+fn workspace_tabs_setup(mut commands: Commands, menu_bar: Single<Entity, With<MenuBarNode>>) {
+    spawn_workspace_tabs(&mut commands).insert(ChildOf(*menu_bar));
+}
+
+/// Show the undo/redo failures in the footer bar.
+fn report_undo_redo_failures(
+    mut failures: EventReader<UndoRedoFailed>,
+    mut messages: EventWriter<FooterMessage>,
+) {
+    for failure in failures.read() {
+        messages.write(FooterMessage::error(failure.to_string()));
+    }
+}
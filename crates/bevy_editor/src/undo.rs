@@ -0,0 +1,110 @@
+//! Undo and redo in the editor: the "undo" and "redo" actions with their keybindings, the state of
+//! the toolbar buttons, and the recording of the transform gizmo interactions.
+//!
+//! The components of the selectable entities are recorded by automatic undo, through reflection,
+//! so that the components defined by games get undo too.
+
+use std::any::TypeId;
+
+use bevy::{prelude::*, ui::InteractionDisabled};
+use bevy_editor_core::{prelude::*, selection::Selectable};
+use bevy_toolbar::{EditorTool, ToolbarButton};
+use bevy_transform_gizmos::{TransformGizmo, TransformGizmoEvent};
+use bevy_undo::{
+    AppAutoUndo, ChangeChain, DynamicComponentChange, NewChange, OneFrameUndoIgnore,
+    SyncUndoMarkersPlugin, UndoPlugin, UndoRedo,
+};
+
+pub(crate) struct EditorUndoPlugin;
+
+impl Plugin for EditorUndoPlugin {
+    fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<UndoPlugin>() {
+            app.add_plugins(UndoPlugin);
+        }
+
+        app.add_plugins(SyncUndoMarkersPlugin::<Selectable>::default())
+            .auto_reflected_undo_all()
+            .register_action("undo", "Undo", undo_action)
+            .register_action("redo", "Redo", redo_action)
+            .register_keybinding(Keybinding::new("undo", KeyCode::KeyZ).ctrl())
+            .register_keybinding(Keybinding::new("redo", KeyCode::KeyZ).ctrl().shift())
+            .register_keybinding(Keybinding::new("redo", KeyCode::KeyY).ctrl())
+            .add_systems(
+                Update,
+                (
+                    ignore_gizmo_drags,
+                    record_gizmo_transforms,
+                    update_undo_buttons.run_if(
+                        resource_changed::<ChangeChain>
+                            .or(any_match_filter::<Added<ToolbarButton>>),
+                    ),
+                ),
+            );
+    }
+}
+
+fn undo_action(mut undo_redo: EventWriter<UndoRedo>) {
+    undo_redo.write(UndoRedo::Undo);
+}
+
+fn redo_action(mut undo_redo: EventWriter<UndoRedo>) {
+    undo_redo.write(UndoRedo::Redo);
+}
+
+/// Keep automatic undo from recording the transforms of the entities dragged by the transform
+/// gizmo, as the whole drag is recorded once it ends.
+fn ignore_gizmo_drags(
+    gizmos: Query<&TransformGizmo>,
+    selection: Res<EditorSelection>,
+    mut commands: Commands,
+) {
+    if gizmos.iter().any(|gizmo| gizmo.interaction().is_some()) {
+        for entity in selection.iter() {
+            commands
+                .entity(entity)
+                .try_insert(OneFrameUndoIgnore::default());
+        }
+    }
+}
+
+/// Record the transforms changed by the transform gizmo. The entities moved together are recorded
+/// as a single step, as they are sent on the same frame.
+fn record_gizmo_transforms(
+    mut gizmo_events: EventReader<TransformGizmoEvent>,
+    mut new_changes: EventWriter<NewChange>,
+) {
+    new_changes.write_batch(
+        gizmo_events
+            .read()
+            .flat_map(|event| &event.transformed)
+            .map(|transformed| {
+                NewChange::new(DynamicComponentChange {
+                    entity: transformed.entity,
+                    component_type: TypeId::of::<Transform>(),
+                    old_value: transformed.from.to_dynamic(),
+                    new_value: transformed.to.to_dynamic(),
+                })
+            }),
+    );
+}
+
+/// Disable the undo and redo toolbar buttons when there is nothing to undo or redo.
+fn update_undo_buttons(
+    change_chain: Res<ChangeChain>,
+    buttons: Query<(Entity, &ToolbarButton, Has<InteractionDisabled>)>,
+    mut commands: Commands,
+) {
+    for (entity, button, disabled) in &buttons {
+        let enabled = match button.tool {
+            EditorTool::Undo => !change_chain.changes.is_empty(),
+            EditorTool::Redo => !change_chain.changes_for_redo.is_empty(),
+            _ => continue,
+        };
+        if enabled && disabled {
+            commands.entity(entity).remove::<InteractionDisabled>();
+        } else if !enabled && !disabled {
+            commands.entity(entity).insert(InteractionDisabled);
+        }
+    }
+}
@@ -41,6 +41,9 @@ impl Default for Keybindings {
 
 /// A keybinding for an editor [`Action`](crate::actions::Action).
 ///
+/// The modifier keys held must match the keybinding exactly, so that e.g. <kbd>Ctrl</kbd> +
+/// <kbd>Z</kbd> doesn't also trigger on <kbd>Ctrl</kbd> + <kbd>Shift</kbd> + <kbd>Z</kbd>.
+///
 /// # Example
 /// This example binds the "load-gltf" action to <kbd>Ctrl</kbd> + <kbd>L</kbd>.
 /// ```no_run
@@ -108,10 +111,10 @@ fn process_keybindings(world: &mut World) {
             let alt = input.any_pressed([KeyCode::AltLeft, KeyCode::AltRight]);
             let os = input.any_pressed([KeyCode::SuperLeft, KeyCode::SuperRight]);
             for binding in &bindings.list {
-                if (!binding.ctrl || ctrl)
-                    && (!binding.alt || alt)
-                    && (!binding.shift || shift)
-                    && (!binding.os || os)
+                if binding.ctrl == ctrl
+                    && binding.alt == alt
+                    && binding.shift == shift
+                    && binding.os == os
                     && input.just_pressed(binding.key)
                 {
                     world.run_action(&binding.action_id);
@@ -0,0 +1,157 @@
+//! Docking panes by dragging their tab or their header.
+//!
+//! While a pane is dragged, the area of the pane stack under the cursor, as given by
+//! [`get_pane_drop_area`], is highlighted. Dropping the pane on a side splits the pane stack to
+//! dock the pane there. Dropping it in the center adds it to the tabs of the stack when dragged by
+//! its tab, and swaps it with the shown pane of the stack when dragged by its header.
+
+use bevy::prelude::*;
+
+use crate::{
+    PaneHeaderNode, PaneRootNode, PaneStack,
+    handlers::{PaneDrop, move_pane, swap_panes},
+    pane_drop_area::{PaneDropArea, get_pane_drop_area},
+};
+
+/// What a pane is dragged by.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum DragHandle {
+    /// The tab of the pane, in the tab bar of its pane stack.
+    Tab,
+    /// The header of the pane.
+    Header,
+}
+
+/// The pane being dragged, and where it would currently be dropped.
+#[derive(Resource, Default)]
+pub(crate) struct PaneDrag {
+    /// The root of the pane being dragged, and what it is dragged by.
+    pub dragged: Option<(Entity, DragHandle)>,
+    /// The pane stack the pane is over, and the area of the stack.
+    pub target: Option<(Entity, PaneDropArea)>,
+}
+
+/// The overlay showing where a dragged pane would be dropped.
+#[derive(Component)]
+struct DropIndicator;
+
+pub(crate) fn spawn_drop_indicator(commands: &mut Commands) {
+    commands.spawn((
+        DropIndicator,
+        Node {
+            position_type: PositionType::Absolute,
+            display: Display::None,
+            ..default()
+        },
+        BackgroundColor(Color::srgba(0.3, 0.5, 0.9, 0.3)),
+        GlobalZIndex(100),
+        Pickable::IGNORE,
+    ));
+}
+
+/// The rect of a node, in logical pixels like the cursor position.
+fn logical_rect(node: &ComputedNode, transform: &UiGlobalTransform) -> Rect {
+    // UI layout is in physical pixels.
+    let scale = node.inverse_scale_factor();
+    Rect::from_center_size(transform.translation * scale, node.size() * scale)
+}
+
+/// Find the pane stack under the cursor, and the area of the stack, while a pane is dragged.
+pub(crate) fn drag_pane_over(
+    trigger: On<Pointer<Drag>>,
+    stacks: Query<(Entity, &ComputedNode, &UiGlobalTransform), With<PaneStack>>,
+    mut drag: ResMut<PaneDrag>,
+) {
+    if drag.dragged.is_none() {
+        return;
+    }
+    let cursor = trigger.pointer_location.position;
+    let target = stacks.iter().find_map(|(stack, node, transform)| {
+        let rect = logical_rect(node, transform);
+        rect.contains(cursor)
+            .then(|| (stack, get_pane_drop_area(&rect, &cursor)))
+    });
+
+    // Only touch the resource when the target changes, as that updates the indicator.
+    if drag.target != target {
+        drag.target = target;
+    }
+}
+
+/// Dock the dragged pane where it was dropped.
+pub(crate) fn drop_pane(
+    _trigger: On<Pointer<DragEnd>>,
+    mut drag: ResMut<PaneDrag>,
+    mut commands: Commands,
+) {
+    match (drag.dragged, drag.target) {
+        (Some((pane, DragHandle::Header)), Some((stack, PaneDropArea::Center))) => {
+            commands.run_system_cached_with(swap_panes, (pane, stack));
+        }
+        (Some((pane, _)), Some((stack, area))) => {
+            commands.run_system_cached_with(move_pane, (pane, PaneDrop::Area(stack, area)));
+        }
+        _ => {}
+    }
+    *drag = PaneDrag::default();
+}
+
+/// Let the panes be dragged by their header.
+pub(crate) fn make_headers_draggable(
+    headers: Query<Entity, Added<PaneHeaderNode>>,
+    mut commands: Commands,
+) {
+    for header in &headers {
+        commands
+            .entity(header)
+            .observe(
+                |mut trigger: On<Pointer<DragStart>>,
+                 parent_query: Query<&ChildOf>,
+                 pane_root_query: Query<(), With<PaneRootNode>>,
+                 mut drag: ResMut<PaneDrag>| {
+                    if trigger.button != PointerButton::Primary {
+                        return;
+                    }
+                    trigger.propagate(false);
+                    let pane = parent_query
+                        .iter_ancestors(trigger.target())
+                        .find(|entity| pane_root_query.contains(*entity));
+                    if let Some(pane) = pane {
+                        drag.dragged = Some((pane, DragHandle::Header));
+                    }
+                },
+            )
+            .observe(drag_pane_over)
+            .observe(drop_pane);
+    }
+}
+
+/// Show where the dragged pane would be dropped.
+pub(crate) fn update_drop_indicator(
+    drag: Res<PaneDrag>,
+    stacks: Query<(&ComputedNode, &UiGlobalTransform)>,
+    mut indicator: Single<&mut Node, With<DropIndicator>>,
+) {
+    let Some(rect) = drag.target.and_then(|(stack, area)| {
+        let (node, transform) = stacks.get(stack).ok()?;
+        let rect = logical_rect(node, transform);
+        let (min, max) = (rect.min, rect.max);
+        let center = rect.center();
+        Some(match area {
+            PaneDropArea::Center => rect,
+            PaneDropArea::Left => Rect::from_corners(min, Vec2::new(center.x, max.y)),
+            PaneDropArea::Right => Rect::from_corners(Vec2::new(center.x, min.y), max),
+            PaneDropArea::Top => Rect::from_corners(min, Vec2::new(max.x, center.y)),
+            PaneDropArea::Bottom => Rect::from_corners(Vec2::new(min.x, center.y), max),
+        })
+    }) else {
+        indicator.display = Display::None;
+        return;
+    };
+
+    indicator.display = Display::Flex;
+    indicator.left = Val::Px(rect.min.x);
+    indicator.top = Val::Px(rect.min.y);
+    indicator.width = Val::Px(rect.width());
+    indicator.height = Val::Px(rect.height());
+}
@@ -1,30 +1,72 @@
+use std::iter;
+
 use bevy::prelude::*;
 use bevy_editor_styles::Theme;
 
 use crate::{
-    Divider, PaneRootNode, RootPaneLayoutNode, Size,
-    ui::{spawn_divider, spawn_pane, spawn_resize_handle},
+    Divider, PaneRootNode, PaneStack, RootPaneLayoutNode, Size,
+    pane_drop_area::PaneDropArea,
+    ui::{spawn_divider, spawn_pane, spawn_pane_stack, spawn_resize_handle},
 };
 
-pub(crate) fn remove_pane(
-    target: In<Entity>,
-    mut commands: Commands,
-    parent_query: Query<&ChildOf>,
-    children_query: Query<&Children>,
-    root_query: Query<(), With<RootPaneLayoutNode>>,
-    mut size_query: Query<&mut Size>,
-) {
-    // Grab the id of the pane root
-    let target = parent_query.iter_ancestors(*target).nth(1).unwrap();
+/// Where a pane is dropped.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum PaneDrop {
+    /// Onto an area of a pane stack: a new tab in the center, or a split on the sides.
+    Area(Entity, PaneDropArea),
+    /// Next to the tab of a pane.
+    Tab {
+        /// The pane whose tab the pane is dropped onto.
+        pane: Entity,
+        /// Whether the pane goes after the tab, rather than before.
+        after: bool,
+    },
+}
+
+/// Find the pane root of `entity`, which is either a pane root or one of its descendants.
+fn pane_root_of(
+    entity: Entity,
+    parent_query: &Query<&ChildOf>,
+    pane_root_query: &Query<&PaneRootNode>,
+) -> Option<Entity> {
+    iter::once(entity)
+        .chain(parent_query.iter_ancestors(entity))
+        .find(|entity| pane_root_query.contains(*entity))
+}
+
+/// Count the panes of a pane stack.
+fn pane_count(
+    stack: Entity,
+    children_query: &Query<&Children>,
+    pane_root_query: &Query<&PaneRootNode>,
+) -> usize {
+    children_query.get(stack).map_or(0, |children| {
+        children
+            .iter()
+            .filter(|child| pane_root_query.contains(*child))
+            .count()
+    })
+}
 
+/// Remove an area from its divider, along with the resize handle next to it, giving its space to
+/// its neighbours. The only area of a divider is removed along with the divider. The last area is
+/// kept.
+fn remove_area(
+    commands: &mut Commands,
+    target: Entity,
+    parent_query: &Query<&ChildOf>,
+    children_query: &Query<&Children>,
+    root_query: &Query<(), With<RootPaneLayoutNode>>,
+    size_query: &mut Query<&mut Size>,
+) {
     let parent = parent_query.get(target).unwrap().parent();
 
-    // Prevent the removal of the last panel
+    // Prevent the removal of the last area
     if root_query.contains(parent) {
         return;
     }
 
-    // Find the index of this pane among its siblings
+    // Find the index of this area among its siblings
     let siblings = children_query.get(parent).unwrap();
     let index = siblings.iter().position(|entity| entity == target).unwrap();
 
@@ -36,7 +78,18 @@ pub(crate) fn remove_pane(
     let b = siblings.get(index + 2);
 
     match (a, b) {
-        (None, None) => unreachable!(),
+        // The divider isn't cleaned up yet, see `cleanup_divider_single_child`
+        (None, None) => {
+            remove_area(
+                commands,
+                parent,
+                parent_query,
+                children_query,
+                root_query,
+                size_query,
+            );
+            return;
+        }
         (None, Some(e)) | (Some(e), None) => {
             size_query.get_mut(*e).unwrap().0 += size;
         }
@@ -46,11 +99,133 @@ pub(crate) fn remove_pane(
         }
     }
 
-    // Despawn the resize handle next to this pane
+    // Despawn the resize handle next to this area
     let resize_handle_index = if not_first_child { index - 1 } else { 1 };
     commands.entity(siblings[resize_handle_index]).despawn();
-    // Despawn this pane
+    // Despawn this area
+    commands.entity(target).despawn();
+}
+
+/// Insert `new_area` next to the `target` area, splitting it in two along `direction`.
+#[expect(clippy::too_many_arguments)]
+fn split_area(
+    commands: &mut Commands,
+    target: Entity,
+    new_area: Entity,
+    direction: Divider,
+    before: bool,
+    divider_query: &Query<&Divider>,
+    size_query: &mut Query<&mut Size>,
+    children_query: &Query<&Children>,
+    parent_query: &Query<&ChildOf>,
+) {
+    let parent = parent_query.get(target).unwrap().parent();
+
+    // Find the index of this area among its siblings
+    let siblings = children_query.get(parent).unwrap();
+    let index = siblings.iter().position(|entity| entity == target).unwrap();
+
+    // Parent has a matching divider direction
+    let matching_direction = divider_query
+        .get(parent)
+        .map(|parent_divider| *parent_divider == direction)
+        .unwrap_or(false);
+
+    let mut size = size_query.get_mut(target).unwrap();
+    let new_size = if matching_direction { size.0 / 2. } else { 0.5 };
+
+    commands.entity(new_area).insert(Size(new_size));
+    let resize_handle = spawn_resize_handle(commands, direction).id();
+
+    if matching_direction {
+        let (index, areas) = if before {
+            (index, [new_area, resize_handle])
+        } else {
+            (index + 1, [resize_handle, new_area])
+        };
+        commands.entity(parent).insert_children(index, &areas);
+    } else {
+        let areas = if before {
+            [new_area, resize_handle, target]
+        } else {
+            [target, resize_handle, new_area]
+        };
+        let divider = spawn_divider(commands, direction, size.0)
+            .add_children(&areas)
+            .id();
+        commands.entity(parent).insert_children(index, &[divider]);
+    }
+    size.0 = new_size;
+}
+
+/// Close a pane, given its root or one of its descendants. The area of the pane is removed along
+/// with it, unless the pane shares it with other panes as tabs.
+pub(crate) fn remove_pane(
+    target: In<Entity>,
+    mut commands: Commands,
+    parent_query: Query<&ChildOf>,
+    children_query: Query<&Children>,
+    root_query: Query<(), With<RootPaneLayoutNode>>,
+    pane_root_query: Query<&PaneRootNode>,
+    mut size_query: Query<&mut Size>,
+) {
+    // Grab the id of the pane root
+    let Some(target) = pane_root_of(*target, &parent_query, &pane_root_query) else {
+        return;
+    };
+    let stack = parent_query.get(target).unwrap().parent();
+
+    if pane_count(stack, &children_query, &pane_root_query) > 1 {
+        // Another tab of the stack is shown instead.
+        commands.entity(target).despawn();
+        return;
+    }
+
+    remove_area(
+        &mut commands,
+        stack,
+        &parent_query,
+        &children_query,
+        &root_query,
+        &mut size_query,
+    );
+}
+
+/// Change the type of a pane, given its root or one of its descendants, replacing it with a new
+/// pane of the type registered under `name` at the same place.
+pub(crate) fn change_pane_type(
+    In((target, name)): In<(Entity, String)>,
+    mut commands: Commands,
+    theme: Res<Theme>,
+    parent_query: Query<&ChildOf>,
+    children_query: Query<&Children>,
+    pane_root_query: Query<&PaneRootNode>,
+    mut stack_query: Query<&mut PaneStack>,
+) {
+    let Some(target) = pane_root_of(target, &parent_query, &pane_root_query) else {
+        return;
+    };
+    if pane_root_query.get(target).unwrap().name == name {
+        return;
+    }
+
+    let stack = parent_query.get(target).unwrap().parent();
+    let index = children_query
+        .get(stack)
+        .unwrap()
+        .iter()
+        .position(|entity| entity == target)
+        .unwrap();
+
+    let new_pane = spawn_pane(&mut commands, &theme, name).id();
+    commands.entity(stack).insert_children(index, &[new_pane]);
     commands.entity(target).despawn();
+
+    if let Ok(mut stack) = stack_query.get_mut(stack)
+        && stack.active == target
+    {
+        stack.active = new_pane;
+    }
 }
 
 /// Right clicking dividers the pane horizontally
@@ -73,39 +248,300 @@ pub(crate) fn split_pane(
     };
 
     // Grab the id of the pane root
-    let target = parent_query.iter_ancestors(target).nth(1).unwrap();
-
+    let Some(target) = pane_root_of(target, &parent_query, &pane_root_query) else {
+        return;
+    };
     let pane = pane_root_query.get(target).unwrap();
+    let stack = parent_query.get(target).unwrap().parent();
 
-    let parent = parent_query.get(target).unwrap().parent();
+    // TODO The new pane should inherit the state of the existing pane
+    let new_pane = spawn_pane(&mut commands, &theme, &pane.name).id();
+    let new_stack = spawn_pane_stack(&mut commands, 1., &[new_pane], new_pane).id();
 
-    // Find the index of this pane among its siblings
-    let siblings = children_query.get(parent).unwrap();
-    let index = siblings.iter().position(|entity| entity == target).unwrap();
+    split_area(
+        &mut commands,
+        stack,
+        new_stack,
+        divider,
+        false,
+        &divider_query,
+        &mut size_query,
+        &children_query,
+        &parent_query,
+    );
+}
 
-    // Parent has a matching divider direction
-    let matching_direction = divider_query
-        .get(parent)
-        .map(|parent_divider| *parent_divider == divider)
-        .unwrap_or(false);
+/// Move a pane to where it was dropped. The pane stack it leaves is removed if it was its only
+/// pane.
+pub(crate) fn move_pane(
+    In((pane, drop)): In<(Entity, PaneDrop)>,
+    mut commands: Commands,
+    parent_query: Query<&ChildOf>,
+    children_query: Query<&Children>,
+    root_query: Query<(), With<RootPaneLayoutNode>>,
+    pane_root_query: Query<&PaneRootNode>,
+    mut size_query: Query<&mut Size>,
+) {
+    let source = parent_query.get(pane).unwrap().parent();
+    let alone = pane_count(source, &children_query, &pane_root_query) == 1;
 
-    let mut size = size_query.get_mut(target).unwrap();
-    let new_size = if matching_direction { size.0 / 2. } else { 0.5 };
+    let nothing_to_do = match drop {
+        PaneDrop::Area(target, area) => target == source && (area == PaneDropArea::Center || alone),
+        PaneDrop::Tab { pane: tab_pane, .. } => tab_pane == pane,
+    };
+    if nothing_to_do {
+        return;
+    }
 
-    // TODO The new pane should inherit the state of the existing pane
-    let new_pane = spawn_pane(&mut commands, &theme, new_size, &pane.name).id();
+    // Take the pane out of the layout first, so that the drop target is found in the layout
+    // without it.
+    commands.entity(pane).remove::<ChildOf>();
+    if alone {
+        remove_area(
+            &mut commands,
+            source,
+            &parent_query,
+            &children_query,
+            &root_query,
+            &mut size_query,
+        );
+    }
+    commands.run_system_cached_with(insert_pane, (pane, drop));
+}
 
-    let resize_handle = spawn_resize_handle(&mut commands, divider).id();
+/// Swap a pane with the shown pane of another pane stack.
+pub(crate) fn swap_panes(
+    In((pane, target)): In<(Entity, Entity)>,
+    mut commands: Commands,
+    parent_query: Query<&ChildOf>,
+    children_query: Query<&Children>,
+    mut stack_query: Query<&mut PaneStack>,
+) {
+    let source = parent_query.get(pane).unwrap().parent();
+    let Ok([mut source_stack, mut target_stack]) = stack_query.get_many_mut([source, target])
+    else {
+        // The pane is dropped onto its own pane stack.
+        return;
+    };
+    let other = target_stack.active;
 
-    if matching_direction {
-        commands
-            .entity(parent)
-            .insert_children(index + 1, &[resize_handle, new_pane]);
-    } else {
-        let divider = spawn_divider(&mut commands, divider, size.0)
-            .add_children(&[target, resize_handle, new_pane])
-            .id();
-        commands.entity(parent).insert_children(index, &[divider]);
+    let index_of = |stack: Entity, pane: Entity| {
+        children_query
+            .get(stack)
+            .unwrap()
+            .iter()
+            .position(|entity| entity == pane)
+            .unwrap()
+    };
+    let (pane_index, other_index) = (index_of(source, pane), index_of(target, other));
+
+    commands.entity(pane).remove::<ChildOf>();
+    commands.entity(other).remove::<ChildOf>();
+    commands
+        .entity(source)
+        .insert_children(pane_index, &[other]);
+    commands
+        .entity(target)
+        .insert_children(other_index, &[pane]);
+
+    if source_stack.active == pane {
+        source_stack.active = other;
+    }
+    target_stack.active = pane;
+}
+
+/// Insert a pane taken out of the layout by [`move_pane`] where it was dropped.
+fn insert_pane(
+    In((pane, drop)): In<(Entity, PaneDrop)>,
+    mut commands: Commands,
+    divider_query: Query<&Divider>,
+    mut size_query: Query<&mut Size>,
+    children_query: Query<&Children>,
+    parent_query: Query<&ChildOf>,
+) {
+    match drop {
+        PaneDrop::Area(target, PaneDropArea::Center) => {
+            commands
+                .entity(target)
+                .add_child(pane)
+                .insert(PaneStack { active: pane });
+        }
+        PaneDrop::Area(target, area) => {
+            let (direction, before) = match area {
+                PaneDropArea::Left => (Divider::Horizontal, true),
+                PaneDropArea::Right => (Divider::Horizontal, false),
+                PaneDropArea::Top => (Divider::Vertical, true),
+                _ => (Divider::Vertical, false),
+            };
+            let stack = spawn_pane_stack(&mut commands, 1., &[pane], pane).id();
+            split_area(
+                &mut commands,
+                target,
+                stack,
+                direction,
+                before,
+                &divider_query,
+                &mut size_query,
+                &children_query,
+                &parent_query,
+            );
+        }
+        PaneDrop::Tab {
+            pane: tab_pane,
+            after,
+        } => {
+            let stack = parent_query.get(tab_pane).unwrap().parent();
+            let index = children_query
+                .get(stack)
+                .unwrap()
+                .iter()
+                .position(|entity| entity == tab_pane)
+                .unwrap();
+            commands
+                .entity(stack)
+                .insert_children(if after { index + 1 } else { index }, &[pane])
+                .insert(PaneStack { active: pane });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ResizeHandle;
+
+    fn spawn_test_pane(world: &mut World, name: &str) -> Entity {
+        world
+            .spawn(PaneRootNode {
+                name: name.to_string(),
+            })
+            .id()
+    }
+
+    fn spawn_test_stack(world: &mut World, size: f32, panes: &[Entity]) -> Entity {
+        world
+            .spawn((Size(size), PaneStack { active: panes[0] }))
+            .add_children(panes)
+            .id()
+    }
+
+    /// Spawn a divider holding `areas`, with resize handles between them.
+    fn spawn_test_divider(
+        world: &mut World,
+        direction: Divider,
+        size: f32,
+        areas: &[Entity],
+    ) -> Entity {
+        let mut children = Vec::new();
+        for (i, area) in areas.iter().enumerate() {
+            if i > 0 {
+                children.push(world.spawn(ResizeHandle).id());
+            }
+            children.push(*area);
+        }
+        world
+            .spawn((Size(size), direction))
+            .add_children(&children)
+            .id()
+    }
+
+    fn spawn_root(world: &mut World, layout: Entity) {
+        world.spawn(RootPaneLayoutNode).add_child(layout);
+    }
+
+    fn children(world: &World, entity: Entity) -> Vec<Entity> {
+        world
+            .get::<Children>(entity)
+            .map_or(Vec::new(), |children| children.to_vec())
+    }
+
+    fn parent(world: &World, entity: Entity) -> Entity {
+        world.get::<ChildOf>(entity).unwrap().parent()
+    }
+
+    fn size(world: &World, entity: Entity) -> f32 {
+        world.get::<Size>(entity).unwrap().0
+    }
+
+    #[test]
+    fn move_lone_pane_next_to_sibling() {
+        let mut world = World::new();
+        let pane_a = spawn_test_pane(&mut world, "A");
+        let pane_b = spawn_test_pane(&mut world, "B");
+        let stack_a = spawn_test_stack(&mut world, 0.5, &[pane_a]);
+        let stack_b = spawn_test_stack(&mut world, 0.5, &[pane_b]);
+        let divider = spawn_test_divider(&mut world, Divider::Horizontal, 1., &[stack_a, stack_b]);
+        spawn_root(&mut world, divider);
+
+        world
+            .run_system_cached_with(
+                move_pane,
+                (pane_a, PaneDrop::Area(stack_b, PaneDropArea::Right)),
+            )
+            .unwrap();
+        world.flush();
+
+        assert!(world.get_entity(stack_a).is_err());
+        let moved_stack = parent(&world, pane_a);
+        let areas = children(&world, divider);
+        assert_eq!(areas.len(), 3);
+        assert_eq!(areas[0], stack_b);
+        assert!(world.entity(areas[1]).contains::<ResizeHandle>());
+        assert_eq!(areas[2], moved_stack);
+        assert_eq!(size(&world, stack_b), 0.5);
+        assert_eq!(size(&world, moved_stack), 0.5);
+    }
+
+    #[test]
+    fn move_pane_onto_tab_of_same_stack() {
+        let mut world = World::new();
+        let pane_a = spawn_test_pane(&mut world, "A");
+        let pane_b = spawn_test_pane(&mut world, "B");
+        let stack = spawn_test_stack(&mut world, 1., &[pane_a, pane_b]);
+        spawn_root(&mut world, stack);
+
+        world
+            .run_system_cached_with(
+                move_pane,
+                (
+                    pane_b,
+                    PaneDrop::Tab {
+                        pane: pane_a,
+                        after: false,
+                    },
+                ),
+            )
+            .unwrap();
+        world.flush();
+
+        assert_eq!(children(&world, stack), [pane_b, pane_a]);
+        assert_eq!(world.get::<PaneStack>(stack).unwrap().active, pane_b);
+    }
+
+    #[test]
+    fn close_last_tab() {
+        let mut world = World::new();
+        let pane_a = spawn_test_pane(&mut world, "A");
+        let pane_b = spawn_test_pane(&mut world, "B");
+        let stack_a = spawn_test_stack(&mut world, 0.5, &[pane_a]);
+        let stack_b = spawn_test_stack(&mut world, 1., &[pane_b]);
+        // A divider left with a single area, before it is cleaned up
+        let inner = spawn_test_divider(&mut world, Divider::Vertical, 0.5, &[stack_b]);
+        let divider = spawn_test_divider(&mut world, Divider::Horizontal, 1., &[stack_a, inner]);
+        spawn_root(&mut world, divider);
+
+        world.run_system_cached_with(remove_pane, pane_b).unwrap();
+        world.flush();
+
+        assert!(world.get_entity(inner).is_err());
+        assert!(world.get_entity(pane_b).is_err());
+        assert_eq!(children(&world, divider), [stack_a]);
+        assert_eq!(size(&world, stack_a), 1.);
+
+        // The last area of the layout is kept
+        world.run_system_cached_with(remove_pane, pane_a).unwrap();
+        world.flush();
+
+        assert_eq!(children(&world, stack_a), [pane_a]);
     }
-    size.0 = new_size;
 }
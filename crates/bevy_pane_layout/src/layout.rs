@@ -0,0 +1,352 @@
+//! Saving and loading pane layouts.
+//!
+//! A [`PaneLayout`] is the tree of dividers and panes below the [`RootPaneLayoutNode`], with the
+//! names of the pane types, the directions of the dividers and the fractions of space taken by
+//! each. The layouts of the [`LayoutPresets`](crate::presets::LayoutPresets) are saved as RON to
+//! the [`PaneLayoutFile`] when the app exits, and restored from it at startup.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    slice,
+};
+
+use bevy::{platform::collections::HashMap, prelude::*};
+use bevy_editor_styles::Theme;
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+
+use crate::{
+    Divider, PaneRootNode, PaneStack, RootPaneLayoutNode, Size,
+    ui::{spawn_divider, spawn_pane, spawn_pane_stack, spawn_resize_handle},
+};
+
+/// The file the pane layouts are restored from at startup and saved to on exit. The layout isn't
+/// persisted if there is none, which is the default.
+#[derive(Resource, Default, Clone, Debug)]
+pub struct PaneLayoutFile(pub Option<PathBuf>);
+
+/// A layout of panes, as saved to the [`PaneLayoutFile`].
+///
+/// ```
+/// use bevy_pane_layout::layout::PaneLayout;
+///
+/// let layout = PaneLayout::horizontal(
+///     1.,
+///     [
+///         PaneLayout::pane("Scene Tree", 0.2),
+///         PaneLayout::pane("Viewport 3D", 0.8),
+///     ],
+/// );
+/// ```
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub enum PaneLayout {
+    /// A pane of the type registered under `name`.
+    Pane {
+        /// The name of the pane type.
+        name: String,
+        /// The fraction of space taken in the parent divider.
+        size: f32,
+    },
+    /// Panes sharing an area as tabs.
+    Tabs {
+        /// The names of the pane types, in the order of the tabs.
+        names: Vec<String>,
+        /// The index of the pane which is shown.
+        active: usize,
+        /// The fraction of space taken in the parent divider.
+        size: f32,
+    },
+    /// An area divided into `children` along an axis.
+    Divider {
+        /// The axis along which the children are laid out.
+        direction: Divider,
+        /// The fraction of space taken in the parent divider.
+        size: f32,
+        /// The areas of the divider, in order.
+        children: Vec<PaneLayout>,
+    },
+}
+
+impl Default for PaneLayout {
+    fn default() -> Self {
+        PaneLayout::horizontal(
+            1.,
+            [
+                PaneLayout::vertical(
+                    0.2,
+                    [
+                        PaneLayout::pane("Scene Tree", 0.4),
+                        PaneLayout::pane("Properties", 0.6),
+                    ],
+                ),
+                PaneLayout::vertical(
+                    0.8,
+                    [
+                        PaneLayout::pane("Viewport 3D", 0.7),
+                        PaneLayout::pane("Asset Browser", 0.3),
+                    ],
+                ),
+            ],
+        )
+    }
+}
+
+impl PaneLayout {
+    /// A pane of the type registered under `name`, taking `size` of its parent divider.
+    pub fn pane(name: impl Into<String>, size: f32) -> Self {
+        PaneLayout::Pane {
+            name: name.into(),
+            size,
+        }
+    }
+
+    /// Panes of the types registered under `names` sharing an area as tabs, the first one being
+    /// shown, taking `size` of its parent divider.
+    pub fn tabs(names: impl IntoIterator<Item = impl Into<String>>, size: f32) -> Self {
+        PaneLayout::Tabs {
+            names: names.into_iter().map(Into::into).collect(),
+            active: 0,
+            size,
+        }
+    }
+
+    /// A divider laying out `children` from left to right, taking `size` of its parent divider.
+    pub fn horizontal(size: f32, children: impl IntoIterator<Item = PaneLayout>) -> Self {
+        PaneLayout::Divider {
+            direction: Divider::Horizontal,
+            size,
+            children: children.into_iter().collect(),
+        }
+    }
+
+    /// A divider laying out `children` from top to bottom, taking `size` of its parent divider.
+    pub fn vertical(size: f32, children: impl IntoIterator<Item = PaneLayout>) -> Self {
+        PaneLayout::Divider {
+            direction: Divider::Vertical,
+            size,
+            children: children.into_iter().collect(),
+        }
+    }
+
+    /// Capture the layout of the panes of the [`RootPaneLayoutNode`].
+    pub fn capture(world: &mut World) -> Option<Self> {
+        let root = world
+            .query_filtered::<&Children, With<RootPaneLayoutNode>>()
+            .single(world)
+            .ok()?
+            .first()
+            .copied()?;
+        Self::capture_node(world, root)
+    }
+
+    /// Capture the layout of a pane stack or a divider node.
+    fn capture_node(world: &World, entity: Entity) -> Option<Self> {
+        let size = world.get::<Size>(entity)?.0;
+        let children = world.get::<Children>(entity).into_iter().flatten();
+        if let Some(stack) = world.get::<PaneStack>(entity) {
+            let panes: Vec<Entity> = children
+                .copied()
+                .filter(|&child| world.get::<PaneRootNode>(child).is_some())
+                .collect();
+            let mut names: Vec<String> = panes
+                .iter()
+                .map(|&pane| world.get::<PaneRootNode>(pane).unwrap().name.clone())
+                .collect();
+            return match names.len() {
+                0 => None,
+                1 => Some(PaneLayout::Pane {
+                    name: names.remove(0),
+                    size,
+                }),
+                _ => Some(PaneLayout::Tabs {
+                    names,
+                    active: panes
+                        .iter()
+                        .position(|&pane| pane == stack.active)
+                        .unwrap_or(0),
+                    size,
+                }),
+            };
+        }
+
+        let direction = *world.get::<Divider>(entity)?;
+        // The resize handles between the areas are neither pane stacks nor dividers, and are
+        // skipped.
+        let children = children
+            .filter_map(|&child| Self::capture_node(world, child))
+            .collect();
+        Some(PaneLayout::Divider {
+            direction,
+            size,
+            children,
+        })
+    }
+
+    /// Spawn the nodes of the layout, returning the top node.
+    ///
+    /// The panes of the `reusable` pane roots, by pane name, are moved into the layout instead of
+    /// spawning new panes, which keeps their state.
+    pub(crate) fn spawn(
+        &self,
+        commands: &mut Commands,
+        theme: &Theme,
+        reusable: &mut HashMap<String, Vec<Entity>>,
+    ) -> Entity {
+        match self {
+            PaneLayout::Pane { name, size } => {
+                spawn_panes(commands, theme, slice::from_ref(name), 0, *size, reusable)
+            }
+            PaneLayout::Tabs {
+                names,
+                active,
+                size,
+            } => spawn_panes(commands, theme, names, *active, *size, reusable),
+            PaneLayout::Divider {
+                direction,
+                size,
+                children,
+            } => {
+                let divider = spawn_divider(commands, *direction, *size).id();
+                for (index, child) in children.iter().enumerate() {
+                    if index > 0 {
+                        spawn_resize_handle(commands, *direction).insert(ChildOf(divider));
+                    }
+                    let child = child.spawn(commands, theme, reusable);
+                    commands.entity(child).insert(ChildOf(divider));
+                }
+                divider
+            }
+        }
+    }
+
+    /// Check that the layout can be spawned: the sizes are valid fractions and the dividers have
+    /// areas.
+    pub(crate) fn validate(&self) -> Result<(), String> {
+        let size = match self {
+            PaneLayout::Pane { size, .. } => *size,
+            PaneLayout::Tabs {
+                names,
+                active,
+                size,
+            } => {
+                if *active >= names.len() {
+                    return Err(format!(
+                        "The active tab {active} is out of the {} tabs",
+                        names.len()
+                    ));
+                }
+                *size
+            }
+            PaneLayout::Divider { size, children, .. } => {
+                if children.is_empty() {
+                    return Err("A divider has no areas".to_string());
+                }
+                for child in children {
+                    child.validate()?;
+                }
+                *size
+            }
+        };
+        if !(size > 0. && size <= 1.) {
+            return Err(format!("Invalid area size {size}"));
+        }
+        Ok(())
+    }
+}
+
+/// Spawn a pane stack holding panes of the given types as tabs, reusing the `reusable` panes.
+fn spawn_panes(
+    commands: &mut Commands,
+    theme: &Theme,
+    names: &[String],
+    active: usize,
+    size: f32,
+    reusable: &mut HashMap<String, Vec<Entity>>,
+) -> Entity {
+    let panes: Vec<Entity> = names
+        .iter()
+        .map(|name| match reusable.get_mut(name).and_then(Vec::pop) {
+            Some(pane) => pane,
+            None => spawn_pane(commands, theme, name).id(),
+        })
+        .collect();
+    spawn_pane_stack(commands, size, &panes, panes[active]).id()
+}
+
+/// Replace the current pane layout, moving the panes of the same type into the new layout so
+/// that they keep their state. The other panes are despawned.
+pub fn apply_pane_layout(
+    In(layout): In<PaneLayout>,
+    mut commands: Commands,
+    theme: Res<Theme>,
+    root: Single<Entity, With<RootPaneLayoutNode>>,
+    children: Query<&Children>,
+    panes: Query<&PaneRootNode>,
+) {
+    let old_layout: Vec<Entity> = children.get(*root).into_iter().flatten().copied().collect();
+
+    // Reuse the panes in their order in the old layout.
+    let mut reusable = HashMap::<String, Vec<Entity>>::default();
+    for entity in children.iter_descendants_depth_first(*root) {
+        if let Ok(pane) = panes.get(entity) {
+            reusable.entry(pane.name.clone()).or_default().push(entity);
+        }
+    }
+    for panes in reusable.values_mut() {
+        panes.reverse();
+    }
+
+    let new_layout = layout.spawn(&mut commands, &theme, &mut reusable);
+    commands.entity(new_layout).insert(ChildOf(*root));
+
+    // The reused panes were moved out of the old layout by now.
+    for child in old_layout {
+        commands.entity(child).despawn();
+    }
+}
+
+/// Read a RON file.
+pub(crate) fn load_ron<T: DeserializeOwned>(path: &Path) -> Result<T, String> {
+    let text = fs::read_to_string(path).map_err(|error| error.to_string())?;
+    ron::from_str(&text).map_err(|error| error.to_string())
+}
+
+/// Write a RON file, creating its directory if needed.
+pub(crate) fn save_ron<T: Serialize>(value: &T, path: &Path) -> Result<(), String> {
+    let text = ron::ser::to_string_pretty(value, ron::ser::PrettyConfig::default())
+        .map_err(|error| error.to_string())?;
+    if let Some(directory) = path.parent() {
+        fs::create_dir_all(directory).map_err(|error| error.to_string())?;
+    }
+    fs::write(path, text).map_err(|error| error.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_layout_round_trip() {
+        let layout = PaneLayout::default();
+        assert_eq!(layout.validate(), Ok(()));
+
+        let text = ron::to_string(&layout).unwrap();
+        assert_eq!(ron::from_str::<PaneLayout>(&text).unwrap(), layout);
+    }
+
+    #[test]
+    fn invalid_layout() {
+        let layout = PaneLayout::vertical(1., []);
+        assert!(layout.validate().is_err());
+
+        let layout = PaneLayout::pane("Properties", f32::NAN);
+        assert!(layout.validate().is_err());
+
+        let layout = PaneLayout::Tabs {
+            names: vec!["Properties".to_string()],
+            active: 1,
+            size: 1.,
+        };
+        assert!(layout.validate().is_err());
+    }
+}
@@ -1,9 +1,13 @@
 //! Resizable, divider-able panes for Bevy.
 
 pub mod components;
+mod docking;
 mod handlers;
+pub mod layout;
 mod pane_drop_area;
+pub mod presets;
 pub mod registry;
+mod tabs;
 mod ui;
 
 /// The Bevy Pane Layout system.
@@ -19,12 +23,19 @@ mod ui;
 /// - Panes cannot have min/max sizes, they must be able to be resized to any size.
 ///   - If a pane can not be sensibly resized, it can overflow under the other panes.
 /// - Panes must not interfere with each other, only temporary/absolute positioned elements are allowed to overlap panes.
-use bevy::prelude::*;
+use bevy::{platform::collections::HashMap, prelude::*};
 use bevy_editor_styles::Theme;
+use serde::{Deserialize, Serialize};
 
 use crate::{
+    docking::{PaneDrag, make_headers_draggable, spawn_drop_indicator, update_drop_indicator},
+    layout::PaneLayoutFile,
+    presets::{
+        LayoutPresets, WorkspaceTabs, save_layout_on_exit, startup_layout, update_workspace_tabs,
+    },
     registry::PaneRegistryPlugin,
-    ui::{spawn_divider, spawn_pane, spawn_resize_handle},
+    tabs::update_pane_stacks,
+    ui::add_pane_type_switchers,
 };
 
 /// Crate prelude.
@@ -32,6 +43,8 @@ pub mod prelude {
     pub use crate::{
         PaneAreaNode, PaneContentNode, PaneHeaderNode,
         components::*,
+        layout::{PaneLayout, PaneLayoutFile},
+        presets::{LayoutPreset, LayoutPresets, reset_pane_layout, switch_layout_preset},
         registry::{PaneAppExt, PaneStructure},
     };
 }
@@ -43,13 +56,26 @@ impl Plugin for PaneLayoutPlugin {
     fn build(&self, app: &mut App) {
         app.add_plugins(PaneRegistryPlugin)
             .init_resource::<DragState>()
+            .init_resource::<PaneLayoutFile>()
+            .init_resource::<LayoutPresets>()
+            .init_resource::<PaneDrag>()
             .add_systems(Startup, setup.in_set(PaneLayoutSet))
             .add_systems(
                 Update,
-                (cleanup_divider_single_child, apply_size)
-                    .chain()
-                    .in_set(PaneLayoutSet),
-            );
+                (
+                    (cleanup_divider_single_child, apply_size, update_pane_stacks)
+                        .chain()
+                        .in_set(PaneLayoutSet),
+                    // Creation callbacks can replace the header of their pane
+                    (make_headers_draggable, add_pane_type_switchers).after(PaneLayoutSet),
+                    update_drop_indicator.run_if(resource_changed::<PaneDrag>),
+                    update_workspace_tabs.run_if(
+                        resource_changed::<LayoutPresets>
+                            .or(any_match_filter::<Added<WorkspaceTabs>>),
+                    ),
+                ),
+            )
+            .add_systems(Last, save_layout_on_exit);
     }
 }
 
@@ -92,11 +118,13 @@ struct DragState {
 #[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
 pub struct PaneLayoutSet;
 
-// TODO There is no way to save or load layouts at this moment.
-// The setup system currently just creates a default layout at startup.
+/// Set up the pane layout root and spawn the layout of the active [`LayoutPresets`], as saved to
+/// the [`PaneLayoutFile`].
 fn setup(
     mut commands: Commands,
     theme: Res<Theme>,
+    layout_file: Res<PaneLayoutFile>,
+    mut presets: ResMut<LayoutPresets>,
     panes_root: Single<Entity, With<RootPaneLayoutNode>>,
 ) {
     commands.entity(*panes_root).insert((
@@ -112,27 +140,14 @@ fn setup(
         theme.general.background_color,
     ));
 
-    let divider = spawn_divider(&mut commands, Divider::Horizontal, 1.)
-        .insert(ChildOf(*panes_root))
-        .id();
+    let layout = startup_layout(&mut presets, &layout_file).spawn(
+        &mut commands,
+        &theme,
+        &mut HashMap::default(),
+    );
+    commands.entity(layout).insert(ChildOf(*panes_root));
 
-    let sub_divider = spawn_divider(&mut commands, Divider::Vertical, 0.2)
-        .insert(ChildOf(divider))
-        .id();
-
-    spawn_pane(&mut commands, &theme, 0.4, "Scene Tree").insert(ChildOf(sub_divider));
-    spawn_resize_handle(&mut commands, Divider::Vertical).insert(ChildOf(sub_divider));
-    spawn_pane(&mut commands, &theme, 0.6, "Properties").insert(ChildOf(sub_divider));
-
-    spawn_resize_handle(&mut commands, Divider::Horizontal).insert(ChildOf(divider));
-
-    let asset_browser_divider = spawn_divider(&mut commands, Divider::Vertical, 0.8)
-        .insert(ChildOf(divider))
-        .id();
-
-    spawn_pane(&mut commands, &theme, 0.70, "Viewport 3D").insert(ChildOf(asset_browser_divider));
-    spawn_resize_handle(&mut commands, Divider::Vertical).insert(ChildOf(asset_browser_divider));
-    spawn_pane(&mut commands, &theme, 0.30, "Asset Browser").insert(ChildOf(asset_browser_divider));
+    spawn_drop_indicator(&mut commands);
 }
 
 /// Removes a divider from the hierarchy when it has only one child left, replacing itself with that child.
@@ -167,15 +182,27 @@ fn cleanup_divider_single_child(
 }
 
 /// A node that divides an area into multiple areas along an axis.
-#[derive(Component, Clone, Copy, PartialEq, Eq)]
-enum Divider {
+#[derive(Component, Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Divider {
+    /// The areas are laid out from left to right.
     Horizontal,
+    /// The areas are laid out from top to bottom.
     Vertical,
 }
 
 #[derive(Component)]
 struct ResizeHandle;
 
+/// An area of the layout holding one or more panes as tabs, of which only the active one is shown.
+#[derive(Component)]
+struct PaneStack {
+    active: Entity,
+}
+
+/// The tab bar of a [`PaneStack`], first child of the stack, shown when it holds several panes.
+#[derive(Component)]
+struct PaneTabBar;
+
 /// The fraction of space this element takes up in the [`Divider`] it's a child of.
 #[derive(Component)]
 struct Size(f32);
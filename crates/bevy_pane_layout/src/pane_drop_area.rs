@@ -15,11 +15,9 @@
 //! |  ⟋ B  ⟍  |
 //! |⟋________⟍|
 //! ```
-#![allow(unused)]
-
 use bevy::{math::AspectRatio, prelude::*};
 
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub enum PaneDropArea {
     Top,
     Bottom,
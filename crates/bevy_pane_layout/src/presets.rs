@@ -0,0 +1,311 @@
+//! Named pane layout presets, such as "Level Design" or "Debug", switched between from a workspace
+//! tab strip, as in Blender's workspaces.
+//!
+//! Changes to the layout of a preset are kept when switching to another preset, and saved to the
+//! [`PaneLayoutFile`] along with the active preset.
+
+use std::path::Path;
+
+use bevy::prelude::*;
+use bevy_editor_styles::Theme;
+use serde::{Deserialize, Serialize};
+
+use crate::layout::{PaneLayout, PaneLayoutFile, apply_pane_layout, load_ron, save_ron};
+
+/// A named pane layout.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct LayoutPreset {
+    /// The name of the preset, shown in the workspace tabs.
+    pub name: String,
+    /// The layout of the preset.
+    pub layout: PaneLayout,
+}
+
+impl LayoutPreset {
+    /// Create a new layout preset.
+    pub fn new(name: impl Into<String>, layout: PaneLayout) -> Self {
+        Self {
+            name: name.into(),
+            layout,
+        }
+    }
+}
+
+/// The pane layout presets, and which one is shown.
+///
+/// Insert it before startup to replace the single "Default" preset.
+#[derive(Resource, Clone, Debug)]
+pub struct LayoutPresets {
+    presets: Vec<LayoutPreset>,
+    /// The presets as they were created, restored by [`reset_pane_layout`].
+    defaults: Vec<LayoutPreset>,
+    active: usize,
+}
+
+impl Default for LayoutPresets {
+    fn default() -> Self {
+        Self::new([LayoutPreset::new("Default", PaneLayout::default())])
+    }
+}
+
+/// The layout presets as saved to the [`PaneLayoutFile`].
+#[derive(Serialize, Deserialize)]
+struct SavedLayoutPresets {
+    active: String,
+    presets: Vec<LayoutPreset>,
+}
+
+impl LayoutPresets {
+    /// Create the layout presets, the first one being shown at startup unless another one was
+    /// saved as active.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there are no presets.
+    pub fn new(presets: impl IntoIterator<Item = LayoutPreset>) -> Self {
+        let presets: Vec<LayoutPreset> = presets.into_iter().collect();
+        assert!(
+            !presets.is_empty(),
+            "There must be at least one layout preset"
+        );
+        Self {
+            defaults: presets.clone(),
+            presets,
+            active: 0,
+        }
+    }
+
+    /// Iterate over the presets, in order.
+    pub fn iter(&self) -> impl Iterator<Item = &LayoutPreset> {
+        self.presets.iter()
+    }
+
+    /// The preset which is shown.
+    pub fn active(&self) -> &LayoutPreset {
+        &self.presets[self.active]
+    }
+
+    fn index_of(&self, name: &str) -> Option<usize> {
+        self.presets.iter().position(|preset| preset.name == name)
+    }
+
+    /// Restore the layouts and the active preset saved to a file. The saved presets which don't
+    /// exist anymore are ignored.
+    fn load(&mut self, path: &Path) -> Result<(), String> {
+        let saved: SavedLayoutPresets = load_ron(path)?;
+        for saved_preset in saved.presets {
+            let Some(index) = self.index_of(&saved_preset.name) else {
+                continue;
+            };
+            match saved_preset.layout.validate() {
+                Ok(()) => self.presets[index].layout = saved_preset.layout,
+                Err(error) => warn!(
+                    "Ignoring the saved layout of the \"{}\" preset: {error}",
+                    saved_preset.name
+                ),
+            }
+        }
+        if let Some(index) = self.index_of(&saved.active) {
+            self.active = index;
+        }
+        Ok(())
+    }
+
+    /// Save the layouts and the active preset to a file.
+    fn save(&self, path: &Path) -> Result<(), String> {
+        save_ron(
+            &SavedLayoutPresets {
+                active: self.active().name.clone(),
+                presets: self.presets.clone(),
+            },
+            path,
+        )
+    }
+}
+
+/// The layout to spawn at startup: the active preset saved to the [`PaneLayoutFile`], if any, or
+/// the first preset.
+pub(crate) fn startup_layout(
+    presets: &mut LayoutPresets,
+    layout_file: &PaneLayoutFile,
+) -> PaneLayout {
+    if let Some(path) = layout_file.0.as_deref().filter(|path| path.exists())
+        && let Err(error) = presets.load(path)
+    {
+        warn!(
+            "Failed to load the pane layouts from {}, using the default ones: {error}",
+            path.display()
+        );
+    }
+    presets.active().layout.clone()
+}
+
+/// Keep the changes made to the layout of the active preset.
+fn store_active_layout(world: &mut World) {
+    let Some(layout) = PaneLayout::capture(world) else {
+        return;
+    };
+    let mut presets = world.resource_mut::<LayoutPresets>();
+    let active = presets.active;
+    presets.presets[active].layout = layout;
+}
+
+/// Save the pane layouts to the [`PaneLayoutFile`] when the app exits.
+pub(crate) fn save_layout_on_exit(world: &mut World) {
+    if world.resource::<Events<AppExit>>().is_empty() {
+        return;
+    }
+    let Some(path) = world.resource::<PaneLayoutFile>().0.clone() else {
+        return;
+    };
+    store_active_layout(world);
+
+    match world.resource::<LayoutPresets>().save(&path) {
+        Ok(()) => info!("Saved the pane layouts to {}", path.display()),
+        Err(error) => error!(
+            "Failed to save the pane layouts to {}: {error}",
+            path.display()
+        ),
+    }
+}
+
+/// Show the layout preset with the given name, keeping the changes made to the current one.
+pub fn switch_layout_preset(In(name): In<String>, world: &mut World) {
+    let presets = world.resource::<LayoutPresets>();
+    let Some(index) = presets.index_of(&name) else {
+        warn!("No layout preset named \"{name}\"");
+        return;
+    };
+    if index == presets.active {
+        return;
+    }
+
+    store_active_layout(world);
+    let mut presets = world.resource_mut::<LayoutPresets>();
+    presets.active = index;
+    let layout = presets.presets[index].layout.clone();
+    if let Err(error) = world.run_system_cached_with(apply_pane_layout, layout) {
+        error!("Failed to switch to the \"{name}\" layout preset: {error}");
+    }
+}
+
+/// Restore the layout the active preset was created with.
+pub fn reset_pane_layout(world: &mut World) {
+    let mut presets = world.resource_mut::<LayoutPresets>();
+    let active = presets.active;
+    let layout = presets.defaults[active].layout.clone();
+    presets.presets[active].layout = layout.clone();
+    if let Err(error) = world.run_system_cached_with(apply_pane_layout, layout) {
+        error!("Failed to reset the pane layout: {error}");
+    }
+}
+
+/// A strip of tabs, one per [`LayoutPreset`], switching to the preset when clicked.
+///
+/// Spawn it with [`spawn_workspace_tabs`].
+#[derive(Component)]
+pub struct WorkspaceTabs;
+
+/// The tab of a [`LayoutPreset`] in the [`WorkspaceTabs`].
+#[derive(Component)]
+struct WorkspaceTab(String);
+
+/// Spawn a [`WorkspaceTabs`] strip.
+pub fn spawn_workspace_tabs<'a>(commands: &'a mut Commands) -> EntityCommands<'a> {
+    commands.spawn((
+        WorkspaceTabs,
+        Node {
+            flex_direction: FlexDirection::Row,
+            align_items: AlignItems::Center,
+            column_gap: Val::Px(2.),
+            margin: UiRect::left(Val::Px(20.)),
+            ..default()
+        },
+    ))
+}
+
+/// Rebuild the tabs of the [`WorkspaceTabs`] when the presets change.
+pub(crate) fn update_workspace_tabs(
+    mut commands: Commands,
+    theme: Res<Theme>,
+    presets: Res<LayoutPresets>,
+    strips: Query<Entity, With<WorkspaceTabs>>,
+) {
+    for strip in &strips {
+        commands.entity(strip).despawn_children();
+        for (index, preset) in presets.iter().enumerate() {
+            let is_active = index == presets.active;
+            commands
+                .spawn((
+                    WorkspaceTab(preset.name.clone()),
+                    Node {
+                        padding: UiRect::axes(Val::Px(8.), Val::Px(3.)),
+                        ..default()
+                    },
+                    if is_active {
+                        theme.pane.header_background_color
+                    } else {
+                        BackgroundColor(Color::NONE)
+                    },
+                    theme.general.border_radius,
+                    ChildOf(strip),
+                ))
+                .with_child((
+                    Text::new(preset.name.clone()),
+                    TextFont {
+                        font: theme.text.font.clone(),
+                        font_size: 12.,
+                        ..default()
+                    },
+                    TextColor(if is_active {
+                        theme.text.text_color
+                    } else {
+                        theme.text.low_priority
+                    }),
+                    Pickable::IGNORE,
+                ))
+                .observe(
+                    |trigger: On<Pointer<Click>>,
+                     tabs: Query<&WorkspaceTab>,
+                     mut commands: Commands| {
+                        if trigger.event().button != PointerButton::Primary {
+                            return;
+                        }
+                        if let Ok(tab) = tabs.get(trigger.target()) {
+                            commands.run_system_cached_with(switch_layout_preset, tab.0.clone());
+                        }
+                    },
+                );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn load_saved_presets() {
+        let mut presets = LayoutPresets::new([
+            LayoutPreset::new("Default", PaneLayout::default()),
+            LayoutPreset::new("Debug", PaneLayout::pane("Properties", 1.)),
+        ]);
+        let mut saved = LayoutPresets::new([
+            LayoutPreset::new("Removed", PaneLayout::default()),
+            LayoutPreset::new("Debug", PaneLayout::pane("Scene Tree", 1.)),
+        ]);
+        saved.active = 1;
+        let path = std::env::temp_dir().join("bevy_pane_layout_test_presets.ron");
+        saved.save(&path).unwrap();
+
+        presets.load(&path).unwrap();
+        let _ = std::fs::remove_file(&path);
+
+        assert_eq!(presets.active().name, "Debug");
+        assert_eq!(presets.active().layout, PaneLayout::pane("Scene Tree", 1.));
+        assert_eq!(
+            presets.defaults[1].layout,
+            PaneLayout::pane("Properties", 1.)
+        );
+    }
+}
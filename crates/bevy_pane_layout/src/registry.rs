@@ -48,6 +48,11 @@ impl PaneRegistry {
             creation_callback: Some(Box::new(IntoSystem::into_system(system))),
         });
     }
+
+    /// Iterate over the names of the registered pane types, in registration order.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.panes.iter().map(|pane| pane.name.as_str())
+    }
 }
 
 struct Pane {
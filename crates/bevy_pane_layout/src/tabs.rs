@@ -0,0 +1,195 @@
+//! Tabs of the panes sharing an area of the layout.
+//!
+//! A [`PaneStack`] shows one of its panes at a time, with a tab per pane in its [`PaneTabBar`].
+//! Tabs can be closed, reordered by dropping them onto another tab, and dragged out of their
+//! stack to dock their pane elsewhere, see [`docking`](crate::docking).
+
+use bevy::{feathers::cursor::EntityCursor, prelude::*, window::SystemCursorIcon};
+use bevy_editor_styles::Theme;
+
+use crate::{
+    PaneRootNode, PaneStack, PaneTabBar,
+    docking::{DragHandle, PaneDrag, drag_pane_over, drop_pane},
+    handlers::{PaneDrop, move_pane, remove_pane},
+};
+
+/// The tab of a pane in a [`PaneTabBar`].
+#[derive(Component)]
+struct PaneTab {
+    pane: Entity,
+}
+
+/// Show the active pane of each pane stack, and rebuild their tab bars, when their panes change.
+pub(crate) fn update_pane_stacks(
+    mut commands: Commands,
+    theme: Res<Theme>,
+    mut stacks: Query<(&mut PaneStack, &Children), Or<(Changed<PaneStack>, Changed<Children>)>>,
+    pane_root_query: Query<&PaneRootNode>,
+    tab_bar_query: Query<(), With<PaneTabBar>>,
+    mut node_query: Query<&mut Node>,
+) {
+    for (mut stack, children) in &mut stacks {
+        let panes: Vec<Entity> = children
+            .iter()
+            .filter(|child| pane_root_query.contains(*child))
+            .collect();
+        let Some(&first) = panes.first() else {
+            continue;
+        };
+        // The active pane was closed or moved to another stack.
+        if !panes.contains(&stack.active) {
+            stack.active = first;
+        }
+
+        for &pane in &panes {
+            if let Ok(mut node) = node_query.get_mut(pane) {
+                node.display = if pane == stack.active {
+                    Display::Flex
+                } else {
+                    Display::None
+                };
+            }
+        }
+
+        let Some(tab_bar) = children.iter().find(|child| tab_bar_query.contains(*child)) else {
+            continue;
+        };
+        if let Ok(mut node) = node_query.get_mut(tab_bar) {
+            node.display = if panes.len() > 1 {
+                Display::Flex
+            } else {
+                Display::None
+            };
+        }
+        commands.entity(tab_bar).despawn_children();
+        for pane in panes {
+            let name = &pane_root_query.get(pane).unwrap().name;
+            spawn_tab(&mut commands, &theme, pane, name, pane == stack.active)
+                .insert(ChildOf(tab_bar));
+        }
+    }
+}
+
+fn spawn_tab<'a>(
+    commands: &'a mut Commands,
+    theme: &Theme,
+    pane: Entity,
+    name: &str,
+    is_active: bool,
+) -> EntityCommands<'a> {
+    let text_font = TextFont {
+        font: theme.text.font.clone(),
+        font_size: 12.,
+        ..default()
+    };
+    let text_color = TextColor(if is_active {
+        theme.text.text_color
+    } else {
+        theme.text.low_priority
+    });
+
+    let mut tab = commands.spawn((
+        PaneTab { pane },
+        Node {
+            align_items: AlignItems::Center,
+            column_gap: Val::Px(6.),
+            padding: UiRect::axes(Val::Px(8.), Val::Px(3.)),
+            ..default()
+        },
+        if is_active {
+            theme.pane.header_background_color
+        } else {
+            BackgroundColor(Color::NONE)
+        },
+        theme.pane.header_border_radius,
+        EntityCursor::System(SystemCursorIcon::Pointer),
+    ));
+    tab.with_children(|parent| {
+        parent.spawn((
+            Text::new(name),
+            text_font.clone(),
+            text_color,
+            Pickable::IGNORE,
+        ));
+        parent
+            .spawn((Text::new("×"), text_font, text_color))
+            .observe(
+                move |mut trigger: On<Pointer<Click>>, mut commands: Commands| {
+                    trigger.propagate(false);
+                    commands.run_system_cached_with(remove_pane, pane);
+                },
+            );
+    })
+    .observe(
+        |trigger: On<Pointer<Click>>,
+         tabs: Query<&PaneTab>,
+         parent_query: Query<&ChildOf>,
+         mut stacks: Query<&mut PaneStack>| {
+            let Ok(tab) = tabs.get(trigger.target()) else {
+                return;
+            };
+            if let Ok(stack) = parent_query.get(tab.pane)
+                && let Ok(mut stack) = stacks.get_mut(stack.parent())
+                && stack.active != tab.pane
+            {
+                stack.active = tab.pane;
+            }
+        },
+    )
+    .observe(
+        |trigger: On<Pointer<DragStart>>, tabs: Query<&PaneTab>, mut drag: ResMut<PaneDrag>| {
+            if trigger.button != PointerButton::Primary {
+                return;
+            }
+            if let Ok(tab) = tabs.get(trigger.target()) {
+                drag.dragged = Some((tab.pane, DragHandle::Tab));
+            }
+        },
+    )
+    .observe(drag_pane_over)
+    // Dropping a tab onto another tab moves it next to that tab.
+    .observe(
+        |mut trigger: On<Pointer<DragDrop>>,
+         tabs: Query<&PaneTab>,
+         parent_query: Query<&ChildOf>,
+         children_query: Query<&Children>,
+         mut drag: ResMut<PaneDrag>,
+         mut commands: Commands| {
+            trigger.propagate(false);
+            let (Some((pane, _)), Ok(tab)) = (drag.dragged, tabs.get(trigger.target())) else {
+                return;
+            };
+            // The pane is dropped onto a tab, rather than onto an area.
+            drag.target = None;
+
+            // A tab dragged to the right along its own tab bar goes after the tab it is dropped
+            // onto, and before it otherwise.
+            let index_of = |pane: Entity| {
+                let stack = parent_query.get(pane).ok()?.parent();
+                let index = children_query
+                    .get(stack)
+                    .ok()?
+                    .iter()
+                    .position(|e| e == pane)?;
+                Some((stack, index))
+            };
+            let after = matches!(
+                (index_of(pane), index_of(tab.pane)),
+                (Some((stack, index)), Some((tab_stack, tab_index)))
+                    if stack == tab_stack && index < tab_index
+            );
+            commands.run_system_cached_with(
+                move_pane,
+                (
+                    pane,
+                    PaneDrop::Tab {
+                        pane: tab.pane,
+                        after,
+                    },
+                ),
+            );
+        },
+    )
+    .observe(drop_pane);
+    tab
+}
@@ -1,10 +1,13 @@
 use bevy::{feathers::cursor::EntityCursor, prelude::*, window::SystemCursorIcon};
-use bevy_context_menu::{ContextMenu, ContextMenuOption};
+use bevy_context_menu::{ContextMenu, ContextMenuOption, OpenContextMenu};
 use bevy_editor_styles::{Theme, icons};
 
 use crate::{
-    Divider, DragState, PaneAreaNode, PaneContentNode, PaneHeaderNode, PaneRootNode, ResizeHandle,
-    Size, handlers::*, registry::PaneStructure,
+    Divider, DragState, PaneAreaNode, PaneContentNode, PaneHeaderNode, PaneRootNode, PaneStack,
+    PaneTabBar, ResizeHandle, Size,
+    handlers::*,
+    presets::reset_pane_layout,
+    registry::{PaneRegistry, PaneStructure},
 };
 
 pub fn header_context_menu() -> ContextMenu {
@@ -18,24 +21,81 @@ pub fn header_context_menu() -> ContextMenu {
         ContextMenuOption::new("Split - Vertical", |mut commands, entity| {
             commands.run_system_cached_with(split_pane, (entity, true));
         }),
+        ContextMenuOption::new("Reset Layout", |mut commands, _entity| {
+            commands.run_system_cached(reset_pane_layout);
+        }),
     ])
 }
 
+/// The drop down button listing the registered pane types, added to the start of each pane header
+/// to change the type of the pane.
+#[derive(Component)]
+struct PaneTypeSwitcher;
+
+/// Add a [`PaneTypeSwitcher`] to the new pane headers.
+pub(crate) fn add_pane_type_switchers(
+    mut commands: Commands,
+    theme: Res<Theme>,
+    registry: Res<PaneRegistry>,
+    headers: Query<Entity, Added<PaneHeaderNode>>,
+) {
+    for header in &headers {
+        let menu = ContextMenu::new(registry.names().map(|name| {
+            let name = name.to_string();
+            ContextMenuOption::new(name.clone(), move |mut commands, entity| {
+                commands.run_system_cached_with(change_pane_type, (entity, name.clone()));
+            })
+        }));
+
+        let switcher = commands
+            .spawn((
+                PaneTypeSwitcher,
+                Node {
+                    align_items: AlignItems::Center,
+                    flex_shrink: 0.,
+                    margin: UiRect::right(Val::Px(4.)),
+                    ..default()
+                },
+                menu,
+                EntityCursor::System(SystemCursorIcon::Pointer),
+            ))
+            .with_child((
+                Text::new(icons::CHEVRON_DOWN),
+                TextFont {
+                    font: theme.icon.font.clone(),
+                    font_size: 16.0,
+                    ..default()
+                },
+                Pickable::IGNORE,
+            ))
+            .observe(|mut trigger: On<Pointer<Click>>, mut commands: Commands| {
+                if trigger.button != PointerButton::Primary {
+                    return;
+                }
+                trigger.propagate(false);
+
+                let position = trigger.pointer_location.position;
+                commands.trigger_targets(OpenContextMenu { position }, trigger.target());
+            })
+            .id();
+        commands.entity(header).insert_children(0, &[switcher]);
+    }
+}
+
 pub(crate) fn spawn_pane<'a>(
     commands: &'a mut Commands,
     theme: &Theme,
-    size: f32,
     name: impl Into<String>,
 ) -> EntityCommands<'a> {
     let name: String = name.into();
-    // Unstyled root node
+    // Unstyled root node, filling its pane stack below the tab bar
     let root = commands
         .spawn((
             Node {
-                padding: UiRect::all(Val::Px(1.5)),
+                flex_grow: 1.,
+                min_height: Val::Px(0.),
                 ..default()
             },
-            Size(size),
             PaneRootNode { name: name.clone() },
         ))
         .id();
@@ -84,16 +144,6 @@ pub(crate) fn spawn_pane<'a>(
                     ..default()
                 })
                 .with_children(|parent| {
-                    // Drop down button for selecting the pane type.
-                    // Once a drop down menu is implemented, this will have that added.
-                    parent.spawn((
-                        Text::new(icons::CHEVRON_DOWN),
-                        TextFont {
-                            font: theme.icon.font.clone(),
-                            font_size: 16.0,
-                            ..default()
-                        },
-                    ));
                     parent.spawn((
                         Text::new(format!(" {name}")),
                         TextFont {
@@ -137,6 +187,38 @@ pub(crate) fn spawn_pane<'a>(
     commands.entity(root)
 }
 
+/// Spawn an area of the layout holding `panes` as tabs.
+pub(crate) fn spawn_pane_stack<'a>(
+    commands: &'a mut Commands,
+    size: f32,
+    panes: &[Entity],
+    active: Entity,
+) -> EntityCommands<'a> {
+    let stack = commands
+        .spawn((
+            Node {
+                flex_direction: FlexDirection::Column,
+                padding: UiRect::all(Val::Px(1.5)),
+                ..default()
+            },
+            Size(size),
+            PaneStack { active },
+        ))
+        .id();
+    commands.spawn((
+        Node {
+            flex_shrink: 0.,
+            column_gap: Val::Px(2.),
+            padding: UiRect::bottom(Val::Px(1.5)),
+            ..default()
+        },
+        PaneTabBar,
+        ChildOf(stack),
+    ));
+    commands.entity(stack).add_children(panes);
+    commands.entity(stack)
+}
+
 pub(crate) fn spawn_divider<'a>(
     commands: &'a mut Commands,
     divider: Divider,
@@ -52,6 +52,19 @@ pub struct TransformGizmoEvent {
     pub to: GlobalTransform,
     /// The kind of interaction that was performed.
     pub kind: InteractionKind,
+    /// The entities moved by the interaction, with their local transforms before and after it.
+    pub transformed: Vec<TransformedEntity>,
+}
+
+/// An entity moved by a transform gizmo interaction, see [`TransformGizmoEvent`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TransformedEntity {
+    /// The moved entity.
+    pub entity: Entity,
+    /// The local transform of the entity before the interaction.
+    pub from: Transform,
+    /// The local transform of the entity after the interaction.
+    pub to: Transform,
 }
 
 /// Marker component for entities that can be transformed by the transform gizmo.
@@ -71,7 +84,8 @@ pub struct ScaleGizmo;
 pub struct InternalGizmoCamera;
 
 /// Available gizmo modes.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+#[reflect(Default)]
 pub enum GizmoMode {
     /// Translation mode (W key).
     Translate,
@@ -88,7 +102,8 @@ impl Default for GizmoMode {
 }
 
 /// Settings for the [`TransformGizmoPlugin`].
-#[derive(Resource, Clone, Debug)]
+#[derive(Resource, Clone, Debug, Reflect)]
+#[reflect(Resource, Default)]
 pub struct TransformGizmoSettings {
     /// Control whether the transform gizmo is active.
     pub enabled: bool,
@@ -251,6 +266,8 @@ pub enum InteractionKind {
 #[derive(Component, Clone, Debug)]
 struct InitialTransform {
     transform: Transform,
+    /// The local transform of the entity, to report in the [`TransformGizmoEvent`].
+    local_transform: Transform,
     rotation_offset: Vec3,
 }
 
@@ -263,7 +280,12 @@ fn on_transform_gizmo_pointer_press(
     target_query: Query<(&InteractionKind, &ChildOf)>,
     mut query: Query<(&mut TransformGizmo, &GlobalTransform)>,
     selection: Res<EditorSelection>,
-    items_query: Query<(&GlobalTransform, Entity, Option<&TransformGizmoOffset>)>,
+    items_query: Query<(
+        &GlobalTransform,
+        &Transform,
+        Entity,
+        Option<&TransformGizmoOffset>,
+    )>,
     mut commands: Commands,
 ) {
     if trigger.button != PointerButton::Primary {
@@ -284,10 +306,11 @@ fn on_transform_gizmo_pointer_press(
     });
 
     // Dragging has started, store the initial position of all selected meshes
-    for (transform, entity, rotation_origin_offset) in items_query.iter() {
+    for (transform, local_transform, entity, rotation_origin_offset) in items_query.iter() {
         if selection.contains(entity) {
             commands.entity(entity).insert(InitialTransform {
                 transform: transform.compute_transform(),
+                local_transform: *local_transform,
                 rotation_offset: rotation_origin_offset
                     .map(|offset| offset.0)
                     .unwrap_or(Vec3::ZERO),
@@ -301,7 +324,7 @@ fn on_transform_gizmo_pointer_release(
     mut query: Query<(&mut TransformGizmo, &GlobalTransform)>,
     mut gizmo_events: EventWriter<TransformGizmoEvent>,
     mut commands: Commands,
-    initial_transform_query: Query<Entity, With<InitialTransform>>,
+    initial_transform_query: Query<(Entity, &InitialTransform, &Transform)>,
 ) {
     if trigger.button != PointerButton::Primary {
         return;
@@ -311,10 +334,20 @@ fn on_transform_gizmo_pointer_release(
     };
 
     if let (Some(from), Some(interaction)) = (gizmo.initial_transform, gizmo.interaction) {
+        let transformed = initial_transform_query
+            .iter()
+            .filter(|(_, initial, transform)| initial.local_transform != **transform)
+            .map(|(entity, initial, transform)| TransformedEntity {
+                entity,
+                from: initial.local_transform,
+                to: *transform,
+            })
+            .collect();
         let event = TransformGizmoEvent {
             from,
             to: *transform,
             kind: interaction.kind,
+            transformed,
         };
         gizmo_events.write(event);
         *gizmo = TransformGizmo::default();
@@ -322,7 +355,7 @@ fn on_transform_gizmo_pointer_release(
 
     *gizmo = default();
 
-    for entity in &initial_transform_query {
+    for (entity, ..) in &initial_transform_query {
         commands.entity(entity).remove::<InitialTransform>();
     }
 }
@@ -10,6 +10,8 @@
 //! - Support for custom undo/redo commands
 //! - Integration with Bevy's entity and component system
 //! - Efficient change tracking and storage
+//! - Transactions grouping the changes of an operation into a single labeled step
+//! - Optional persistence of the history next to the scene file, see [`PersistentHistory`]
 //! - Memory-efficient design using Arc for change storage
 //!
 //! # Usage
@@ -17,7 +19,8 @@
 //! To use this crate in your Bevy application:
 //!
 //! 1. Add the `UndoPlugin` to your app
-//! 2. Use the `auto_undo` or `auto_reflected_undo` methods to enable automatic undo for specific components
+//! 2. Use the `auto_undo` or `auto_reflected_undo` methods to enable automatic undo for specific components,
+//!    or `auto_reflected_undo_all` for every reflected component
 //! 3. Mark entities that should support undo/redo with the `UndoMarker` component
 //! 4. Use `UndoRedo` events to trigger undo and redo operations
 //!
@@ -107,7 +110,9 @@
 //!
 //! - Changes are stored individually, allowing for fine-grained control over the undo/redo history.
 //! - Only the differences between states are stored, not entire world snapshots.
-//! - The undo history has a configurable maximum size to prevent unbounded memory growth.
+//! - The undo history has a configurable maximum size to prevent unbounded memory growth, and
+//!   optionally a memory budget, see [`ChangeChainSettings::max_memory`]. The memory used by the
+//!   history is reported by the [`UndoPlugin::HISTORY_MEMORY`] diagnostic.
 //! - For complex operations involving multiple changes, the `ManyChanges` struct is used to group
 //!   related changes together, reducing overhead.
 //!
@@ -132,11 +137,34 @@
 
 // Remove after update to newer rust version
 #![allow(clippy::type_complexity)]
-use std::sync::Arc;
+use std::{
+    any::{Any, TypeId},
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use bevy::{
+    diagnostic::{Diagnostic, DiagnosticPath, Diagnostics, RegisterDiagnostic},
+    ecs::{
+        entity::{EntityHashMap, EntityMapper},
+        event::EventCursor,
+        query::QueryBuilder,
+        reflect::ReflectMapEntities,
+    },
+    platform::collections::HashMap,
+    prelude::*,
+    reflect::{NamedField, ReflectRef, TypeInfo, TypeRegistry, UnnamedField, VariantInfo},
+};
+
+mod persistence;
+mod reflected_auto_undo;
+
+pub use persistence::{
+    PersistContext, PersistedChange, PersistedHierarchy, PersistentHistory, StableEntityId,
+    load_history, save_history,
+};
+pub use reflected_auto_undo::ReflectNoAutoUndo;
 
-use bevy::{platform::collections::HashMap, prelude::*};
-
-const MAX_REFLECT_RECURSION: i32 = 10;
 const AUTO_UNDO_LATENCY: i32 = 2;
 
 /// Plugin for implementing undo/redo functionality
@@ -145,6 +173,7 @@ pub struct UndoPlugin;
 
 /// Components with this marker will be used for undo
 #[derive(Component)]
+#[require(StableEntityId)]
 pub struct UndoMarker;
 
 impl Plugin for UndoPlugin {
@@ -152,9 +181,11 @@ impl Plugin for UndoPlugin {
         app.init_resource::<ChangeChain>();
         app.init_resource::<UndoIgnoreStorage>();
         app.init_resource::<ChangeChainSettings>();
+        app.init_resource::<persistence::StableEntityIds>();
 
         app.add_event::<NewChange>();
         app.add_event::<UndoRedo>();
+        app.add_event::<UndoRedoFailed>();
 
         app.configure_sets(
             PostUpdate,
@@ -174,9 +205,34 @@ impl Plugin for UndoPlugin {
                 .chain()
                 .in_set(UndoSet::UpdateAll),
         );
+        app.add_systems(
+            PostUpdate,
+            persistence::persist_history.after(UndoSet::UpdateAll),
+        );
+        app.add_observer(persistence::cache_stable_entity_id);
+
+        app.register_diagnostic(Diagnostic::new(Self::HISTORY_MEMORY).with_suffix(" bytes"));
+        app.add_systems(
+            PostUpdate,
+            measure_history_memory
+                .after(UndoSet::UpdateAll)
+                .run_if(resource_changed::<ChangeChain>),
+        );
     }
 }
 
+impl UndoPlugin {
+    /// Diagnostic of the memory used by the undo history, in bytes, as estimated by
+    /// [`ChangeChain::memory_size`].
+    pub const HISTORY_MEMORY: DiagnosticPath = DiagnosticPath::const_new("undo_history_memory");
+}
+
+fn measure_history_memory(change_chain: Res<ChangeChain>, mut diagnostics: Diagnostics) {
+    diagnostics.add_measurement(&UndoPlugin::HISTORY_MEMORY, || {
+        change_chain.memory_size() as f64
+    });
+}
+
 /// Allows to make `UndoMarker` attached to another marker M so that
 /// if there is an entity with marker M, then `UndoMarker` will be added to that entity,
 /// and likewise, if there is an entity with `UndoMarker` but without marker M, then `UndoMarker` will be removed
@@ -214,7 +270,8 @@ pub enum UndoSet {
     /// Systems that manage the global undo/redo chain and logic.
     UpdateAll,
 
-    /// Systems responsible for remapping entities after undo/redo operations.
+    /// Runs after the undo/redo operations. Kept for the systems ordered against it, as entities
+    /// are now remapped by the undo/redo operations themselves.
     Remapping,
 
     /// A set containing all undo-related systems.
@@ -225,6 +282,8 @@ pub enum UndoSet {
 ///
 /// This event is useful for systems that need to react to undo/redo operations
 /// on specific component types, such as updating derived data or triggering side effects.
+/// The undo system itself doesn't read it, as entity references are remapped as part of the
+/// undo/redo, but it is still sent for the registered types for the systems reacting to it.
 ///
 /// # Type Parameters
 ///
@@ -291,53 +350,56 @@ impl Default for OneFrameUndoIgnore {
     }
 }
 
-fn update_change_chain(
-    mut buffer: Local<Vec<NewChange>>, //Buffer will use for chain reaction changes and collecting them together
-    settings: Res<ChangeChainSettings>,
-    mut change_chain: ResMut<ChangeChain>,
-    mut events: EventReader<NewChange>,
-) {
-    //collect buffer
-    let mut events_on_current_frame = 0;
-    for event in events.read() {
-        buffer.push(event.clone());
-        events_on_current_frame += 1;
-    }
-
-    if events_on_current_frame > 0 {
-        return;
-    }
-
-    if buffer.is_empty() {
-        return;
-    }
-
-    //Drop buffer to vec of arc
-    let mut new_changes = vec![];
-    for b in buffer.iter() {
-        new_changes.push(b.change.clone());
-        change_chain.changes_for_redo.clear();
-    }
-
-    //Clear buffer
-    buffer.clear();
+fn update_change_chain(world: &mut World) {
+    let received = collect_new_changes(world);
+    let now = world.resource::<Time<Real>>().elapsed();
+    world.resource_scope(|world, mut change_chain: Mut<ChangeChain>| {
+        // A transaction committed or cancelled this frame is finished
+        if change_chain
+            .transaction
+            .as_ref()
+            .is_some_and(|transaction| transaction.depth == 0 || transaction.cancelled)
+        {
+            change_chain.finish_transaction();
+        }
 
-    match new_changes.len().cmp(&1) {
-        std::cmp::Ordering::Less => {}
-        std::cmp::Ordering::Equal => {
-            change_chain.changes.push(new_changes[0].clone());
+        // Only mark the chain as changed when there are steps to record
+        let finished_steps = if change_chain.finished_steps.is_empty() {
+            Vec::new()
+        } else {
+            std::mem::take(&mut change_chain.finished_steps)
+        };
+        for step in finished_steps {
+            match step {
+                FinishedStep::Record(changes, label) => {
+                    let settings = world.resource::<ChangeChainSettings>();
+                    change_chain.push_step(changes, label, settings, now);
+                }
+                FinishedStep::Revert(changes) => change_chain.revert_cancelled(world, &changes),
+            }
         }
-        std::cmp::Ordering::Greater => {
-            change_chain.changes.push(Arc::new(ManyChanges {
-                changes: new_changes,
-            }));
+
+        // Changes sent in consecutive frames are recorded together, as they may be chain
+        // reactions to each other
+        if received > 0 || change_chain.pending_changes.is_empty() {
+            return;
         }
-    };
+        let changes = std::mem::take(&mut change_chain.pending_changes);
+        let settings = world.resource::<ChangeChainSettings>();
+        change_chain.push_step(changes, None, settings, now);
+    });
+}
 
-    if change_chain.changes.len() > settings.max_change_chain_size {
-        let count = change_chain.changes.len() - settings.max_change_chain_size;
-        change_chain.changes.drain(0..count);
-    }
+/// Collect the changes sent since the last call, see [`ChangeChain::collect_new_changes`].
+fn collect_new_changes(world: &mut World) -> usize {
+    world.resource_scope(|world, mut change_chain: Mut<ChangeChain>| {
+        let events = world.resource::<Events<NewChange>>();
+        // Only mark the chain as changed when there are changes to collect
+        if change_chain.new_changes.is_empty(events) {
+            return 0;
+        }
+        change_chain.collect_new_changes(events)
+    })
 }
 
 fn clear_one_frame_ignore(
@@ -358,28 +420,14 @@ fn undo_redo_logic(world: &mut World) {
             {
                 let mut reader = events.get_cursor();
                 for event in reader.read(&events) {
-                    match event {
-                        UndoRedo::Undo => {
-                            if let Some(change) = change_chain.changes.pop() {
-                                let res = change.revert(world, &change_chain.entity_remap).unwrap();
-                                if let ChangeResult::SuccessWithRemap(remap) = res {
-                                    change_chain.entity_remap.extend(remap);
-                                }
-                                change_chain.changes_for_redo.push(change);
-                            }
-                        }
-                        UndoRedo::Redo => {
-                            if let Some(change) = change_chain.changes_for_redo.pop() {
-                                let inverse_change = change.get_inverse();
-                                let res = inverse_change
-                                    .revert(world, &change_chain.entity_remap)
-                                    .unwrap();
-                                if let ChangeResult::SuccessWithRemap(remap) = res {
-                                    change_chain.entity_remap.extend(remap);
-                                }
-                                change_chain.changes.push(change);
-                            }
-                        }
+                    let result = match event {
+                        UndoRedo::Undo => change_chain.undo(world),
+                        UndoRedo::Redo => change_chain.redo(world),
+                        UndoRedo::JumpTo(position) => change_chain.jump_to(world, *position),
+                    };
+                    if let Err(failed) = result {
+                        warn!("{failed}");
+                        world.write_event(failed);
                     }
                 }
             }
@@ -399,6 +447,37 @@ pub struct ChangeChain {
     /// We need to store entity remapping if any of the entities changed their id by
     /// destroying/spawning, and to handle entity links in component fields.
     entity_remap: HashMap<Entity, Entity>,
+    /// The transaction grouping the new changes, if any.
+    transaction: Option<Transaction>,
+    /// When the last step was recorded, for merging the next changes into it.
+    last_step_time: Option<Duration>,
+    /// The reader of the [`NewChange`] events.
+    new_changes: EventCursor<NewChange>,
+    /// The changes sent outside of a transaction, not yet recorded as a step.
+    pending_changes: Vec<Arc<dyn EditorChange + Send + Sync>>,
+    /// The steps finished this frame, in order, not yet recorded.
+    finished_steps: Vec<FinishedStep>,
+    /// The memory used by the changes, see [`memory_size`](Self::memory_size).
+    memory: usize,
+}
+
+/// Changes to be recorded as one step, or reverted, at the end of the frame.
+enum FinishedStep {
+    /// Changes recorded as a step, labeled if they were made in a transaction.
+    Record(Vec<Arc<dyn EditorChange + Send + Sync>>, Option<String>),
+    /// The changes of a cancelled transaction, reverted instead of being recorded.
+    Revert(Vec<Arc<dyn EditorChange + Send + Sync>>),
+}
+
+/// Changes grouped into a single labeled step of the change chain, see
+/// [`ChangeChain::begin_transaction`].
+struct Transaction {
+    label: String,
+    changes: Vec<Arc<dyn EditorChange + Send + Sync>>,
+    /// How many nested transactions are still to be committed.
+    depth: usize,
+    /// Whether the transaction was cancelled, discarding its changes.
+    cancelled: bool,
 }
 
 /// Settings for `ChangeChain` resource
@@ -407,46 +486,506 @@ pub struct ChangeChain {
 pub struct ChangeChainSettings {
     /// Maximum number of changes in the change chain that can be stored
     pub max_change_chain_size: usize,
+    /// How long after a change a following change is merged into it, if they can be, see
+    /// [`EditorChange::merge`].
+    pub merge_window: Duration,
+    /// Memory budget of the change chain, in bytes, as estimated by [`EditorChange::memory_size`].
+    /// The oldest changes are dropped when it is exceeded. `None` means no budget.
+    pub max_memory: Option<usize>,
 }
 
 impl Default for ChangeChainSettings {
     fn default() -> Self {
         Self {
             max_change_chain_size: 200,
+            merge_window: Duration::from_secs(1),
+            max_memory: None,
         }
     }
 }
 
 impl ChangeChain {
     /// Undo last registered change
-    pub fn undo(&mut self, world: &mut World) {
-        if let Some(change) = self.changes.pop() {
-            let res = change.revert(world, &self.entity_remap).unwrap();
-            self.changes_for_redo.push(change);
-            self.update_remap(res);
+    ///
+    /// If the change fails to revert, it is dropped from the chain along with the changes for redo,
+    /// which were made on top of it, and the failure is returned.
+    pub fn undo(&mut self, world: &mut World) -> Result<(), UndoRedoFailed> {
+        let Some(change) = self.changes.pop() else {
+            return Ok(());
+        };
+        // The next changes shouldn't be merged into a step which was undone or redone
+        self.last_step_time = None;
+        // Reverting a change can change its memory, such as the snapshot an added entity keeps
+        self.memory = self.memory.saturating_sub(change.memory_size());
+        match change.revert(world, &self.entity_remap) {
+            Ok(res) => {
+                self.memory += change.memory_size();
+                self.changes_for_redo.push(change);
+                self.update_remap(world, res);
+                Ok(())
+            }
+            Err(error) => {
+                self.clear_changes_for_redo();
+                Err(UndoRedoFailed {
+                    operation: UndoRedo::Undo,
+                    change_debug_text: change.debug_text(),
+                    error,
+                })
+            }
         }
     }
 
     /// Redo last undone change
-    pub fn redo(&mut self, world: &mut World) {
-        if let Some(change) = self.changes_for_redo.pop() {
-            let inverse_change = change.get_inverse();
-            let res = inverse_change.revert(world, &self.entity_remap).unwrap();
-            self.changes.push(change);
-            self.update_remap(res);
+    ///
+    /// If the change fails to be reapplied, it is dropped from the chain along with the remaining
+    /// changes for redo, which were made on top of it, and the failure is returned.
+    pub fn redo(&mut self, world: &mut World) -> Result<(), UndoRedoFailed> {
+        let Some(change) = self.changes_for_redo.pop() else {
+            return Ok(());
+        };
+        self.last_step_time = None;
+        let inverse_change = change.get_inverse();
+        match inverse_change.revert(world, &self.entity_remap) {
+            Ok(res) => {
+                self.changes.push(change);
+                self.update_remap(world, res);
+                Ok(())
+            }
+            Err(error) => {
+                self.memory = self.memory.saturating_sub(change.memory_size());
+                self.clear_changes_for_redo();
+                Err(UndoRedoFailed {
+                    operation: UndoRedo::Redo,
+                    change_debug_text: change.debug_text(),
+                    error,
+                })
+            }
+        }
+    }
+
+    /// Undo or redo changes until `position` changes are applied, the other ones being left for
+    /// redo.
+    ///
+    /// Stops at the first change which fails to be undone or redone, which is dropped as by
+    /// [`undo`](Self::undo) and [`redo`](Self::redo), and returns the failure.
+    pub fn jump_to(&mut self, world: &mut World, position: usize) -> Result<(), UndoRedoFailed> {
+        while self.changes.len() > position {
+            self.undo(world)?;
+        }
+        while self.changes.len() < position && !self.changes_for_redo.is_empty() {
+            self.redo(world)?;
+        }
+        Ok(())
+    }
+
+    /// Begin grouping the new changes into a single undo step labeled `label`, until
+    /// [`commit_transaction`](Self::commit_transaction) is called.
+    ///
+    /// Unlike the changes sent in consecutive frames, which are grouped automatically, the changes
+    /// of a transaction make exactly one step however many frames they span. Transactions can be
+    /// nested, the changes of the inner ones being part of the outermost one.
+    ///
+    /// The changes already sent as `new_changes` are collected first, so that the changes sent
+    /// earlier in the frame are not part of the transaction.
+    pub fn begin_transaction(&mut self, label: impl Into<String>, new_changes: &Events<NewChange>) {
+        self.collect_new_changes(new_changes);
+        match &mut self.transaction {
+            Some(transaction) if transaction.depth > 0 && !transaction.cancelled => {
+                transaction.depth += 1;
+            }
+            _ => {
+                // A transaction committed or cancelled earlier this frame is a step of its own, as
+                // are the changes sent before this transaction began.
+                self.finish_transaction();
+                if !self.pending_changes.is_empty() {
+                    let changes = std::mem::take(&mut self.pending_changes);
+                    self.finished_steps
+                        .push(FinishedStep::Record(changes, None));
+                }
+                self.transaction = Some(Transaction {
+                    label: label.into(),
+                    changes: vec![],
+                    depth: 1,
+                    cancelled: false,
+                });
+            }
+        }
+    }
+
+    /// Commit the current transaction.
+    ///
+    /// Its changes are recorded as one step once the changes sent this frame have been collected,
+    /// so that the changes sent right before committing are part of the transaction.
+    pub fn commit_transaction(&mut self) {
+        match &mut self.transaction {
+            Some(transaction) if transaction.depth > 0 => transaction.depth -= 1,
+            _ => warn!("Committed an undo transaction which was not begun"),
+        }
+    }
+
+    /// Cancel the current transaction, along with the transactions it is nested in.
+    ///
+    /// The changes sent during the transaction are reverted, latest first, once the changes sent
+    /// this frame have been collected, and are not recorded.
+    pub fn cancel_transaction(&mut self) {
+        match &mut self.transaction {
+            Some(transaction) => transaction.cancelled = true,
+            None => warn!("Cancelled an undo transaction which was not begun"),
+        }
+    }
+
+    /// Collect the changes sent since the last call into the current transaction, if any, or into
+    /// the changes waiting to be recorded as a step. Returns how many changes were sent outside of
+    /// a transaction.
+    fn collect_new_changes(&mut self, events: &Events<NewChange>) -> usize {
+        let mut received = 0;
+        for event in self.new_changes.read(events) {
+            if let Some(transaction) = &mut self.transaction {
+                transaction.changes.push(event.change.clone());
+            } else {
+                self.pending_changes.push(event.change.clone());
+                received += 1;
+            }
+        }
+        received
+    }
+
+    /// Queue the changes of the current transaction, if any, to be recorded as a step, or reverted
+    /// if it was cancelled.
+    fn finish_transaction(&mut self) {
+        let Some(transaction) = self.transaction.take() else {
+            return;
+        };
+        self.finished_steps.push(if transaction.cancelled {
+            FinishedStep::Revert(transaction.changes)
+        } else {
+            FinishedStep::Record(transaction.changes, Some(transaction.label))
+        });
+    }
+
+    /// Revert the changes of a cancelled transaction, latest first.
+    fn revert_cancelled(
+        &mut self,
+        world: &mut World,
+        changes: &[Arc<dyn EditorChange + Send + Sync>],
+    ) {
+        for change in changes.iter().rev() {
+            match change.revert(world, &self.entity_remap) {
+                Ok(result) => self.update_remap(world, result),
+                Err(error) => warn!(
+                    "Failed to revert \"{}\" of a cancelled transaction: {error}",
+                    change.debug_text()
+                ),
+            }
+        }
+    }
+
+    /// Whether new changes are currently grouped into a transaction.
+    pub fn in_transaction(&self) -> bool {
+        self.transaction
+            .as_ref()
+            .is_some_and(|transaction| transaction.depth > 0 && !transaction.cancelled)
+    }
+
+    /// Record changes made together as a single step, dropping the oldest steps over the size
+    /// limit.
+    ///
+    /// Unlabeled steps following the last step within [`ChangeChainSettings::merge_window`] are
+    /// merged into it when possible, see [`EditorChange::merge`].
+    fn push_step(
+        &mut self,
+        changes: Vec<Arc<dyn EditorChange + Send + Sync>>,
+        label: Option<String>,
+        settings: &ChangeChainSettings,
+        now: Duration,
+    ) {
+        let mut changes = merge_consecutive(changes);
+        if changes.is_empty() {
+            return;
+        }
+        self.clear_changes_for_redo();
+
+        let mergeable = label.is_none()
+            && self
+                .last_step_time
+                .is_some_and(|time| now.saturating_sub(time) <= settings.merge_window);
+        self.last_step_time = Some(now);
+
+        let step: Arc<dyn EditorChange + Send + Sync> = match label {
+            Some(label) => Arc::new(ManyChanges::with_label(changes, label)),
+            None if changes.len() == 1 => changes.remove(0),
+            None => Arc::new(ManyChanges::new(changes)),
+        };
+
+        let merged = self
+            .changes
+            .last()
+            .filter(|_| mergeable)
+            .and_then(|last| last.merge(step.as_ref()));
+        if let Some(merged) = merged {
+            let last = self.changes.pop().unwrap();
+            self.memory = self.memory.saturating_sub(last.memory_size()) + merged.memory_size();
+            self.changes.push(merged);
+        } else {
+            self.memory += step.memory_size();
+            self.changes.push(step);
+        }
+
+        if self.changes.len() > settings.max_change_chain_size {
+            let count = self.changes.len() - settings.max_change_chain_size;
+            let dropped: usize = self
+                .changes
+                .drain(0..count)
+                .map(|change| change.memory_size())
+                .sum();
+            self.memory = self.memory.saturating_sub(dropped);
+        }
+        if let Some(max_memory) = settings.max_memory {
+            self.evict_to_memory_budget(max_memory);
         }
     }
 
+    /// Drop the oldest changes until the history fits in `max_memory` bytes. The latest change is
+    /// always kept, even when it doesn't fit on its own.
+    fn evict_to_memory_budget(&mut self, max_memory: usize) {
+        let mut memory = self.memory_size();
+        let evicted = self
+            .changes
+            .iter()
+            .take(self.changes.len().saturating_sub(1))
+            .take_while(|change| {
+                let over_budget = memory > max_memory;
+                if over_budget {
+                    memory -= change.memory_size();
+                }
+                over_budget
+            })
+            .count();
+        if evicted > 0 {
+            debug!("Evicted {evicted} undo steps to fit in the history memory budget");
+            self.changes.drain(0..evicted);
+            self.memory = memory;
+        }
+    }
+
+    /// Drop the changes for redo, which can't be redone anymore.
+    fn clear_changes_for_redo(&mut self) {
+        let dropped: usize = self
+            .changes_for_redo
+            .drain(..)
+            .map(|change| change.memory_size())
+            .sum();
+        self.memory = self.memory.saturating_sub(dropped);
+    }
+
+    /// Returns an estimate of the memory used by the recorded changes, including the changes for
+    /// redo, in bytes. See [`EditorChange::memory_size`].
+    ///
+    /// The estimate is kept up to date as changes are recorded, undone and redone, rather than
+    /// measured on each call. Changes pushed to [`changes`](Self::changes) or
+    /// [`changes_for_redo`](Self::changes_for_redo) directly are not accounted for.
+    pub fn memory_size(&self) -> usize {
+        self.memory
+    }
+
     /// Update destroyed-entity->new-entity mapping for handling entities links after undo / redo
-    fn update_remap(&mut self, result: ChangeResult) {
+    fn update_remap(&mut self, world: &mut World, result: ChangeResult) {
         match result {
             ChangeResult::Success => {}
             ChangeResult::SuccessWithRemap(new_remap) => {
-                for (prev, new) in new_remap {
-                    self.entity_remap.insert(prev, new);
+                if new_remap.is_empty() {
+                    return;
+                }
+                // References to entities remapped earlier were already updated at the time
+                let new_remap: HashMap<Entity, Entity> = new_remap.into_iter().collect();
+                self.entity_remap
+                    .extend(new_remap.iter().map(|(&prev, &new)| (prev, new)));
+                remap_entity_references(world, &new_remap);
+            }
+        }
+    }
+}
+
+/// Map the entity references held by the components of every entity, such as [`ChildOf`], from
+/// the entities destroyed to the entities respawned in their place by undo or redo.
+///
+/// Components are remapped with their [`ReflectComponent`], which maps the entities the way their
+/// [`MapEntities`](bevy::ecs::entity::MapEntities) implementation does, and are inserted again so
+/// that relationships update their targets. Only the components which can hold entities are
+/// visited, and only the ones holding remapped entities are inserted again.
+fn remap_entity_references(world: &mut World, entity_remap: &HashMap<Entity, Entity>) {
+    let type_registry = world.resource::<AppTypeRegistry>().clone();
+    let type_registry = type_registry.read();
+
+    let mut checked = HashMap::default();
+    let components: Vec<_> = type_registry
+        .iter()
+        // Children follow the `ChildOf` of the children, and can't be inserted on their own
+        .filter(|registration| registration.type_id() != TypeId::of::<Children>())
+        .filter_map(|registration| {
+            let reflect_component = registration.data::<ReflectComponent>()?;
+            let component_id = world.components().get_id(registration.type_id())?;
+            let holds_entities = registration.contains::<ReflectMapEntities>()
+                || can_hold_entities(registration.type_info(), &mut checked);
+            holds_entities.then_some((component_id, reflect_component))
+        })
+        .collect();
+
+    let mut remapped = Vec::new();
+    for (component_id, reflect_component) in components {
+        let mut entities = QueryBuilder::<EntityRef>::new(world)
+            .with_id(component_id)
+            .build();
+        for entity_ref in entities.iter(world) {
+            let Some(Ok(mut component)) = reflect_component
+                .reflect(entity_ref)
+                .map(PartialReflect::reflect_clone)
+            else {
+                continue;
+            };
+
+            let mut mapper = RemapEntityMapper {
+                entity_remap,
+                changed: false,
+            };
+            reflect_component.map_entities(component.as_mut(), &mut mapper);
+            if mapper.changed {
+                remapped.push((entity_ref.id(), reflect_component, component));
+            }
+        }
+    }
+
+    for (entity, reflect_component, component) in remapped {
+        let mut entity_mut = world.entity_mut(entity);
+        reflect_component.insert(
+            &mut entity_mut,
+            component.as_partial_reflect(),
+            &type_registry,
+        );
+        entity_mut.insert(OneFrameUndoIgnore::default());
+    }
+}
+
+/// Whether values of a type can hold entities, in its fields or the fields of its fields, as told
+/// by its [`TypeInfo`]. `checked` holds the types already checked, with the result.
+fn can_hold_entities(type_info: &TypeInfo, checked: &mut HashMap<TypeId, bool>) -> bool {
+    let type_id = type_info.type_id();
+    if type_id == TypeId::of::<Entity>() {
+        return true;
+    }
+    if let Some(&holds_entities) = checked.get(&type_id) {
+        return holds_entities;
+    }
+    // Recursive types aren't checked again within themselves
+    checked.insert(type_id, false);
+
+    let fields: Vec<Option<&'static TypeInfo>> = match type_info {
+        TypeInfo::Struct(info) => info.iter().map(NamedField::type_info).collect(),
+        TypeInfo::TupleStruct(info) => info.iter().map(UnnamedField::type_info).collect(),
+        TypeInfo::Tuple(info) => info.iter().map(UnnamedField::type_info).collect(),
+        TypeInfo::List(info) => vec![info.item_info()],
+        TypeInfo::Array(info) => vec![info.item_info()],
+        TypeInfo::Map(info) => vec![info.key_info(), info.value_info()],
+        TypeInfo::Enum(info) => info
+            .iter()
+            .flat_map(|variant| match variant {
+                VariantInfo::Struct(info) => {
+                    info.iter().map(NamedField::type_info).collect::<Vec<_>>()
                 }
+                VariantInfo::Tuple(info) => info.iter().map(UnnamedField::type_info).collect(),
+                VariantInfo::Unit(_) => Vec::new(),
+            })
+            .collect(),
+        _ => Vec::new(),
+    };
+    let holds_entities = fields
+        .into_iter()
+        .flatten()
+        .any(|info| can_hold_entities(info, checked));
+    checked.insert(type_id, holds_entities);
+    holds_entities
+}
+
+/// Maps the entities of [`ChangeChain::entity_remap`], following the entities respawned several
+/// times to their latest instance, and records whether any entity was mapped.
+struct RemapEntityMapper<'a> {
+    entity_remap: &'a HashMap<Entity, Entity>,
+    changed: bool,
+}
+
+impl EntityMapper for RemapEntityMapper<'_> {
+    fn get_mapped(&mut self, source: Entity) -> Entity {
+        let mut entity = source;
+        // Bounded by the number of remapped entities, in case of a cycle
+        for _ in 0..self.entity_remap.len() {
+            match self.entity_remap.get(&entity) {
+                Some(&mapped) if mapped != entity => entity = mapped,
+                _ => break,
+            }
+        }
+        self.changed |= entity != source;
+        entity
+    }
+
+    fn set_mapped(&mut self, _source: Entity, _target: Entity) {}
+}
+
+/// Merge the consecutive changes which can be, see [`EditorChange::merge`].
+fn merge_consecutive(
+    changes: Vec<Arc<dyn EditorChange + Send + Sync>>,
+) -> Vec<Arc<dyn EditorChange + Send + Sync>> {
+    let mut merged: Vec<Arc<dyn EditorChange + Send + Sync>> = Vec::with_capacity(changes.len());
+    for change in changes {
+        let merged_change = merged.last().and_then(|last| last.merge(change.as_ref()));
+        match merged_change {
+            Some(merged_change) => {
+                merged.pop();
+                merged.push(merged_change);
             }
+            None => merged.push(change),
+        }
+    }
+    merged
+}
+
+/// Returns an estimate of the memory used by a boxed reflected value, in bytes, for
+/// [`EditorChange::memory_size`].
+pub fn reflect_memory_size(value: &dyn PartialReflect) -> usize {
+    size_of_val(value) + reflect_heap_size(value)
+}
+
+/// Returns an estimate of the memory a reflected value uses on the heap, such as the elements of
+/// its lists and the contents of its strings.
+pub fn reflect_heap_size(value: &dyn PartialReflect) -> usize {
+    let fields_heap_size = |fields: &mut dyn Iterator<Item = &dyn PartialReflect>| {
+        fields.map(reflect_heap_size).sum::<usize>()
+    };
+    let elements_size = |elements: &mut dyn Iterator<Item = &dyn PartialReflect>| {
+        elements.map(reflect_memory_size).sum::<usize>()
+    };
+
+    match value.reflect_ref() {
+        ReflectRef::Struct(value) => fields_heap_size(&mut value.iter_fields()),
+        ReflectRef::TupleStruct(value) => fields_heap_size(&mut value.iter_fields()),
+        ReflectRef::Tuple(value) => fields_heap_size(&mut value.iter_fields()),
+        ReflectRef::Enum(value) => {
+            fields_heap_size(&mut value.iter_fields().map(|field| field.value()))
+        }
+        ReflectRef::List(value) => elements_size(&mut value.iter()),
+        ReflectRef::Set(value) => elements_size(&mut value.iter()),
+        ReflectRef::Map(value) => {
+            elements_size(&mut value.iter().flat_map(|(key, value)| [key, value]))
         }
+        // Arrays are stored inline
+        ReflectRef::Array(value) => fields_heap_size(&mut value.iter()),
+        ReflectRef::Opaque(value) => value
+            .try_downcast_ref::<String>()
+            .map_or(0, String::capacity),
+        #[allow(
+            unreachable_patterns,
+            reason = "Functions are only reflected with the `reflect_functions` feature"
+        )]
+        _ => 0,
     }
 }
 
@@ -456,7 +995,7 @@ pub fn get_entity_with_remap(entity: Entity, entity_remap: &HashMap<Entity, Enti
 }
 
 /// Change, which can be stored in the change chain
-pub trait EditorChange {
+pub trait EditorChange: Any {
     /// Revert all changes applied to the world by this change
     fn revert(
         &self,
@@ -473,6 +1012,35 @@ pub trait EditorChange {
     /// for `despawn()` -> `spawn()`
     /// for insert component -> remove component
     fn get_inverse(&self) -> Arc<dyn EditorChange + Send + Sync>;
+
+    /// Returns a single change going from the state before this change to the state after `next`,
+    /// if `next` continues this change, such as another edit of the same component.
+    ///
+    /// This lets continuous edits, such as dragging a value or typing in a field, make a single
+    /// undo step instead of one per frame or keystroke. Changes are not merged by default.
+    fn merge(
+        &self,
+        next: &(dyn EditorChange + Send + Sync),
+    ) -> Option<Arc<dyn EditorChange + Send + Sync>> {
+        let _ = next;
+        None
+    }
+
+    /// Returns an estimate of the memory used by this change, in bytes, including the values it
+    /// stores on the heap. This is what [`ChangeChainSettings::max_memory`] is checked against.
+    ///
+    /// The default only counts the size of the change itself, changes storing values on the heap
+    /// should add their size, e.g. with [`reflect_memory_size`].
+    fn memory_size(&self) -> usize {
+        size_of_val(self)
+    }
+
+    /// Returns the form of this change saved to disk by [`PersistentHistory`], or `None` if it
+    /// can't be persisted, which is the default.
+    fn persist(&self, context: &PersistContext) -> Option<PersistedChange> {
+        let _ = context;
+        None
+    }
 }
 
 /// Represents the result of applying or reverting a change in the undo/redo system.
@@ -485,13 +1053,46 @@ pub enum ChangeResult {
     SuccessWithRemap(Vec<(Entity, Entity)>),
 }
 /// Represents an undo or redo operation to be performed on the change chain.
-#[derive(Event, BufferedEvent)]
+#[derive(Event, BufferedEvent, Clone, Copy, Debug, PartialEq, Eq)]
 pub enum UndoRedo {
     /// Requests to undo the last change in the change chain.
     Undo,
 
     /// Requests to redo the last undone change in the change chain.
     Redo,
+
+    /// Requests to undo or redo changes until the given number of changes are applied, see
+    /// [`ChangeChain::jump_to`].
+    JumpTo(usize),
+}
+
+/// An event that is sent when a change fails to be undone or redone.
+///
+/// The failed change is dropped from the [`ChangeChain`], see [`ChangeChain::undo`] and
+/// [`ChangeChain::redo`].
+#[derive(Event, BufferedEvent, Clone, Debug)]
+pub struct UndoRedoFailed {
+    /// Whether the change failed to be undone or redone.
+    pub operation: UndoRedo,
+    /// The [`EditorChange::debug_text`] of the failed change.
+    pub change_debug_text: String,
+    /// The error returned by the change.
+    pub error: String,
+}
+
+impl std::fmt::Display for UndoRedoFailed {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let operation = match self.operation {
+            UndoRedo::Undo => "undo",
+            UndoRedo::Redo => "redo",
+            UndoRedo::JumpTo(_) => "jump to",
+        };
+        write!(
+            f,
+            "Failed to {operation} \"{}\": {}",
+            self.change_debug_text, self.error
+        )
+    }
 }
 
 /// Represents a new change to be added to the change chain.
@@ -510,13 +1111,79 @@ impl NewChange {
     }
 }
 
+/// Extension trait for [`Commands`] to group the changes of an operation into one undo step.
+pub trait UndoTransactionCommandsExt {
+    /// Run `operation` within an undo transaction labeled `label`, so that the changes it sends
+    /// are recorded as a single step, see [`ChangeChain::begin_transaction`].
+    fn undo_transaction(
+        &mut self,
+        label: impl Into<String>,
+        operation: impl FnOnce(&mut World) + Send + 'static,
+    );
+}
+
+impl UndoTransactionCommandsExt for Commands<'_, '_> {
+    fn undo_transaction(
+        &mut self,
+        label: impl Into<String>,
+        operation: impl FnOnce(&mut World) + Send + 'static,
+    ) {
+        let label = label.into();
+        self.queue(move |world: &mut World| {
+            // Collect the changes right away, so that they are told apart from the changes sent
+            // before and after the operation in the same frame.
+            world.resource_scope(|world, mut change_chain: Mut<ChangeChain>| {
+                change_chain.begin_transaction(label, world.resource::<Events<NewChange>>());
+            });
+            operation(world);
+            collect_new_changes(world);
+            world.resource_mut::<ChangeChain>().commit_transaction();
+        });
+    }
+}
+
+/// Extension trait for [`EntityCommands`] to despawn entities so that undoing respawns them.
+pub trait UndoDespawnEntityCommandsExt {
+    /// Despawn the entity and its descendants, recording a [`RemovedEntityHierarchy`] so that
+    /// undoing the despawn respawns them with their components.
+    fn despawn_with_undo(&mut self);
+}
+
+impl UndoDespawnEntityCommandsExt for EntityCommands<'_> {
+    fn despawn_with_undo(&mut self) {
+        let entity = self.id();
+        self.commands().queue(move |world: &mut World| {
+            let Some(change) = RemovedEntityHierarchy::capture(world, entity) else {
+                warn!("Entity {entity} to despawn with undo does not exist");
+                return;
+            };
+            world.entity_mut(entity).despawn();
+            world.write_event(NewChange::new(change));
+        });
+    }
+}
+
 /// Represents an change for adding an entity to the world.
 ///
 /// This struct is used to revert the spawning of an entity by storing its ID,
 /// allowing the undo system to remove it when necessary.
+/// The entity and its descendants are captured when they are despawned, so that reverting the
+/// inverse change respawns them as they were.
 pub struct AddedEntity {
     /// The ID of the entity that was added to the world.
     pub entity: Entity,
+    /// The entity and its descendants as they were when the change was last reverted.
+    removed: Mutex<Option<Arc<RemovedEntityHierarchy>>>,
+}
+
+impl AddedEntity {
+    /// Record the addition of `entity` to the world.
+    pub fn new(entity: Entity) -> Self {
+        Self {
+            entity,
+            removed: Mutex::default(),
+        }
+    }
 }
 
 impl EditorChange for AddedEntity {
@@ -526,11 +1193,21 @@ impl EditorChange for AddedEntity {
         entity_remap: &HashMap<Entity, Entity>,
     ) -> Result<ChangeResult, String> {
         let e = get_entity_with_remap(self.entity, entity_remap);
+        let Some(removed) = RemovedEntityHierarchy::capture(world, e) else {
+            return Err(format!("Entity {e} of AddedEntity does not exist"));
+        };
         world.entity_mut(e).despawn();
-        world
-            .resource_mut::<UndoIgnoreStorage>()
-            .storage
-            .insert(e, OneFrameUndoIgnore::default());
+
+        let mut entities = vec![];
+        removed.snapshot.entities(&mut entities);
+        let mut ignore_storage = world.resource_mut::<UndoIgnoreStorage>();
+        for entity in entities {
+            ignore_storage
+                .storage
+                .insert(entity, OneFrameUndoIgnore::default());
+        }
+        *self.removed.lock().unwrap() = Some(Arc::new(removed));
+
         info!("Removed Entity: {}", e.index());
         Ok(ChangeResult::Success)
     }
@@ -540,9 +1217,26 @@ impl EditorChange for AddedEntity {
     }
 
     fn get_inverse(&self) -> Arc<dyn EditorChange + Send + Sync> {
-        Arc::new(RemovedEntity {
-            entity: self.entity,
-        })
+        match self.removed.lock().unwrap().clone() {
+            Some(removed) => Arc::new(RespawnedEntity {
+                entity: self.entity,
+                removed,
+            }),
+            None => Arc::new(RemovedEntity {
+                entity: self.entity,
+            }),
+        }
+    }
+
+    fn persist(&self, context: &PersistContext) -> Option<PersistedChange> {
+        Some(PersistedChange::EntityAdded(
+            context.stable_id(self.entity)?,
+        ))
+    }
+
+    fn memory_size(&self) -> usize {
+        let removed = self.removed.lock().unwrap();
+        size_of::<Self>() + removed.as_ref().map_or(0, |removed| removed.memory_size())
     }
 }
 
@@ -550,6 +1244,8 @@ impl EditorChange for AddedEntity {
 ///
 /// This struct is used to revert the removal of an entity by storing its ID,
 /// allowing the undo system to respawn entity when necessary.
+/// The entity is respawned empty, use [`RemovedEntityHierarchy`] to restore its components and
+/// descendants as well, as recorded by [`UndoDespawnEntityCommandsExt::despawn_with_undo`].
 pub struct RemovedEntity {
     /// The ID of the entity that was removed from the world.
     pub entity: Entity,
@@ -588,828 +1284,1694 @@ impl EditorChange for RemovedEntity {
     }
 
     fn get_inverse(&self) -> Arc<dyn EditorChange + Send + Sync> {
-        Arc::new(AddedEntity {
-            entity: self.entity,
-        })
+        Arc::new(AddedEntity::new(self.entity))
+    }
+
+    fn persist(&self, context: &PersistContext) -> Option<PersistedChange> {
+        Some(PersistedChange::EntityRemoved(
+            context.stable_id(self.entity)?,
+        ))
     }
 }
 
-/// Represents an changing a component in an entity.
-///
-/// This struct stores both the old and new values of a component, as well as
-/// the entity ID, allowing the undo system to revert changes to components.
-///
-/// # Type Parameters
-///
-/// * `T`: The type of the component that was changed. Must implement the `Component` trait.
-pub struct ComponentChange<T: Component> {
-    /// The previous value of the component before the change.
-    old_value: T,
-    /// The new value of the component after the change.
-    new_value: T,
-    /// The ID of the entity whose component was changed.
+/// The inverse of an [`AddedEntity`] which was reverted, respawning the entity and its
+/// descendants as they were captured when they were despawned.
+struct RespawnedEntity {
+    /// The ID of the entity when it was added to the world.
     entity: Entity,
+    removed: Arc<RemovedEntityHierarchy>,
 }
 
-impl<T: Component + Clone> EditorChange for ComponentChange<T> {
+impl EditorChange for RespawnedEntity {
     fn revert(
         &self,
         world: &mut World,
         entity_remap: &HashMap<Entity, Entity>,
     ) -> Result<ChangeResult, String> {
-        let e = get_entity_with_remap(self.entity, entity_remap);
-
-        world
-            .entity_mut(e)
-            .insert(self.old_value.clone())
-            .insert(OneFrameUndoIgnore::default());
-        info!("Reverted ComponentChange for entity: {}", e.index());
-        Ok(ChangeResult::Success)
+        let mut result = self.removed.revert(world, entity_remap)?;
+        // The snapshot knows the entity by the ID it had when it was despawned, which differs from
+        // the ID the other changes know it by if it was respawned before.
+        let captured = self.removed.snapshot.entity;
+        if let ChangeResult::SuccessWithRemap(pairs) = &mut result
+            && captured != self.entity
+            && let Some(&(_, new)) = pairs.iter().find(|(old, _)| *old == captured)
+        {
+            pairs.push((self.entity, new));
+        }
+        Ok(result)
     }
 
     fn debug_text(&self) -> String {
-        format!("ComponentChange for entity {:?}", self.entity)
+        format!("Removed Entity: {}", self.entity.index())
     }
 
     fn get_inverse(&self) -> Arc<dyn EditorChange + Send + Sync> {
-        Arc::new(ComponentChange {
-            old_value: self.new_value.clone(),
-            new_value: self.old_value.clone(),
-            entity: self.entity,
-        })
+        Arc::new(AddedEntity::new(self.entity))
+    }
+
+    fn persist(&self, context: &PersistContext) -> Option<PersistedChange> {
+        self.removed.persist(context)
+    }
+
+    fn memory_size(&self) -> usize {
+        size_of::<Self>() + self.removed.memory_size()
     }
 }
 
-/// Represents a change in a component that supports reflection.
-///
-/// This struct is used to track changes to components that implement `Component`, `Reflect`, and `FromReflect` traits.
-/// It stores both the old and new values of the component, as well as the entity ID, allowing the undo system
-/// to revert changes to reflected components.
-///
-/// # Type Parameters
-///
-/// * `T`: The type of the component that was changed. Must implement `Component`, `Reflect`, and `FromReflect` traits.
-///
-/// # Fields
-///
-/// * `old_value`: The previous value of the component before the change.
-/// * `new_value`: The new value of the component after the change.
-/// * `entity`: The ID of the entity whose component was changed.
-///
-/// # Usage
+/// The reflectable state of an entity and of its descendants, captured before despawning them so
+/// that undoing the despawn can respawn them as they were.
 ///
-/// This struct is primarily used internally by the undo system to track and revert changes to reflected components.
-/// It's particularly useful for components that haven't clone and partial equal triats.
-/// Note: This struct is part of the internal undo system and is typically not used directly in user code.
-pub struct ReflectedComponentChange<T: Component + Reflect + FromReflect> {
-    /// The old value of the component
-    old_value: T,
-    /// The new value of the component
-    new_value: T,
-    /// The ID of the entity whose component was changed
-    entity: Entity,
+/// Only the components registered with [`ReflectComponent`] are captured. [`Children`] is left out,
+/// as it is rebuilt from the [`ChildOf`] components of the respawned descendants.
+pub struct EntitySnapshot {
+    /// The ID of the entity when it was captured.
+    pub entity: Entity,
+    /// The reflectable components of the entity, by type.
+    components: Vec<(TypeId, Box<dyn PartialReflect>)>,
+    /// The snapshots of the children of the entity, in order.
+    children: Vec<EntitySnapshot>,
 }
 
-impl<T: Component + Reflect + FromReflect> EditorChange for ReflectedComponentChange<T> {
-    fn revert(
-        &self,
-        world: &mut World,
-        entity_remap: &HashMap<Entity, Entity>,
-    ) -> Result<ChangeResult, String> {
-        let e = get_entity_with_remap(self.entity, entity_remap);
+impl EntitySnapshot {
+    /// Capture the state of an entity and of its descendants, `None` if the entity doesn't exist.
+    pub fn capture(world: &World, entity: Entity) -> Option<Self> {
+        let type_registry = world.resource::<AppTypeRegistry>().read();
+        Self::capture_with_registry(world, entity, &type_registry)
+    }
+
+    fn capture_with_registry(
+        world: &World,
+        entity: Entity,
+        type_registry: &TypeRegistry,
+    ) -> Option<Self> {
+        let entity_ref = world.get_entity(entity).ok()?;
+        let components = world
+            .inspect_entity(entity)
+            .ok()?
+            .filter_map(|component_info| component_info.type_id())
+            .filter(|&type_id| type_id != TypeId::of::<Children>())
+            .filter_map(|type_id| {
+                let reflect_component = type_registry.get_type_data::<ReflectComponent>(type_id)?;
+                let value = reflect_component.reflect(entity_ref)?;
+                // Keep concrete values when possible, as entities can only be mapped in those.
+                let value = value
+                    .reflect_clone()
+                    .map_or_else(|_| value.to_dynamic(), |value| value.into_partial_reflect());
+                Some((type_id, value))
+            })
+            .collect();
+        let children = entity_ref
+            .get::<Children>()
+            .map(|children| {
+                children
+                    .iter()
+                    .filter_map(|child| Self::capture_with_registry(world, child, type_registry))
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Some(Self {
+            entity,
+            components,
+            children,
+        })
+    }
 
-        world
-            .entity_mut(e)
-            .insert(<T as FromReflect>::from_reflect(&self.old_value).unwrap())
-            .insert(OneFrameUndoIgnore::default());
-        world.write_event(UndoRedoApplied::<T> {
-            entity: e,
-            _phantom: std::marker::PhantomData,
-        });
+    /// An estimate of the memory used by the snapshot, see [`EditorChange::memory_size`].
+    fn memory_size(&self) -> usize {
+        size_of::<Self>()
+            + self
+                .components
+                .iter()
+                .map(|(_, value)| {
+                    size_of::<(TypeId, Box<dyn PartialReflect>)>()
+                        + reflect_memory_size(value.as_ref())
+                })
+                .sum::<usize>()
+            + self
+                .children
+                .iter()
+                .map(EntitySnapshot::memory_size)
+                .sum::<usize>()
+    }
+
+    /// The IDs of the captured entities, when they were captured.
+    fn entities(&self, entities: &mut Vec<Entity>) {
+        entities.push(self.entity);
+        for child in &self.children {
+            child.entities(entities);
+        }
+    }
+
+    /// Spawn an empty entity for the entity and each of its descendants, returning the
+    /// (`old_entity`, `new_entity`) pairs.
+    fn spawn_empty(&self, world: &mut World, spawned: &mut Vec<(Entity, Entity)>) {
+        let id = world.spawn(OneFrameUndoIgnore::default()).id();
+        spawned.push((self.entity, id));
+        for child in &self.children {
+            child.spawn_empty(world, spawned);
+        }
+    }
+
+    /// Check that the captured components are still registered as reflected components, so that
+    /// they can be restored.
+    fn check_registered(&self, type_registry: &TypeRegistry) -> Result<(), String> {
+        for (type_id, value) in &self.components {
+            if type_registry
+                .get_type_data::<ReflectComponent>(*type_id)
+                .is_none()
+            {
+                return Err(format!(
+                    "{} is not registered as a reflected component",
+                    value.reflect_type_path()
+                ));
+            }
+        }
+        self.children
+            .iter()
+            .try_for_each(|child| child.check_registered(type_registry))
+    }
+
+    /// Insert the captured components into the respawned entities, mapping the entities they
+    /// reference with `entity_mapper`. The components must have passed
+    /// [`check_registered`](Self::check_registered).
+    fn insert_components(
+        &self,
+        world: &mut World,
+        type_registry: &TypeRegistry,
+        entity_mapper: &mut EntityHashMap<Entity>,
+    ) {
+        let entity = entity_mapper.get_mapped(self.entity);
+        for (type_id, value) in &self.components {
+            let reflect_component = type_registry
+                .get_type_data::<ReflectComponent>(*type_id)
+                .expect("components are checked to be registered before being inserted");
+
+            let mut entity_mut = world.entity_mut(entity);
+            match value.reflect_clone() {
+                Ok(mut component) => {
+                    reflect_component.map_entities(component.as_mut(), entity_mapper);
+                    reflect_component.insert(
+                        &mut entity_mut,
+                        component.as_partial_reflect(),
+                        type_registry,
+                    );
+                }
+                Err(_) => reflect_component.insert(&mut entity_mut, value.as_ref(), type_registry),
+            }
+        }
+
+        for child in &self.children {
+            child.insert_components(world, type_registry, entity_mapper);
+        }
+    }
+}
+
+/// Represents the removal of an entity from the world along with its descendants.
+///
+/// Unlike [`RemovedEntity`], which respawns an empty entity, reverting this change respawns the
+/// entity and its descendants with the components captured in its [`EntitySnapshot`].
+/// The entities referenced by the restored components, such as the [`ChildOf`] parents, are
+/// remapped to the respawned entities, or to the entities respawned by earlier undos.
+pub struct RemovedEntityHierarchy {
+    /// The state of the entity and its descendants before they were removed.
+    pub snapshot: Arc<EntitySnapshot>,
+    /// The index of the entity among the children of its parent before it was removed, if it had
+    /// one.
+    pub index: Option<usize>,
+}
+
+impl RemovedEntityHierarchy {
+    /// Capture the state of an entity and its descendants, to be recorded before despawning them.
+    /// Returns `None` if the entity doesn't exist.
+    pub fn capture(world: &World, entity: Entity) -> Option<Self> {
+        let snapshot = EntitySnapshot::capture(world, entity)?;
+        let index = world
+            .get::<ChildOf>(entity)
+            .and_then(|child_of| world.get::<Children>(child_of.parent()))
+            .and_then(|children| children.iter().position(|child| child == entity));
+
+        Some(Self {
+            snapshot: Arc::new(snapshot),
+            index,
+        })
+    }
+}
+
+impl EditorChange for RemovedEntityHierarchy {
+    fn revert(
+        &self,
+        world: &mut World,
+        entity_remap: &HashMap<Entity, Entity>,
+    ) -> Result<ChangeResult, String> {
+        let type_registry = world.resource::<AppTypeRegistry>().clone();
+        let type_registry = type_registry.read();
+        // Fail before spawning anything, so that no empty entities are left behind.
+        self.snapshot.check_registered(&type_registry)?;
+
+        let mut spawned = vec![];
+        self.snapshot.spawn_empty(world, &mut spawned);
+
+        // Entities outside of the hierarchy may have been respawned by earlier undos.
+        let mut entity_mapper = EntityHashMap::default();
+        entity_mapper.extend(entity_remap.iter().map(|(&old, &new)| (old, new)));
+        entity_mapper.extend(spawned.iter().copied());
+
+        self.snapshot
+            .insert_components(world, &type_registry, &mut entity_mapper);
+
+        let e = entity_mapper.get_mapped(self.snapshot.entity);
+        if let (Some(index), Some(parent)) = (
+            self.index,
+            world.get::<ChildOf>(e).map(|child_of| child_of.parent()),
+        ) {
+            let mut parent_mut = world.entity_mut(parent);
+            let len = parent_mut
+                .get::<Children>()
+                .map(|children| children.iter().filter(|child| *child != e).count())
+                .unwrap_or(0);
+            parent_mut.insert_children(index.min(len), &[e]);
+        }
 
         info!(
-            "Reverted ReflectedComponentChange for entity: {}",
-            e.index()
+            "Reverted RemovedEntityHierarchy: {} entities respawned",
+            spawned.len()
         );
-        Ok(ChangeResult::Success)
+        Ok(ChangeResult::SuccessWithRemap(spawned))
     }
 
     fn debug_text(&self) -> String {
-        format!(
-            "{:?} changed for entity {:?}",
-            pretty_type_name::pretty_type_name::<T>(),
-            self.entity
-        )
+        format!("Removed Entity hierarchy: {}", self.snapshot.entity.index())
     }
 
     fn get_inverse(&self) -> Arc<dyn EditorChange + Send + Sync> {
-        Arc::new(ReflectedComponentChange {
-            old_value: <T as FromReflect>::from_reflect(&self.new_value).unwrap(),
-            new_value: <T as FromReflect>::from_reflect(&self.old_value).unwrap(),
-            entity: self.entity,
+        Arc::new(AddedEntityHierarchy {
+            snapshot: self.snapshot.clone(),
+            index: self.index,
         })
     }
+
+    fn persist(&self, context: &PersistContext) -> Option<PersistedChange> {
+        Some(PersistedChange::EntityHierarchyRemoved(
+            context.persist_hierarchy(&self.snapshot, self.index)?,
+        ))
+    }
+
+    fn memory_size(&self) -> usize {
+        size_of::<Self>() + self.snapshot.memory_size()
+    }
 }
 
-/// Represents a change for adding a component to an entity.
-///
-/// This struct is used to track the addition of a component to an entity,
-/// allowing the undo system to remove it when necessary.
-///
-/// # Type Parameters
+/// Represents adding an entity to the world along with its descendants.
 ///
-/// * `T`: The type of the component that was added. Must implement the `Component` trait.
-pub struct AddedComponent<T: Component> {
-    /// The value of the component that was added.
-    new_value: T,
-    /// The ID of the entity to which the component was added.
-    entity: Entity,
+/// This is the inverse of [`RemovedEntityHierarchy`]: reverting it despawns the entity and its
+/// descendants, keeping their snapshot to respawn them when the change is reverted again.
+pub struct AddedEntityHierarchy {
+    /// The state of the entity and its descendants.
+    pub snapshot: Arc<EntitySnapshot>,
+    /// The index of the entity among the children of its parent, if it has one.
+    pub index: Option<usize>,
 }
 
-impl<T: Component + Clone> EditorChange for AddedComponent<T> {
+impl EditorChange for AddedEntityHierarchy {
     fn revert(
         &self,
         world: &mut World,
         entity_remap: &HashMap<Entity, Entity>,
     ) -> Result<ChangeResult, String> {
-        let e = get_entity_with_remap(self.entity, entity_remap);
-        let mut add_to_ignore = false;
-        if let Ok(mut e) = world.get_entity_mut(e) {
-            e.remove::<T>().insert(OneFrameUndoIgnore::default());
-            add_to_ignore = true;
-        }
-        if add_to_ignore {
-            world
-                .resource_mut::<UndoIgnoreStorage>()
-                .storage
-                .insert(e, OneFrameUndoIgnore::default());
+        let e = get_entity_with_remap(self.snapshot.entity, entity_remap);
+        let Ok(entity_mut) = world.get_entity_mut(e) else {
+            return Err(format!("Entity {e} of AddedEntityHierarchy does not exist"));
+        };
+        entity_mut.despawn();
+
+        let mut entities = vec![];
+        self.snapshot.entities(&mut entities);
+        let mut ignore_storage = world.resource_mut::<UndoIgnoreStorage>();
+        for entity in entities {
+            ignore_storage.storage.insert(
+                get_entity_with_remap(entity, entity_remap),
+                OneFrameUndoIgnore::default(),
+            );
         }
 
-        info!("Reverted AddedComponent for entity: {}", e.index());
-
+        info!("Removed Entity hierarchy: {}", e.index());
         Ok(ChangeResult::Success)
     }
 
     fn debug_text(&self) -> String {
-        format!("AddedComponent for entity {:?}", self.entity)
+        format!("Added Entity hierarchy: {}", self.snapshot.entity.index())
     }
 
     fn get_inverse(&self) -> Arc<dyn EditorChange + Send + Sync> {
-        Arc::new(RemovedComponent {
-            entity: self.entity,
-            old_value: self.new_value.clone(),
+        Arc::new(RemovedEntityHierarchy {
+            snapshot: self.snapshot.clone(),
+            index: self.index,
         })
     }
+
+    fn persist(&self, context: &PersistContext) -> Option<PersistedChange> {
+        Some(PersistedChange::EntityHierarchyAdded(
+            context.persist_hierarchy(&self.snapshot, self.index)?,
+        ))
+    }
+
+    fn memory_size(&self) -> usize {
+        size_of::<Self>() + self.snapshot.memory_size()
+    }
 }
 
-/// Represents a change for adding a reflected component to an entity.
-///
-/// This struct is used to track the addition of a component that supports reflection
-/// to an entity, allowing the undo system to remove it when necessary.
-///
-/// # Type Parameters
+/// Represents moving an entity to another place in the hierarchy.
 ///
-/// * `T`: The type of the component that was added. Must implement `Component`, `Reflect`,
-///   and `FromReflect` traits.
-pub struct ReflectedAddedComponent<T: Component + Reflect + FromReflect> {
-    /// The value of the component that was added.
-    new_value: T,
-    /// The ID of the entity to which the component was added.
-    entity: Entity,
+/// This covers both reparenting an entity and reordering it among its siblings.
+/// Reverting the change puts the entity back under its old parent at its old index,
+/// and restores its old [`Transform`] if one was stored.
+pub struct HierarchyChange {
+    /// The ID of the entity that was moved.
+    pub entity: Entity,
+    /// The parent of the entity before the move, `None` if it was a root entity.
+    pub old_parent: Option<Entity>,
+    /// The index of the entity among the children of `old_parent` before the move.
+    pub old_index: usize,
+    /// The parent of the entity after the move, `None` if it became a root entity.
+    pub new_parent: Option<Entity>,
+    /// The index of the entity among the children of `new_parent` after the move.
+    pub new_index: usize,
+    /// The local transform of the entity before the move, if it was adjusted by the move.
+    pub old_transform: Option<Transform>,
+    /// The local transform of the entity after the move, if it was adjusted by the move.
+    pub new_transform: Option<Transform>,
 }
 
-impl<T: Component + Reflect + FromReflect> EditorChange for ReflectedAddedComponent<T> {
+impl EditorChange for HierarchyChange {
     fn revert(
         &self,
         world: &mut World,
         entity_remap: &HashMap<Entity, Entity>,
     ) -> Result<ChangeResult, String> {
-        let dst = entity_remap
-            .get(&self.entity)
-            .map_or(self.entity, |remapped| *remapped);
-        if let Ok(mut e) = world.get_entity_mut(dst) {
-            e.remove::<T>().insert(OneFrameUndoIgnore::default());
+        let e = get_entity_with_remap(self.entity, entity_remap);
+        if world.get_entity(e).is_err() {
+            return Err(format!("Entity {e} of HierarchyChange does not exist"));
         }
-        world
-            .resource_mut::<UndoIgnoreStorage>()
-            .storage
-            .insert(dst, OneFrameUndoIgnore::default());
-        world.write_event(UndoRedoApplied::<T> {
-            entity: dst,
-            _phantom: std::marker::PhantomData,
-        });
 
-        info!(
-            "Reverted ReflectedAddedComponent for entity: {}",
-            dst.index()
-        );
+        match self.old_parent {
+            Some(parent) => {
+                let parent = get_entity_with_remap(parent, entity_remap);
+                let Ok(mut parent_mut) = world.get_entity_mut(parent) else {
+                    return Err(format!("Parent {parent} of HierarchyChange does not exist"));
+                };
+                let len = parent_mut
+                    .get::<Children>()
+                    .map(|children| children.iter().filter(|child| *child != e).count())
+                    .unwrap_or(0);
+                parent_mut.insert_children(self.old_index.min(len), &[e]);
+            }
+            None => {
+                world.entity_mut(e).remove::<ChildOf>();
+            }
+        }
+
+        let mut entity_mut = world.entity_mut(e);
+        if let Some(transform) = self.old_transform {
+            entity_mut.insert(transform);
+        }
+        entity_mut.insert(OneFrameUndoIgnore::default());
 
+        info!("Reverted HierarchyChange for entity: {}", e.index());
         Ok(ChangeResult::Success)
     }
 
     fn debug_text(&self) -> String {
-        format!("ReflectedAddedComponent for entity {:?}", self.entity)
+        match self.new_parent {
+            Some(parent) => format!("Moved entity {:?} under {:?}", self.entity, parent),
+            None => format!("Moved entity {:?} to the root", self.entity),
+        }
     }
 
     fn get_inverse(&self) -> Arc<dyn EditorChange + Send + Sync> {
-        Arc::new(ReflectedRemovedComponent {
-            old_value: <T as FromReflect>::from_reflect(&self.new_value).unwrap(),
+        Arc::new(HierarchyChange {
             entity: self.entity,
+            old_parent: self.new_parent,
+            old_index: self.new_index,
+            new_parent: self.old_parent,
+            new_index: self.old_index,
+            old_transform: self.new_transform,
+            new_transform: self.old_transform,
         })
     }
+
+    fn persist(&self, context: &PersistContext) -> Option<PersistedChange> {
+        context.persist_hierarchy_change(self)
+    }
 }
 
-/// Represents a change for removing a component from an entity.
+/// Represents an changing a component in an entity.
 ///
-/// This struct is used to track the removal of a component from an entity,
-/// allowing the undo system to re-add it when necessary.
+/// This struct stores both the old and new values of a component, as well as
+/// the entity ID, allowing the undo system to revert changes to components.
 ///
 /// # Type Parameters
 ///
-/// * `T`: The type of the component that was removed. Must implement the `Component` and `Clone` traits.
-pub struct RemovedComponent<T: Component + Clone> {
-    /// The value of the component that was removed.
+/// * `T`: The type of the component that was changed. Must implement the `Component` trait.
+pub struct ComponentChange<T: Component> {
+    /// The previous value of the component before the change.
     old_value: T,
-    /// The ID of the entity from which the component was removed.
+    /// The new value of the component after the change.
+    new_value: T,
+    /// The ID of the entity whose component was changed.
     entity: Entity,
 }
 
-impl<T: Component + Clone> EditorChange for RemovedComponent<T> {
+impl<T: Component + Clone> EditorChange for ComponentChange<T> {
     fn revert(
         &self,
         world: &mut World,
         entity_remap: &HashMap<Entity, Entity>,
     ) -> Result<ChangeResult, String> {
-        let mut remap = vec![];
-        let dst = entity_remap.get(&self.entity).map_or_else(
-            || {
-                if world.get_entity(self.entity).is_ok() {
-                    self.entity
-                } else {
-                    let id = world.spawn_empty().id();
-                    remap.push((self.entity, id));
-                    id
-                }
-            },
-            |remapped| *remapped,
-        );
+        let e = get_entity_with_remap(self.entity, entity_remap);
+        let Ok(mut entity_mut) = world.get_entity_mut(e) else {
+            return Err(format!("Entity {e} of ComponentChange does not exist"));
+        };
 
-        world
-            .entity_mut(dst)
+        entity_mut
             .insert(self.old_value.clone())
             .insert(OneFrameUndoIgnore::default());
-
-        info!("Reverted RemovedComponent for entity: {}", dst.index());
-
-        Ok(ChangeResult::SuccessWithRemap(remap))
+        info!("Reverted ComponentChange for entity: {}", e.index());
+        Ok(ChangeResult::Success)
     }
 
     fn debug_text(&self) -> String {
-        format!("RemovedComponent for entity {:?}", self.entity)
+        format!("ComponentChange for entity {:?}", self.entity)
     }
 
     fn get_inverse(&self) -> Arc<dyn EditorChange + Send + Sync> {
-        Arc::new(AddedComponent {
+        Arc::new(ComponentChange {
+            old_value: self.new_value.clone(),
             new_value: self.old_value.clone(),
             entity: self.entity,
         })
     }
+
+    fn merge(
+        &self,
+        next: &(dyn EditorChange + Send + Sync),
+    ) -> Option<Arc<dyn EditorChange + Send + Sync>> {
+        let next = (next as &dyn Any).downcast_ref::<Self>()?;
+        (next.entity == self.entity).then(|| {
+            Arc::new(ComponentChange {
+                old_value: self.old_value.clone(),
+                new_value: next.new_value.clone(),
+                entity: self.entity,
+            }) as _
+        })
+    }
 }
 
-/// Represents a change for removing a reflected component from an entity.
+/// Represents a change in a component that supports reflection.
 ///
-/// This struct is used to track the removal of a component that supports reflection
-/// from an entity, allowing the undo system to re-add it when necessary.
+/// This struct is used to track changes to components that implement `Component`, `Reflect`, and `FromReflect` traits.
+/// It stores both the old and new values of the component, as well as the entity ID, allowing the undo system
+/// to revert changes to reflected components.
 ///
 /// # Type Parameters
 ///
-/// * `T`: The type of the component that was removed. Must implement `Component` and `Reflect` traits.
-pub struct ReflectedRemovedComponent<T: Component + Reflect> {
-    /// The value of the component that was removed.
+/// * `T`: The type of the component that was changed. Must implement `Component`, `Reflect`, and `FromReflect` traits.
+///
+/// # Fields
+///
+/// * `old_value`: The previous value of the component before the change.
+/// * `new_value`: The new value of the component after the change.
+/// * `entity`: The ID of the entity whose component was changed.
+///
+/// # Usage
+///
+/// This struct is primarily used internally by the undo system to track and revert changes to reflected components.
+/// It's particularly useful for components that haven't clone and partial equal triats.
+/// Note: This struct is part of the internal undo system and is typically not used directly in user code.
+pub struct ReflectedComponentChange<T: Component + Reflect + FromReflect> {
+    /// The old value of the component
     old_value: T,
-    /// The ID of the entity from which the component was removed.
+    /// The new value of the component
+    new_value: T,
+    /// The ID of the entity whose component was changed
     entity: Entity,
 }
 
-impl<T: Component + Reflect + FromReflect> EditorChange for ReflectedRemovedComponent<T> {
+impl<T: Component + Reflect + FromReflect> EditorChange for ReflectedComponentChange<T> {
     fn revert(
         &self,
         world: &mut World,
         entity_remap: &HashMap<Entity, Entity>,
     ) -> Result<ChangeResult, String> {
-        let mut remap = vec![];
-        let dst = entity_remap.get(&self.entity).map_or_else(
-            || {
-                if world.get_entity(self.entity).is_ok() {
-                    self.entity
-                } else {
-                    let id = world.spawn_empty().id();
-                    remap.push((self.entity, id));
-                    id
-                }
-            },
-            |remapped| *remapped,
-        );
+        let e = get_entity_with_remap(self.entity, entity_remap);
+        let Ok(mut entity_mut) = world.get_entity_mut(e) else {
+            return Err(format!(
+                "Entity {e} of ReflectedComponentChange does not exist"
+            ));
+        };
 
-        world
-            .entity_mut(dst)
+        entity_mut
             .insert(<T as FromReflect>::from_reflect(&self.old_value).unwrap())
             .insert(OneFrameUndoIgnore::default());
         world.write_event(UndoRedoApplied::<T> {
-            entity: dst,
+            entity: e,
             _phantom: std::marker::PhantomData,
         });
 
         info!(
-            "Reverted ReflectedRemovedComponent for entity: {}",
-            dst.index()
+            "Reverted ReflectedComponentChange for entity: {}",
+            e.index()
         );
-
-        Ok(ChangeResult::SuccessWithRemap(remap))
+        Ok(ChangeResult::Success)
     }
 
     fn debug_text(&self) -> String {
-        format!("ReflectedRemovedComponent for entity {:?}", self.entity)
+        format!(
+            "{:?} changed for entity {:?}",
+            pretty_type_name::pretty_type_name::<T>(),
+            self.entity
+        )
     }
 
     fn get_inverse(&self) -> Arc<dyn EditorChange + Send + Sync> {
-        Arc::new(ReflectedAddedComponent {
+        Arc::new(ReflectedComponentChange {
+            old_value: <T as FromReflect>::from_reflect(&self.new_value).unwrap(),
             new_value: <T as FromReflect>::from_reflect(&self.old_value).unwrap(),
             entity: self.entity,
         })
     }
-}
 
-/// Represents a collection of multiple changes that occurred simultaneously and should be applied or reverted together.
-///
-/// `ManyChanges` is automatically generated by the undo system to group multiple `EditorChange`
-/// instances that occur within the same frame or update cycle. This allows complex or multi-part
-/// operations to be treated as a single, atomic change for undo/redo purposes.
-///
-/// # Implementation
+    fn merge(
+        &self,
+        next: &(dyn EditorChange + Send + Sync),
+    ) -> Option<Arc<dyn EditorChange + Send + Sync>> {
+        let next = (next as &dyn Any).downcast_ref::<Self>()?;
+        if next.entity != self.entity {
+            return None;
+        }
+        Some(Arc::new(ReflectedComponentChange {
+            old_value: <T as FromReflect>::from_reflect(&self.old_value)?,
+            new_value: <T as FromReflect>::from_reflect(&next.new_value)?,
+            entity: self.entity,
+        }))
+    }
+
+    fn persist(&self, context: &PersistContext) -> Option<PersistedChange> {
+        Some(PersistedChange::ComponentChanged {
+            entity: context.stable_id(self.entity)?,
+            old_value: context.serialize(self.old_value.as_partial_reflect())?,
+            new_value: context.serialize(self.new_value.as_partial_reflect())?,
+        })
+    }
+
+    fn memory_size(&self) -> usize {
+        size_of::<Self>()
+            + reflect_heap_size(self.old_value.as_partial_reflect())
+            + reflect_heap_size(self.new_value.as_partial_reflect())
+    }
+}
+
+/// Represents a change in a reflected component whose type is only known at runtime.
 ///
-/// `ManyChanges` implements the `EditorChange` trait, allowing it to be treated as a single change
-/// in the undo/redo system. When reverted, it applies all contained changes in reverse order
-/// to ensure proper undo behavior.
-pub struct ManyChanges {
-    changes: Vec<Arc<dyn EditorChange + Send + Sync>>,
+/// Unlike [`ReflectedComponentChange`], this does not need the component type at compile time,
+/// which makes it suitable for generic editing tools such as inspectors. The component is written
+/// back through its [`ReflectComponent`] type data, so its type has to be registered in the
+/// [`AppTypeRegistry`].
+pub struct DynamicComponentChange {
+    /// The ID of the entity whose component was changed.
+    pub entity: Entity,
+    /// The type of the component that was changed.
+    pub component_type: TypeId,
+    /// The previous value of the component before the change.
+    pub old_value: Box<dyn PartialReflect>,
+    /// The new value of the component after the change.
+    pub new_value: Box<dyn PartialReflect>,
 }
 
-impl EditorChange for ManyChanges {
+impl EditorChange for DynamicComponentChange {
     fn revert(
         &self,
         world: &mut World,
         entity_remap: &HashMap<Entity, Entity>,
     ) -> Result<ChangeResult, String> {
-        let mut remap = entity_remap.clone();
-        for change in self.changes.iter() {
-            let res = change.revert(world, &remap)?;
-            match res {
-                ChangeResult::Success => {}
-                ChangeResult::SuccessWithRemap(new_remap) => {
-                    remap.extend(new_remap);
-                }
-            }
-        }
+        let e = get_entity_with_remap(self.entity, entity_remap);
 
-        info!("Reverted ManyChanges");
+        let type_registry = world.resource::<AppTypeRegistry>().clone();
+        let type_registry = type_registry.read();
+        let Some(reflect_component) =
+            type_registry.get_type_data::<ReflectComponent>(self.component_type)
+        else {
+            return Err(format!(
+                "{} is not registered as a reflected component",
+                self.old_value.reflect_type_path()
+            ));
+        };
+        let Ok(mut entity_mut) = world.get_entity_mut(e) else {
+            return Err(format!(
+                "Entity {e} of DynamicComponentChange does not exist"
+            ));
+        };
+
+        reflect_component.apply_or_insert(
+            &mut entity_mut,
+            self.old_value.as_partial_reflect(),
+            &type_registry,
+        );
+        entity_mut.insert(OneFrameUndoIgnore::default());
 
-        Ok(ChangeResult::SuccessWithRemap(
-            remap.iter().map(|(key, value)| (*key, *value)).collect(),
-        ))
+        info!("Reverted DynamicComponentChange for entity: {}", e.index());
+        Ok(ChangeResult::Success)
     }
 
     fn debug_text(&self) -> String {
-        "ManyChanges".to_string()
+        format!(
+            "{:?} changed for entity {:?}",
+            self.new_value.reflect_short_type_path(),
+            self.entity
+        )
     }
 
     fn get_inverse(&self) -> Arc<dyn EditorChange + Send + Sync> {
-        let mut old_changes = self.changes.clone();
-        old_changes.reverse();
-        let new_changes = old_changes
-            .iter()
-            .map(|change| change.get_inverse())
-            .collect::<Vec<_>>();
+        Arc::new(DynamicComponentChange {
+            entity: self.entity,
+            component_type: self.component_type,
+            old_value: self.new_value.to_dynamic(),
+            new_value: self.old_value.to_dynamic(),
+        })
+    }
 
-        Arc::new(ManyChanges {
-            changes: new_changes,
+    fn merge(
+        &self,
+        next: &(dyn EditorChange + Send + Sync),
+    ) -> Option<Arc<dyn EditorChange + Send + Sync>> {
+        let next = (next as &dyn Any).downcast_ref::<Self>()?;
+        (next.entity == self.entity && next.component_type == self.component_type).then(|| {
+            Arc::new(DynamicComponentChange {
+                entity: self.entity,
+                component_type: self.component_type,
+                old_value: self.old_value.to_dynamic(),
+                new_value: next.new_value.to_dynamic(),
+            }) as _
         })
     }
-}
 
-/// A component that marks an entity as having a changed component of type `T`.
-///
-/// This marker is used internally by the undo system to track changes to components
-/// and manage the timing of when these changes should be recorded for undo operations.
-///
-/// # Type Parameters
-///
-/// * `T`: The type of the component that has changed.
-///
-/// # Fields
-///
-/// * `latency`: An integer representing the number of frames to wait before recording the change.
-///   This helps to batch rapid changes and avoid creating unnecessary undo entries.
-///
-/// # Usage
-///
-/// This component is automatically added and removed by the undo system and should not
-/// be manipulated directly by users.
-#[derive(Component)]
-pub struct ChangedMarker<T> {
-    latency: i32,
-    _phantom: std::marker::PhantomData<T>,
-}
+    fn persist(&self, context: &PersistContext) -> Option<PersistedChange> {
+        Some(PersistedChange::ComponentChanged {
+            entity: context.stable_id(self.entity)?,
+            old_value: context.serialize(self.old_value.as_ref())?,
+            new_value: context.serialize(self.new_value.as_ref())?,
+        })
+    }
 
-impl<T> Default for ChangedMarker<T> {
-    fn default() -> Self {
-        Self {
-            latency: AUTO_UNDO_LATENCY, //2 frame latency
-            _phantom: std::marker::PhantomData,
-        }
+    fn memory_size(&self) -> usize {
+        size_of::<Self>()
+            + reflect_memory_size(self.old_value.as_ref())
+            + reflect_memory_size(self.new_value.as_ref())
     }
 }
 
-/// A resource that stores entities that should be ignored by the undo system for a short period.
-///
-/// This storage is used to prevent newly undone or redone changes from immediately
-/// triggering new undo entries, which could lead to infinite loops or unexpected behavior.
-///
-/// # Fields
-///
-/// * `storage`: A `HashMap` that associates entities with `OneFrameUndoIgnore` components.
-///
-/// # Usage
+/// Represents the addition of a reflected component whose type is only known at runtime.
 ///
-/// This resource is managed internally by the undo system. It is updated automatically
-/// when undo or redo operations are performed, and its contents are used to filter
-/// which entity changes should be recorded for future undo operations.
-#[derive(Resource, Default)]
-pub struct UndoIgnoreStorage {
-    /// A `HashMap` that associates entities which should be ignored by the undo system for a short period.
-    pub storage: HashMap<Entity, OneFrameUndoIgnore>,
+/// This is the counterpart of [`AddedComponent`] for generic editing tools, see
+/// [`DynamicComponentChange`].
+pub struct DynamicAddedComponent {
+    /// The ID of the entity to which the component was added.
+    pub entity: Entity,
+    /// The type of the component that was added.
+    pub component_type: TypeId,
+    /// The value of the component that was added.
+    pub new_value: Box<dyn PartialReflect>,
 }
 
-/// A resource that stores the previous state of components for automatic undo functionality.
-///
-/// `AutoUndoStorage<T>` is used internally by the undo system to keep track of component values
-/// before they are changed. This allows the system to revert components to their previous state
-/// when an undo operation is performed.
-///
-/// # Type Parameters
-///
-/// * `T`: The type of component being stored. Must implement the `Component` trait.
-///
-/// # Fields
-///
-/// * `storage`: A `HashMap` that associates entity IDs with their corresponding component values.
-///
-/// # Usage
-///
-/// This resource is automatically created and managed by the undo system when you use the
-/// `auto_undo` or `auto_reflected_undo` methods. You typically don't need to interact with
-/// this resource directly in your application code.
-///
-/// # Example
-///
-/// ```rust
-/// use bevy::prelude::*;
-/// use bevy_undo::*;
-///
-///
-/// App::new()
-///     .add_plugins(UndoPlugin)
-///     .auto_undo::<Transform>();
-///
-/// ```
-///
-/// In this example, `AutoUndoStorage<Transform>` will be automatically created and managed
-/// by the undo system.
-#[derive(Resource)]
-pub struct AutoUndoStorage<T: Component> {
-    /// The storage of "old" components values to allow store "old" component value in `ComponentChange<T>` change
-    pub storage: HashMap<Entity, T>,
-}
+impl EditorChange for DynamicAddedComponent {
+    fn revert(
+        &self,
+        world: &mut World,
+        entity_remap: &HashMap<Entity, Entity>,
+    ) -> Result<ChangeResult, String> {
+        let e = get_entity_with_remap(self.entity, entity_remap);
 
-impl<T: Component> Default for AutoUndoStorage<T> {
-    fn default() -> Self {
-        Self {
-            storage: HashMap::default(),
-        }
+        let reflect_component =
+            dynamic_reflect_component(world, self.component_type, self.new_value.as_ref())?;
+        let Ok(mut entity_mut) = world.get_entity_mut(e) else {
+            return Err(format!(
+                "Entity {e} of DynamicAddedComponent does not exist"
+            ));
+        };
+
+        reflect_component.remove(&mut entity_mut);
+        entity_mut.insert(OneFrameUndoIgnore::default());
+
+        info!("Reverted DynamicAddedComponent for entity: {}", e.index());
+        Ok(ChangeResult::Success)
+    }
+
+    fn debug_text(&self) -> String {
+        format!(
+            "{:?} added to entity {:?}",
+            self.new_value.reflect_short_type_path(),
+            self.entity
+        )
+    }
+
+    fn get_inverse(&self) -> Arc<dyn EditorChange + Send + Sync> {
+        Arc::new(DynamicRemovedComponent {
+            entity: self.entity,
+            component_type: self.component_type,
+            old_value: self.new_value.to_dynamic(),
+        })
+    }
+
+    fn persist(&self, context: &PersistContext) -> Option<PersistedChange> {
+        Some(PersistedChange::ComponentAdded {
+            entity: context.stable_id(self.entity)?,
+            value: context.serialize(self.new_value.as_ref())?,
+        })
+    }
+
+    fn memory_size(&self) -> usize {
+        size_of::<Self>() + reflect_memory_size(self.new_value.as_ref())
     }
 }
 
-/// A trait that extends `App` with methods for setting up automatic undo functionality.
-///
-/// `AppAutoUndo` provides methods to easily configure automatic undo/redo support for
-/// specific component types in your Bevy application.
-///
-/// # Methods
-///
-/// * `auto_undo<T: Component + Clone>`: Sets up automatic undo for components that implement
-///   `Clone`.
-/// * `auto_reflected_undo<T: Component + Reflect + FromReflect>`: Sets up automatic undo for
-///   components that support reflection.
-///
-/// # Usage
-///
-/// Import this trait and use its methods in your `App` setup to enable automatic undo
-/// functionality for specific components.
-///
-/// # Example
-///
-/// ```rust
-/// use bevy::prelude::*;
-/// use bevy_undo::*;
-///
-///
-///  App::new()
-///       .add_plugins(UndoPlugin)
-///       .auto_undo::<Transform>()
-///       .auto_reflected_undo::<MyReflectedComponent>();
-///
-///
-/// #[derive(Component, Reflect)]
-/// struct MyReflectedComponent {
-///     // fields...
-/// }
-/// ```
+/// Represents the removal of a reflected component whose type is only known at runtime.
 ///
-/// This example sets up automatic undo for the `Transform` component and a custom
-/// `MyReflectedComponent` that supports reflection.
-pub trait AppAutoUndo {
-    /// Sets up automatic undo logic for components that implement `Clone`.
-    fn auto_undo<T: Component + Clone>(&mut self) -> &mut Self;
-    /// Sets up automatic undo logic for components that implement `Reflect` and `FromReflect`.
-    fn auto_reflected_undo<T: Component + Reflect + FromReflect>(&mut self) -> &mut Self;
+/// This is the counterpart of [`RemovedComponent`] for generic editing tools, see
+/// [`DynamicComponentChange`].
+pub struct DynamicRemovedComponent {
+    /// The ID of the entity from which the component was removed.
+    pub entity: Entity,
+    /// The type of the component that was removed.
+    pub component_type: TypeId,
+    /// The value of the component that was removed.
+    pub old_value: Box<dyn PartialReflect>,
 }
 
-impl AppAutoUndo for App {
-    fn auto_undo<T: Component + Clone>(&mut self) -> &mut Self {
-        if !self.world().contains_resource::<ChangeChain>() {
-            return self;
-        }
+impl EditorChange for DynamicRemovedComponent {
+    fn revert(
+        &self,
+        world: &mut World,
+        entity_remap: &HashMap<Entity, Entity>,
+    ) -> Result<ChangeResult, String> {
+        let reflect_component =
+            dynamic_reflect_component(world, self.component_type, self.old_value.as_ref())?;
 
-        self.world_mut()
-            .insert_resource(AutoUndoStorage::<T>::default());
-        self.add_event::<UndoRedoApplied<T>>();
+        let mut remap = vec![];
+        let dst = entity_remap.get(&self.entity).map_or_else(
+            || {
+                if world.get_entity(self.entity).is_ok() {
+                    self.entity
+                } else {
+                    let id = world.spawn_empty().id();
+                    remap.push((self.entity, id));
+                    id
+                }
+            },
+            |remapped| *remapped,
+        );
 
-        self.add_systems(
-            PostUpdate,
-            (
-                auto_undo_update_cache::<T>,
-                auto_undo_add_init::<T>,
-                auto_undo_remove_detect::<T>,
-                ApplyDeferred,
-                auto_undo_system_changed::<T>,
-                auto_undo_system::<T>,
-            )
-                .chain()
-                .in_set(UndoSet::PerType),
+        let type_registry = world.resource::<AppTypeRegistry>().clone();
+        let mut entity_mut = world.entity_mut(dst);
+        reflect_component.insert(
+            &mut entity_mut,
+            self.old_value.as_ref(),
+            &type_registry.read(),
         );
+        entity_mut.insert(OneFrameUndoIgnore::default());
 
-        self
+        info!(
+            "Reverted DynamicRemovedComponent for entity: {}",
+            dst.index()
+        );
+        Ok(ChangeResult::SuccessWithRemap(remap))
     }
 
-    fn auto_reflected_undo<T: Component + Reflect + FromReflect>(&mut self) -> &mut Self {
-        if !self.world().contains_resource::<ChangeChain>() {
-            return self;
-        }
+    fn debug_text(&self) -> String {
+        format!(
+            "{:?} removed from entity {:?}",
+            self.old_value.reflect_short_type_path(),
+            self.entity
+        )
+    }
 
-        self.world_mut()
-            .insert_resource(AutoUndoStorage::<T>::default());
-        self.add_event::<UndoRedoApplied<T>>();
+    fn get_inverse(&self) -> Arc<dyn EditorChange + Send + Sync> {
+        Arc::new(DynamicAddedComponent {
+            entity: self.entity,
+            component_type: self.component_type,
+            new_value: self.old_value.to_dynamic(),
+        })
+    }
 
-        self.add_systems(
-            PostUpdate,
-            (
-                auto_undo_reflected_update_cache::<T>,
-                auto_undo_reflected_add_init::<T>,
-                auto_undo_reflected_remove_detect::<T>,
-                ApplyDeferred,
-                auto_undo_system_changed::<T>,
-                auto_undo_reflected_system::<T>,
+    fn persist(&self, context: &PersistContext) -> Option<PersistedChange> {
+        Some(PersistedChange::ComponentRemoved {
+            entity: context.stable_id(self.entity)?,
+            value: context.serialize(self.old_value.as_ref())?,
+        })
+    }
+
+    fn memory_size(&self) -> usize {
+        size_of::<Self>() + reflect_memory_size(self.old_value.as_ref())
+    }
+}
+
+/// Look up the [`ReflectComponent`] of a component type, for the changes of components whose type
+/// is only known at runtime.
+fn dynamic_reflect_component(
+    world: &World,
+    component_type: TypeId,
+    value: &dyn PartialReflect,
+) -> Result<ReflectComponent, String> {
+    world
+        .resource::<AppTypeRegistry>()
+        .read()
+        .get_type_data::<ReflectComponent>(component_type)
+        .cloned()
+        .ok_or_else(|| {
+            format!(
+                "{} is not registered as a reflected component",
+                value.reflect_type_path()
             )
-                .chain()
-                .in_set(UndoSet::PerType),
+        })
+}
+
+/// Represents a change in a reflected resource whose type is only known at runtime.
+///
+/// This is the resource counterpart of [`DynamicComponentChange`]. The resource is written back
+/// through its [`ReflectResource`] type data, so its type has to be registered in the
+/// [`AppTypeRegistry`].
+pub struct DynamicResourceChange {
+    /// The type of the resource that was changed.
+    pub resource_type: TypeId,
+    /// The previous value of the resource before the change.
+    pub old_value: Box<dyn PartialReflect>,
+    /// The new value of the resource after the change.
+    pub new_value: Box<dyn PartialReflect>,
+}
+
+impl EditorChange for DynamicResourceChange {
+    fn revert(
+        &self,
+        world: &mut World,
+        _entity_remap: &HashMap<Entity, Entity>,
+    ) -> Result<ChangeResult, String> {
+        let type_registry = world.resource::<AppTypeRegistry>().clone();
+        let type_registry = type_registry.read();
+        let Some(reflect_resource) =
+            type_registry.get_type_data::<ReflectResource>(self.resource_type)
+        else {
+            return Err(format!(
+                "{} is not registered as a reflected resource",
+                self.old_value.reflect_type_path()
+            ));
+        };
+
+        reflect_resource.apply_or_insert(
+            world,
+            self.old_value.as_partial_reflect(),
+            &type_registry,
         );
 
-        self.add_systems(
-            PostUpdate,
-            auto_remap_undo_redo::<T>.in_set(UndoSet::Remapping),
+        info!(
+            "Reverted DynamicResourceChange for {}",
+            self.old_value.reflect_short_type_path()
         );
+        Ok(ChangeResult::Success)
+    }
 
-        self
+    fn debug_text(&self) -> String {
+        format!("{:?} changed", self.new_value.reflect_short_type_path())
     }
-}
 
-fn apply_for_every_typed_field<D: Reflect + TypePath>(
-    value: &mut dyn PartialReflect,
-    applyer: &dyn Fn(&mut D),
-    max_recursion: i32,
-) {
-    if max_recursion < 0 {
-        return;
-    }
-
-    if let Some(v) = value.try_downcast_mut::<D>() {
-        applyer(v);
-    } else {
-        match value.reflect_mut() {
-            bevy::reflect::ReflectMut::Struct(s) => {
-                for field_idx in 0..s.field_len() {
-                    apply_for_every_typed_field(
-                        s.field_at_mut(field_idx).unwrap(),
-                        applyer,
-                        max_recursion - 1,
-                    );
-                }
-            }
-            bevy::reflect::ReflectMut::TupleStruct(s) => {
-                for field_idx in 0..s.field_len() {
-                    apply_for_every_typed_field(
-                        s.field_mut(field_idx).unwrap(),
-                        applyer,
-                        max_recursion - 1,
-                    );
-                }
-            }
-            bevy::reflect::ReflectMut::Tuple(s) => {
-                for field_idx in 0..s.field_len() {
-                    apply_for_every_typed_field(
-                        s.field_mut(field_idx).unwrap(),
-                        applyer,
-                        max_recursion - 1,
-                    );
-                }
-            }
-            bevy::reflect::ReflectMut::List(s) => {
-                for field_idx in 0..s.len() {
-                    apply_for_every_typed_field(
-                        s.get_mut(field_idx).unwrap(),
-                        applyer,
-                        max_recursion - 1,
-                    );
-                }
-            }
-            bevy::reflect::ReflectMut::Array(s) => {
-                for field_idx in 0..s.len() {
-                    apply_for_every_typed_field(
-                        s.get_mut(field_idx).unwrap(),
-                        applyer,
-                        max_recursion - 1,
-                    );
-                }
-            }
-            bevy::reflect::ReflectMut::Map(_s) => {
-                unimplemented!("See: https://github.com/bevyengine/bevy/pull/19802");
-            }
-            bevy::reflect::ReflectMut::Enum(s) => {
-                for field_idx in 0..s.field_len() {
-                    apply_for_every_typed_field(
-                        s.field_at_mut(field_idx).unwrap(),
-                        applyer,
-                        max_recursion - 1,
-                    );
-                }
-            }
-            bevy::reflect::ReflectMut::Opaque(op) => {
-                apply_for_every_typed_field(op, applyer, max_recursion - 1);
-            }
-            bevy::reflect::ReflectMut::Set(set) => {
-                let mut queue_to_replace = vec![];
-                for field in set.iter() {
-                    if field.represents::<D>() {
-                        queue_to_replace.push(field.reflect_clone().unwrap());
-                    }
-                }
-                for mut field in queue_to_replace {
-                    set.remove(field.as_ref());
-                    applyer(field.as_mut().downcast_mut().unwrap());
-                    set.insert_boxed(field);
-                }
-            }
-        }
+    fn get_inverse(&self) -> Arc<dyn EditorChange + Send + Sync> {
+        Arc::new(DynamicResourceChange {
+            resource_type: self.resource_type,
+            old_value: self.new_value.to_dynamic(),
+            new_value: self.old_value.to_dynamic(),
+        })
     }
-}
 
-fn auto_remap_undo_redo<T: Component + Reflect>(
-    mut undoredo_applied: EventReader<UndoRedoApplied<T>>,
-    mut commands: Commands,
-) {
-    for event in undoredo_applied.read() {
-        println!("remapping {:?}", event.entity);
-        let entity_id = event.entity;
-        commands.queue(move |world: &mut World| {
-            world.resource_scope(|world: &mut World, change_chain: Mut<ChangeChain>| {
-                let mut entity = world.entity_mut(entity_id);
-                if let Some(mut data) = entity.take::<T>() {
-                    let reflect = data.as_reflect_mut();
-
-                    apply_for_every_typed_field::<Entity>(
-                        reflect.as_partial_reflect_mut(),
-                        &|v| {
-                            if let Some(e) = change_chain.entity_remap.get(v) {
-                                println!("remap {v:?} to {e:?}");
-                                *v = *e;
-                            }
-                        },
-                        MAX_REFLECT_RECURSION,
-                    );
+    fn merge(
+        &self,
+        next: &(dyn EditorChange + Send + Sync),
+    ) -> Option<Arc<dyn EditorChange + Send + Sync>> {
+        let next = (next as &dyn Any).downcast_ref::<Self>()?;
+        (next.resource_type == self.resource_type).then(|| {
+            Arc::new(DynamicResourceChange {
+                resource_type: self.resource_type,
+                old_value: self.old_value.to_dynamic(),
+                new_value: next.new_value.to_dynamic(),
+            }) as _
+        })
+    }
 
-                    entity.insert(data);
-                }
-            });
-        });
+    fn memory_size(&self) -> usize {
+        size_of::<Self>()
+            + reflect_memory_size(self.old_value.as_ref())
+            + reflect_memory_size(self.new_value.as_ref())
     }
 }
 
-fn auto_undo_update_cache<T: Component + Clone>(
-    mut storage: ResMut<AutoUndoStorage<T>>,
-    ignored_query: Query<(Entity, &T), With<OneFrameUndoIgnore>>,
-) {
-    for (e, data) in ignored_query.iter() {
-        storage.storage.insert(e, data.clone());
-    }
+/// Represents a change for adding a component to an entity.
+///
+/// This struct is used to track the addition of a component to an entity,
+/// allowing the undo system to remove it when necessary.
+///
+/// # Type Parameters
+///
+/// * `T`: The type of the component that was added. Must implement the `Component` trait.
+pub struct AddedComponent<T: Component> {
+    /// The value of the component that was added.
+    new_value: T,
+    /// The ID of the entity to which the component was added.
+    entity: Entity,
 }
 
-fn auto_undo_reflected_update_cache<T: Component + Reflect + FromReflect>(
-    mut storage: ResMut<AutoUndoStorage<T>>,
-    ignored_query: Query<(Entity, &T), With<OneFrameUndoIgnore>>,
-) {
-    for (e, data) in ignored_query.iter() {
-        storage
-            .storage
-            .insert(e, <T as FromReflect>::from_reflect(data).unwrap());
+impl<T: Component + Clone> EditorChange for AddedComponent<T> {
+    fn revert(
+        &self,
+        world: &mut World,
+        entity_remap: &HashMap<Entity, Entity>,
+    ) -> Result<ChangeResult, String> {
+        let e = get_entity_with_remap(self.entity, entity_remap);
+        let mut add_to_ignore = false;
+        if let Ok(mut e) = world.get_entity_mut(e) {
+            e.remove::<T>().insert(OneFrameUndoIgnore::default());
+            add_to_ignore = true;
+        }
+        if add_to_ignore {
+            world
+                .resource_mut::<UndoIgnoreStorage>()
+                .storage
+                .insert(e, OneFrameUndoIgnore::default());
+        }
+
+        info!("Reverted AddedComponent for entity: {}", e.index());
+
+        Ok(ChangeResult::Success)
     }
-}
 
-fn auto_undo_add_init<T: Component + Clone>(
-    mut commands: Commands,
-    mut storage: ResMut<AutoUndoStorage<T>>,
-    query: Query<(Entity, &T), (With<UndoMarker>, Added<T>, Without<OneFrameUndoIgnore>)>,
-    just_maker_added_query: Query<(Entity, &T), (Added<UndoMarker>, Without<OneFrameUndoIgnore>)>,
-    mut new_changes: EventWriter<NewChange>,
-) {
-    for (e, data) in query.iter() {
-        storage.storage.insert(e, data.clone());
-        commands.entity(e).insert(OneFrameUndoIgnore::default());
-        new_changes.write(NewChange::new(AddedComponent {
-            new_value: data.clone(),
-            entity: e,
-        }));
+    fn debug_text(&self) -> String {
+        format!("AddedComponent for entity {:?}", self.entity)
     }
 
-    for (e, data) in just_maker_added_query.iter() {
-        storage.storage.insert(e, data.clone());
+    fn get_inverse(&self) -> Arc<dyn EditorChange + Send + Sync> {
+        Arc::new(RemovedComponent {
+            entity: self.entity,
+            old_value: self.new_value.clone(),
+        })
     }
 }
 
-fn auto_undo_reflected_add_init<T: Component + Reflect + FromReflect>(
-    mut commands: Commands,
-    mut storage: ResMut<AutoUndoStorage<T>>,
-    query: Query<(Entity, &T), (With<UndoMarker>, Added<T>, Without<OneFrameUndoIgnore>)>,
-    just_maker_added_query: Query<(Entity, &T), (Added<UndoMarker>, Without<OneFrameUndoIgnore>)>,
-    mut new_changes: EventWriter<NewChange>,
-) {
-    for (e, data) in query.iter() {
-        storage
+/// Represents a change for adding a reflected component to an entity.
+///
+/// This struct is used to track the addition of a component that supports reflection
+/// to an entity, allowing the undo system to remove it when necessary.
+///
+/// # Type Parameters
+///
+/// * `T`: The type of the component that was added. Must implement `Component`, `Reflect`,
+///   and `FromReflect` traits.
+pub struct ReflectedAddedComponent<T: Component + Reflect + FromReflect> {
+    /// The value of the component that was added.
+    new_value: T,
+    /// The ID of the entity to which the component was added.
+    entity: Entity,
+}
+
+impl<T: Component + Reflect + FromReflect> EditorChange for ReflectedAddedComponent<T> {
+    fn revert(
+        &self,
+        world: &mut World,
+        entity_remap: &HashMap<Entity, Entity>,
+    ) -> Result<ChangeResult, String> {
+        let dst = entity_remap
+            .get(&self.entity)
+            .map_or(self.entity, |remapped| *remapped);
+        if let Ok(mut e) = world.get_entity_mut(dst) {
+            e.remove::<T>().insert(OneFrameUndoIgnore::default());
+        }
+        world
+            .resource_mut::<UndoIgnoreStorage>()
             .storage
-            .insert(e, <T as FromReflect>::from_reflect(data).unwrap());
-        commands.entity(e).insert(OneFrameUndoIgnore::default());
-        new_changes.write(NewChange {
-            change: Arc::new(ReflectedAddedComponent {
-                new_value: <T as FromReflect>::from_reflect(data).unwrap(),
-                entity: e,
-            }),
+            .insert(dst, OneFrameUndoIgnore::default());
+        world.write_event(UndoRedoApplied::<T> {
+            entity: dst,
+            _phantom: std::marker::PhantomData,
         });
+
+        info!(
+            "Reverted ReflectedAddedComponent for entity: {}",
+            dst.index()
+        );
+
+        Ok(ChangeResult::Success)
     }
 
-    for (e, data) in just_maker_added_query.iter() {
-        storage
-            .storage
-            .insert(e, <T as FromReflect>::from_reflect(data).unwrap());
+    fn debug_text(&self) -> String {
+        format!("ReflectedAddedComponent for entity {:?}", self.entity)
     }
-}
 
-fn undo_ignore_tick(mut ignore_storage: ResMut<UndoIgnoreStorage>) {
-    for (_, frame) in ignore_storage.storage.iter_mut() {
-        frame.counter -= 1;
+    fn get_inverse(&self) -> Arc<dyn EditorChange + Send + Sync> {
+        Arc::new(ReflectedRemovedComponent {
+            old_value: <T as FromReflect>::from_reflect(&self.new_value).unwrap(),
+            entity: self.entity,
+        })
+    }
+
+    fn persist(&self, context: &PersistContext) -> Option<PersistedChange> {
+        Some(PersistedChange::ComponentAdded {
+            entity: context.stable_id(self.entity)?,
+            value: context.serialize(self.new_value.as_partial_reflect())?,
+        })
+    }
+
+    fn memory_size(&self) -> usize {
+        size_of::<Self>() + reflect_heap_size(self.new_value.as_partial_reflect())
     }
-    ignore_storage.storage.retain(|_, frame| frame.counter > 0);
 }
 
-fn auto_undo_remove_detect<T: Component + Clone>(
-    _commands: Commands,
-    mut storage: ResMut<AutoUndoStorage<T>>,
-    mut removed_query: RemovedComponents<T>,
-    mut new_changes: EventWriter<NewChange>,
-    ignore_storage: ResMut<UndoIgnoreStorage>,
-) {
-    for e in removed_query.read() {
-        if !ignore_storage.storage.contains_key(&e)
-            && let Some(prev_value) = storage.storage.remove(&e)
-        {
+/// Represents a change for removing a component from an entity.
+///
+/// This struct is used to track the removal of a component from an entity,
+/// allowing the undo system to re-add it when necessary.
+///
+/// # Type Parameters
+///
+/// * `T`: The type of the component that was removed. Must implement the `Component` and `Clone` traits.
+pub struct RemovedComponent<T: Component + Clone> {
+    /// The value of the component that was removed.
+    old_value: T,
+    /// The ID of the entity from which the component was removed.
+    entity: Entity,
+}
+
+impl<T: Component + Clone> EditorChange for RemovedComponent<T> {
+    fn revert(
+        &self,
+        world: &mut World,
+        entity_remap: &HashMap<Entity, Entity>,
+    ) -> Result<ChangeResult, String> {
+        let mut remap = vec![];
+        let dst = entity_remap.get(&self.entity).map_or_else(
+            || {
+                if world.get_entity(self.entity).is_ok() {
+                    self.entity
+                } else {
+                    let id = world.spawn_empty().id();
+                    remap.push((self.entity, id));
+                    id
+                }
+            },
+            |remapped| *remapped,
+        );
+
+        world
+            .entity_mut(dst)
+            .insert(self.old_value.clone())
+            .insert(OneFrameUndoIgnore::default());
+
+        info!("Reverted RemovedComponent for entity: {}", dst.index());
+
+        Ok(ChangeResult::SuccessWithRemap(remap))
+    }
+
+    fn debug_text(&self) -> String {
+        format!("RemovedComponent for entity {:?}", self.entity)
+    }
+
+    fn get_inverse(&self) -> Arc<dyn EditorChange + Send + Sync> {
+        Arc::new(AddedComponent {
+            new_value: self.old_value.clone(),
+            entity: self.entity,
+        })
+    }
+}
+
+/// Represents a change for removing a reflected component from an entity.
+///
+/// This struct is used to track the removal of a component that supports reflection
+/// from an entity, allowing the undo system to re-add it when necessary.
+///
+/// # Type Parameters
+///
+/// * `T`: The type of the component that was removed. Must implement `Component` and `Reflect` traits.
+pub struct ReflectedRemovedComponent<T: Component + Reflect> {
+    /// The value of the component that was removed.
+    old_value: T,
+    /// The ID of the entity from which the component was removed.
+    entity: Entity,
+}
+
+impl<T: Component + Reflect + FromReflect> EditorChange for ReflectedRemovedComponent<T> {
+    fn revert(
+        &self,
+        world: &mut World,
+        entity_remap: &HashMap<Entity, Entity>,
+    ) -> Result<ChangeResult, String> {
+        let mut remap = vec![];
+        let dst = entity_remap.get(&self.entity).map_or_else(
+            || {
+                if world.get_entity(self.entity).is_ok() {
+                    self.entity
+                } else {
+                    let id = world.spawn_empty().id();
+                    remap.push((self.entity, id));
+                    id
+                }
+            },
+            |remapped| *remapped,
+        );
+
+        world
+            .entity_mut(dst)
+            .insert(<T as FromReflect>::from_reflect(&self.old_value).unwrap())
+            .insert(OneFrameUndoIgnore::default());
+        world.write_event(UndoRedoApplied::<T> {
+            entity: dst,
+            _phantom: std::marker::PhantomData,
+        });
+
+        info!(
+            "Reverted ReflectedRemovedComponent for entity: {}",
+            dst.index()
+        );
+
+        Ok(ChangeResult::SuccessWithRemap(remap))
+    }
+
+    fn debug_text(&self) -> String {
+        format!("ReflectedRemovedComponent for entity {:?}", self.entity)
+    }
+
+    fn get_inverse(&self) -> Arc<dyn EditorChange + Send + Sync> {
+        Arc::new(ReflectedAddedComponent {
+            new_value: <T as FromReflect>::from_reflect(&self.old_value).unwrap(),
+            entity: self.entity,
+        })
+    }
+
+    fn persist(&self, context: &PersistContext) -> Option<PersistedChange> {
+        Some(PersistedChange::ComponentRemoved {
+            entity: context.stable_id(self.entity)?,
+            value: context.serialize(self.old_value.as_partial_reflect())?,
+        })
+    }
+
+    fn memory_size(&self) -> usize {
+        size_of::<Self>() + reflect_heap_size(self.old_value.as_partial_reflect())
+    }
+}
+
+/// Represents a collection of multiple changes that occurred simultaneously and should be applied or reverted together.
+///
+/// `ManyChanges` is automatically generated by the undo system to group multiple `EditorChange`
+/// instances that occur within the same frame or update cycle. This allows complex or multi-part
+/// operations to be treated as a single, atomic change for undo/redo purposes.
+///
+/// # Implementation
+///
+/// `ManyChanges` implements the `EditorChange` trait, allowing it to be treated as a single change
+/// in the undo/redo system. When reverted, it applies all contained changes in reverse order
+/// to ensure proper undo behavior.
+pub struct ManyChanges {
+    changes: Vec<Arc<dyn EditorChange + Send + Sync>>,
+    label: Option<String>,
+}
+
+impl ManyChanges {
+    /// Group changes made together, in the order they were made, so they are undone as one.
+    pub fn new(changes: Vec<Arc<dyn EditorChange + Send + Sync>>) -> Self {
+        Self {
+            changes,
+            label: None,
+        }
+    }
+
+    /// Group changes made together like [`ManyChanges::new`], describing them with `label`.
+    pub fn with_label(
+        changes: Vec<Arc<dyn EditorChange + Send + Sync>>,
+        label: impl Into<String>,
+    ) -> Self {
+        Self {
+            changes,
+            label: Some(label.into()),
+        }
+    }
+}
+
+impl ManyChanges {
+    /// Reapply the first `count` changes, latest first, after the next one failed to be reverted,
+    /// so that the group is left as a whole in the world, as the chain expects of a failed change.
+    ///
+    /// The entities respawned by reapplying the changes have their references remapped in the
+    /// world, but not in the chain, which only learns of the entities respawned by a successful
+    /// change.
+    fn reapply(&self, world: &mut World, count: usize, mut remap: HashMap<Entity, Entity>) {
+        let mut new_remap = HashMap::default();
+        for change in self.changes[..count].iter().rev() {
+            match change.get_inverse().revert(world, &remap) {
+                Ok(ChangeResult::Success) => {}
+                Ok(ChangeResult::SuccessWithRemap(pairs)) => {
+                    remap.extend(pairs.iter().copied());
+                    new_remap.extend(pairs);
+                }
+                Err(error) => warn!(
+                    "Failed to reapply \"{}\" after its group failed to be reverted: {error}",
+                    change.debug_text()
+                ),
+            }
+        }
+        if !new_remap.is_empty() {
+            remap_entity_references(world, &new_remap);
+        }
+    }
+}
+
+impl EditorChange for ManyChanges {
+    fn revert(
+        &self,
+        world: &mut World,
+        entity_remap: &HashMap<Entity, Entity>,
+    ) -> Result<ChangeResult, String> {
+        // The chain's remap is only copied once a change respawns entities the next ones may refer to
+        let mut remap: Option<HashMap<Entity, Entity>> = None;
+        let mut new_pairs = Vec::new();
+        for (reverted, change) in self.changes.iter().enumerate() {
+            let res = match change.revert(world, remap.as_ref().unwrap_or(entity_remap)) {
+                Ok(res) => res,
+                Err(error) => {
+                    let remap = remap.unwrap_or_else(|| entity_remap.clone());
+                    self.reapply(world, reverted, remap);
+                    return Err(error);
+                }
+            };
+            match res {
+                ChangeResult::Success => {}
+                ChangeResult::SuccessWithRemap(new_remap) => {
+                    remap
+                        .get_or_insert_with(|| entity_remap.clone())
+                        .extend(new_remap.iter().copied());
+                    new_pairs.extend(new_remap);
+                }
+            }
+        }
+
+        info!("Reverted ManyChanges");
+
+        if new_pairs.is_empty() {
+            Ok(ChangeResult::Success)
+        } else {
+            Ok(ChangeResult::SuccessWithRemap(new_pairs))
+        }
+    }
+
+    fn debug_text(&self) -> String {
+        self.label
+            .clone()
+            .unwrap_or_else(|| "ManyChanges".to_string())
+    }
+
+    fn get_inverse(&self) -> Arc<dyn EditorChange + Send + Sync> {
+        let mut old_changes = self.changes.clone();
+        old_changes.reverse();
+        let new_changes = old_changes
+            .iter()
+            .map(|change| change.get_inverse())
+            .collect::<Vec<_>>();
+
+        Arc::new(ManyChanges {
+            changes: new_changes,
+            label: self.label.clone(),
+        })
+    }
+
+    /// Changes made together merge with the next ones if they are all merged pairwise, e.g. when
+    /// editing several entities at once. Labeled steps are never merged, as they are explicit.
+    fn merge(
+        &self,
+        next: &(dyn EditorChange + Send + Sync),
+    ) -> Option<Arc<dyn EditorChange + Send + Sync>> {
+        let next = (next as &dyn Any).downcast_ref::<Self>()?;
+        if self.label.is_some() || next.label.is_some() || self.changes.len() != next.changes.len()
+        {
+            return None;
+        }
+        let changes = self
+            .changes
+            .iter()
+            .zip(&next.changes)
+            .map(|(change, next)| change.merge(next.as_ref()))
+            .collect::<Option<Vec<_>>>()?;
+        Some(Arc::new(ManyChanges::new(changes)))
+    }
+
+    fn persist(&self, context: &PersistContext) -> Option<PersistedChange> {
+        Some(PersistedChange::Many {
+            label: self.label.clone(),
+            changes: self
+                .changes
+                .iter()
+                .map(|change| change.persist(context))
+                .collect::<Option<_>>()?,
+        })
+    }
+
+    fn memory_size(&self) -> usize {
+        size_of::<Self>()
+            + self.label.as_ref().map_or(0, String::capacity)
+            + self
+                .changes
+                .iter()
+                .map(|change| size_of_val(change) + change.memory_size())
+                .sum::<usize>()
+    }
+}
+
+/// A component that marks an entity as having a changed component of type `T`.
+///
+/// This marker is used internally by the undo system to track changes to components
+/// and manage the timing of when these changes should be recorded for undo operations.
+///
+/// # Type Parameters
+///
+/// * `T`: The type of the component that has changed.
+///
+/// # Fields
+///
+/// * `latency`: An integer representing the number of frames to wait before recording the change.
+///   This helps to batch rapid changes and avoid creating unnecessary undo entries.
+///
+/// # Usage
+///
+/// This component is automatically added and removed by the undo system and should not
+/// be manipulated directly by users.
+#[derive(Component)]
+pub struct ChangedMarker<T> {
+    latency: i32,
+    _phantom: std::marker::PhantomData<T>,
+}
+
+impl<T> Default for ChangedMarker<T> {
+    fn default() -> Self {
+        Self {
+            latency: AUTO_UNDO_LATENCY, //2 frame latency
+            _phantom: std::marker::PhantomData,
+        }
+    }
+}
+
+/// A resource that stores entities that should be ignored by the undo system for a short period.
+///
+/// This storage is used to prevent newly undone or redone changes from immediately
+/// triggering new undo entries, which could lead to infinite loops or unexpected behavior.
+///
+/// # Fields
+///
+/// * `storage`: A `HashMap` that associates entities with `OneFrameUndoIgnore` components.
+///
+/// # Usage
+///
+/// This resource is managed internally by the undo system. It is updated automatically
+/// when undo or redo operations are performed, and its contents are used to filter
+/// which entity changes should be recorded for future undo operations.
+#[derive(Resource, Default)]
+pub struct UndoIgnoreStorage {
+    /// A `HashMap` that associates entities which should be ignored by the undo system for a short period.
+    pub storage: HashMap<Entity, OneFrameUndoIgnore>,
+}
+
+/// A resource that stores the previous state of components for automatic undo functionality.
+///
+/// `AutoUndoStorage<T>` is used internally by the undo system to keep track of component values
+/// before they are changed. This allows the system to revert components to their previous state
+/// when an undo operation is performed.
+///
+/// # Type Parameters
+///
+/// * `T`: The type of component being stored. Must implement the `Component` trait.
+///
+/// # Fields
+///
+/// * `storage`: A `HashMap` that associates entity IDs with their corresponding component values.
+///
+/// # Usage
+///
+/// This resource is automatically created and managed by the undo system when you use the
+/// `auto_undo` or `auto_reflected_undo` methods. You typically don't need to interact with
+/// this resource directly in your application code.
+///
+/// # Example
+///
+/// ```rust
+/// use bevy::prelude::*;
+/// use bevy_undo::*;
+///
+///
+/// App::new()
+///     .add_plugins(UndoPlugin)
+///     .auto_undo::<Transform>();
+///
+/// ```
+///
+/// In this example, `AutoUndoStorage<Transform>` will be automatically created and managed
+/// by the undo system.
+#[derive(Resource)]
+pub struct AutoUndoStorage<T: Component> {
+    /// The storage of "old" components values to allow store "old" component value in `ComponentChange<T>` change
+    pub storage: HashMap<Entity, T>,
+}
+
+impl<T: Component> Default for AutoUndoStorage<T> {
+    fn default() -> Self {
+        Self {
+            storage: HashMap::default(),
+        }
+    }
+}
+
+/// A trait that extends `App` with methods for setting up automatic undo functionality.
+///
+/// `AppAutoUndo` provides methods to easily configure automatic undo/redo support for
+/// specific component types in your Bevy application.
+///
+/// # Methods
+///
+/// * `auto_undo<T: Component + Clone>`: Sets up automatic undo for components that implement
+///   `Clone`.
+/// * `auto_reflected_undo<T: Component + Reflect + FromReflect>`: Sets up automatic undo for
+///   components that support reflection.
+/// * `auto_reflected_undo_all`: Sets up automatic undo for every component registered with
+///   [`ReflectComponent`], except those opting out with [`ReflectNoAutoUndo`].
+///
+/// # Usage
+///
+/// Import this trait and use its methods in your `App` setup to enable automatic undo
+/// functionality for specific components.
+///
+/// # Example
+///
+/// ```rust
+/// use bevy::prelude::*;
+/// use bevy_undo::*;
+///
+///
+///  App::new()
+///       .add_plugins(UndoPlugin)
+///       .auto_undo::<Transform>()
+///       .auto_reflected_undo::<MyReflectedComponent>();
+///
+///
+/// #[derive(Component, Reflect)]
+/// struct MyReflectedComponent {
+///     // fields...
+/// }
+/// ```
+///
+/// This example sets up automatic undo for the `Transform` component and a custom
+/// `MyReflectedComponent` that supports reflection.
+pub trait AppAutoUndo {
+    /// Sets up automatic undo logic for components that implement `Clone`.
+    fn auto_undo<T: Component + Clone>(&mut self) -> &mut Self;
+    /// Sets up automatic undo logic for components that implement `Reflect` and `FromReflect`.
+    fn auto_reflected_undo<T: Component + Reflect + FromReflect>(&mut self) -> &mut Self;
+    /// Sets up automatic undo logic for all the components registered with [`ReflectComponent`]
+    /// in the type registry, including those registered later, on the entities with
+    /// [`UndoMarker`]. Component types opt out by registering [`ReflectNoAutoUndo`].
+    ///
+    /// The changes are recorded as [`DynamicComponentChange`], [`DynamicAddedComponent`] and
+    /// [`DynamicRemovedComponent`]. Components also set up with [`Self::auto_undo`] or
+    /// [`Self::auto_reflected_undo`] should opt out, so that their changes aren't recorded twice.
+    fn auto_reflected_undo_all(&mut self) -> &mut Self;
+}
+
+impl AppAutoUndo for App {
+    fn auto_undo<T: Component + Clone>(&mut self) -> &mut Self {
+        if !self.world().contains_resource::<ChangeChain>() {
+            return self;
+        }
+
+        self.world_mut()
+            .insert_resource(AutoUndoStorage::<T>::default());
+        self.add_event::<UndoRedoApplied<T>>();
+
+        self.add_systems(
+            PostUpdate,
+            (
+                auto_undo_update_cache::<T>,
+                auto_undo_add_init::<T>,
+                auto_undo_remove_detect::<T>,
+                ApplyDeferred,
+                auto_undo_system_changed::<T>,
+                auto_undo_system::<T>,
+            )
+                .chain()
+                .in_set(UndoSet::PerType),
+        );
+
+        self
+    }
+
+    fn auto_reflected_undo<T: Component + Reflect + FromReflect>(&mut self) -> &mut Self {
+        if !self.world().contains_resource::<ChangeChain>() {
+            return self;
+        }
+
+        self.world_mut()
+            .insert_resource(AutoUndoStorage::<T>::default());
+        self.add_event::<UndoRedoApplied<T>>();
+
+        self.add_systems(
+            PostUpdate,
+            (
+                auto_undo_reflected_update_cache::<T>,
+                auto_undo_reflected_add_init::<T>,
+                auto_undo_reflected_remove_detect::<T>,
+                ApplyDeferred,
+                auto_undo_system_changed::<T>,
+                auto_undo_reflected_system::<T>,
+            )
+                .chain()
+                .in_set(UndoSet::PerType),
+        );
+
+        self
+    }
+
+    fn auto_reflected_undo_all(&mut self) -> &mut Self {
+        if !self.world().contains_resource::<ChangeChain>()
+            || self
+                .world()
+                .contains_resource::<reflected_auto_undo::ReflectedAutoUndoStorage>()
+        {
+            return self;
+        }
+
+        // Components maintained by Bevy from other components
+        self.register_type::<Children>()
+            .register_type_data::<Children, ReflectNoAutoUndo>()
+            .register_type::<GlobalTransform>()
+            .register_type_data::<GlobalTransform, ReflectNoAutoUndo>()
+            .register_type::<InheritedVisibility>()
+            .register_type_data::<InheritedVisibility, ReflectNoAutoUndo>()
+            .register_type::<ViewVisibility>()
+            .register_type_data::<ViewVisibility, ReflectNoAutoUndo>();
+
+        self.init_resource::<reflected_auto_undo::ReflectedAutoUndoStorage>();
+        self.add_systems(
+            PostUpdate,
+            reflected_auto_undo::auto_undo_reflected_components.in_set(UndoSet::PerType),
+        );
+
+        self
+    }
+}
+
+fn auto_undo_update_cache<T: Component + Clone>(
+    mut storage: ResMut<AutoUndoStorage<T>>,
+    ignored_query: Query<(Entity, &T), With<OneFrameUndoIgnore>>,
+) {
+    for (e, data) in ignored_query.iter() {
+        storage.storage.insert(e, data.clone());
+    }
+}
+
+fn auto_undo_reflected_update_cache<T: Component + Reflect + FromReflect>(
+    mut storage: ResMut<AutoUndoStorage<T>>,
+    ignored_query: Query<(Entity, &T), With<OneFrameUndoIgnore>>,
+) {
+    for (e, data) in ignored_query.iter() {
+        storage
+            .storage
+            .insert(e, <T as FromReflect>::from_reflect(data).unwrap());
+    }
+}
+
+fn auto_undo_add_init<T: Component + Clone>(
+    mut commands: Commands,
+    mut storage: ResMut<AutoUndoStorage<T>>,
+    query: Query<(Entity, &T), (With<UndoMarker>, Added<T>, Without<OneFrameUndoIgnore>)>,
+    just_maker_added_query: Query<(Entity, &T), (Added<UndoMarker>, Without<OneFrameUndoIgnore>)>,
+    mut new_changes: EventWriter<NewChange>,
+) {
+    for (e, data) in query.iter() {
+        storage.storage.insert(e, data.clone());
+        commands.entity(e).insert(OneFrameUndoIgnore::default());
+        new_changes.write(NewChange::new(AddedComponent {
+            new_value: data.clone(),
+            entity: e,
+        }));
+    }
+
+    for (e, data) in just_maker_added_query.iter() {
+        storage.storage.insert(e, data.clone());
+    }
+}
+
+fn auto_undo_reflected_add_init<T: Component + Reflect + FromReflect>(
+    mut commands: Commands,
+    mut storage: ResMut<AutoUndoStorage<T>>,
+    query: Query<(Entity, &T), (With<UndoMarker>, Added<T>, Without<OneFrameUndoIgnore>)>,
+    just_maker_added_query: Query<(Entity, &T), (Added<UndoMarker>, Without<OneFrameUndoIgnore>)>,
+    mut new_changes: EventWriter<NewChange>,
+) {
+    for (e, data) in query.iter() {
+        storage
+            .storage
+            .insert(e, <T as FromReflect>::from_reflect(data).unwrap());
+        commands.entity(e).insert(OneFrameUndoIgnore::default());
+        new_changes.write(NewChange {
+            change: Arc::new(ReflectedAddedComponent {
+                new_value: <T as FromReflect>::from_reflect(data).unwrap(),
+                entity: e,
+            }),
+        });
+    }
+
+    for (e, data) in just_maker_added_query.iter() {
+        storage
+            .storage
+            .insert(e, <T as FromReflect>::from_reflect(data).unwrap());
+    }
+}
+
+fn undo_ignore_tick(mut ignore_storage: ResMut<UndoIgnoreStorage>) {
+    for (_, frame) in ignore_storage.storage.iter_mut() {
+        frame.counter -= 1;
+    }
+    ignore_storage.storage.retain(|_, frame| frame.counter > 0);
+}
+
+fn auto_undo_remove_detect<T: Component + Clone>(
+    _commands: Commands,
+    mut storage: ResMut<AutoUndoStorage<T>>,
+    mut removed_query: RemovedComponents<T>,
+    mut new_changes: EventWriter<NewChange>,
+    ignore_storage: ResMut<UndoIgnoreStorage>,
+) {
+    for e in removed_query.read() {
+        if !ignore_storage.storage.contains_key(&e)
+            && let Some(prev_value) = storage.storage.remove(&e)
+        {
             new_changes.write(NewChange {
                 change: Arc::new(RemovedComponent {
                     old_value: prev_value,
@@ -1420,207 +2982,1101 @@ fn auto_undo_remove_detect<T: Component + Clone>(
     }
 }
 
-fn auto_undo_reflected_remove_detect<T: Component + Reflect + FromReflect>(
-    _commands: Commands,
-    mut storage: ResMut<AutoUndoStorage<T>>,
-    mut removed_query: RemovedComponents<T>,
-    mut new_changes: EventWriter<NewChange>,
-    ignore_storage: ResMut<UndoIgnoreStorage>,
-) {
-    for e in removed_query.read() {
-        if !ignore_storage.storage.contains_key(&e)
-            && let Some(prev_value) = storage.storage.remove(&e)
-        {
-            new_changes.write(NewChange {
-                change: Arc::new(ReflectedRemovedComponent {
-                    old_value: prev_value,
-                    entity: e,
-                }),
+fn auto_undo_reflected_remove_detect<T: Component + Reflect + FromReflect>(
+    _commands: Commands,
+    mut storage: ResMut<AutoUndoStorage<T>>,
+    mut removed_query: RemovedComponents<T>,
+    mut new_changes: EventWriter<NewChange>,
+    ignore_storage: ResMut<UndoIgnoreStorage>,
+) {
+    for e in removed_query.read() {
+        if !ignore_storage.storage.contains_key(&e)
+            && let Some(prev_value) = storage.storage.remove(&e)
+        {
+            new_changes.write(NewChange {
+                change: Arc::new(ReflectedRemovedComponent {
+                    old_value: prev_value,
+                    entity: e,
+                }),
+            });
+        }
+    }
+}
+
+fn auto_undo_system_changed<T: Component>(
+    mut commands: Commands,
+    query: Query<Entity, (With<UndoMarker>, Changed<T>, Without<OneFrameUndoIgnore>)>,
+) {
+    for entity in query.iter() {
+        commands
+            .entity(entity)
+            .insert(ChangedMarker::<T>::default());
+    }
+}
+
+fn auto_undo_system<T: Component + Clone>(
+    mut commands: Commands,
+    mut storage: ResMut<AutoUndoStorage<T>>,
+    mut query: Query<(Entity, Ref<T>), With<ChangedMarker<T>>>,
+    mut new_change: EventWriter<NewChange>,
+) {
+    for (e, data) in query.iter_mut() {
+        if !data.is_changed() {
+            commands.entity(e).remove::<ChangedMarker<T>>();
+
+            if let Some(prev_value) = storage.storage.get(&e) {
+                new_change.write(NewChange {
+                    change: Arc::new(ComponentChange {
+                        old_value: prev_value.clone(),
+                        new_value: data.clone(),
+                        entity: e,
+                    }),
+                });
+                info!("Auto undo change for entity {:?}", e);
+            }
+
+            storage.storage.insert(e, data.clone());
+        }
+    }
+}
+
+fn auto_undo_reflected_system<T: Component + Reflect + FromReflect>(
+    mut commands: Commands,
+    mut storage: ResMut<AutoUndoStorage<T>>,
+    mut query: Query<(Entity, Ref<T>, &mut ChangedMarker<T>)>,
+    mut new_change: EventWriter<NewChange>,
+) {
+    for (e, data, mut marker) in query.iter_mut() {
+        if !data.is_changed() {
+            marker.latency -= 1;
+            if marker.latency > 0 {
+                continue;
+            }
+
+            commands.entity(e).remove::<ChangedMarker<T>>();
+
+            if let Some(prev_value) = storage.storage.get(&e) {
+                new_change.write(NewChange {
+                    change: Arc::new(ReflectedComponentChange {
+                        old_value: <T as FromReflect>::from_reflect(prev_value).unwrap(),
+                        new_value: <T as FromReflect>::from_reflect(data.as_ref()).unwrap(),
+                        entity: e,
+                    }),
+                });
+                info!("Auto undo change for entity {:?}", e);
+            }
+
+            storage
+                .storage
+                .insert(e, <T as FromReflect>::from_reflect(data.as_ref()).unwrap());
+        } else {
+            marker.latency = AUTO_UNDO_LATENCY;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use bevy::diagnostic::DiagnosticsStore;
+
+    use super::*;
+
+    fn configure_app() -> App {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins).add_plugins(UndoPlugin);
+        app
+    }
+
+    #[test]
+    fn test_undo() {
+        let mut app = configure_app();
+        app.auto_undo::<Name>();
+
+        app.update();
+
+        let test_id = app.world_mut().spawn_empty().id();
+        app.world_mut().write_event(NewChange {
+            change: Arc::new(AddedEntity::new(test_id)),
+        });
+
+        app.update();
+        app.update();
+
+        app.world_mut()
+            .entity_mut(test_id)
+            .insert(Name::default())
+            .insert(UndoMarker);
+        app.world_mut()
+            .get_mut::<Name>(test_id)
+            .unwrap()
+            .set_changed();
+
+        app.update();
+        app.update();
+        app.update();
+        app.update();
+        app.update();
+        app.update();
+
+        assert!(app.world_mut().get_entity(test_id).is_ok());
+
+        app.world_mut().write_event(UndoRedo::Undo);
+
+        app.update();
+        app.update();
+
+        app.update();
+        app.update();
+        app.update();
+
+        assert!(app.world_mut().get::<Name>(test_id).is_none());
+        assert!(app.world_mut().get_entity(test_id).is_ok());
+
+        app.world_mut().write_event(UndoRedo::Undo);
+        app.update();
+        app.update();
+
+        assert!(app.world_mut().get_entity(test_id).is_err());
+    }
+
+    #[test]
+    fn test_auto_reflected_undo_all() {
+        #[derive(Component, Reflect, Default)]
+        #[reflect(Component, NoAutoUndo)]
+        struct Ignored(u32);
+
+        let mut app = configure_app();
+        app.register_type::<Transform>()
+            .register_type::<Ignored>()
+            .auto_reflected_undo_all();
+
+        let test_id = app
+            .world_mut()
+            .spawn((Transform::default(), Ignored(0), UndoMarker))
+            .id();
+        app.update();
+        app.update();
+
+        app.world_mut().get_mut::<Ignored>(test_id).unwrap().0 = 1;
+        app.world_mut()
+            .get_mut::<Transform>(test_id)
+            .unwrap()
+            .translation = Vec3::X;
+        for _ in 0..5 {
+            app.update();
+        }
+
+        assert_eq!(app.world().resource::<ChangeChain>().changes.len(), 1);
+
+        app.world_mut().write_event(UndoRedo::Undo);
+        for _ in 0..5 {
+            app.update();
+        }
+
+        assert_eq!(
+            *app.world().get::<Transform>(test_id).unwrap(),
+            Transform::default()
+        );
+        assert_eq!(app.world().get::<Ignored>(test_id).unwrap().0, 1);
+        // Reverting the change isn't recorded as a new one
+        assert_eq!(app.world().resource::<ChangeChain>().changes.len(), 0);
+        assert_eq!(
+            app.world().resource::<ChangeChain>().changes_for_redo.len(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_undo_with_remap() {
+        let mut app = configure_app();
+
+        app.auto_reflected_undo::<ChildOf>();
+        app.auto_reflected_undo::<Children>();
+
+        let test_id_1 = app.world_mut().spawn(UndoMarker).id();
+        let test_id_2 = app.world_mut().spawn(UndoMarker).id();
+
+        app.world_mut().write_event(NewChange {
+            change: Arc::new(AddedEntity::new(test_id_1)),
+        });
+        app.world_mut().write_event(NewChange {
+            change: Arc::new(AddedEntity::new(test_id_2)),
+        });
+
+        app.update();
+        app.update();
+
+        app.world_mut().entity_mut(test_id_1).add_child(test_id_2);
+
+        app.update();
+        app.update();
+        app.cleanup();
+
+        app.world_mut().entity_mut(test_id_1).despawn();
+        app.world_mut().write_event(NewChange {
+            change: Arc::new(RemovedEntity { entity: test_id_1 }),
+        });
+
+        app.update();
+        app.update();
+
+        app.world_mut().write_event(UndoRedo::Undo);
+
+        app.update();
+        app.update();
+        app.update();
+
+        assert!(app.world_mut().get_entity(test_id_1).is_err());
+        assert!(app.world_mut().get_entity(test_id_2).is_err());
+        assert_eq!(app.world_mut().entities().len(), 2);
+
+        let mut query = app.world_mut().query::<&Children>();
+        assert!(query.single(app.world_mut()).is_ok());
+    }
+
+    #[test]
+    fn test_remap_entity_references() {
+        #[derive(Component, Reflect)]
+        #[reflect(Component)]
+        struct Link(#[entities] Entity);
+
+        let mut app = configure_app();
+        app.register_type::<Link>().register_type::<Transform>();
+
+        let target = app.world_mut().spawn_empty().id();
+        let linked = app.world_mut().spawn(Link(target)).id();
+        let other_target = app.world_mut().spawn_empty().id();
+        let unrelated = [
+            app.world_mut().spawn(Link(other_target)).id(),
+            app.world_mut().spawn(Transform::default()).id(),
+        ];
+        app.update();
+
+        app.world_mut().entity_mut(target).despawn();
+        app.world_mut()
+            .write_event(NewChange::new(RemovedEntity { entity: target }));
+        app.update();
+        app.update();
+
+        app.world_mut().write_event(UndoRedo::Undo);
+        app.update();
+
+        let link = app.world().get::<Link>(linked).unwrap().0;
+        assert_ne!(link, target);
+        assert!(app.world().get_entity(link).is_ok());
+        assert!(app.world().get::<OneFrameUndoIgnore>(linked).is_some());
+        // Components which don't hold the remapped entity aren't inserted again
+        for entity in unrelated {
+            assert!(app.world().get::<OneFrameUndoIgnore>(entity).is_none());
+        }
+    }
+
+    #[test]
+    fn test_many_changes_remap_only_their_entities() {
+        let mut app = configure_app();
+
+        let removed = app.world_mut().spawn_empty().id();
+        let earlier = app.world_mut().spawn_empty().id();
+        let respawned_earlier = app.world_mut().spawn_empty().id();
+        app.world_mut().entity_mut(removed).despawn();
+
+        let mut entity_remap = HashMap::default();
+        entity_remap.insert(earlier, respawned_earlier);
+
+        let changes = ManyChanges::new(vec![Arc::new(RemovedEntity { entity: removed })]);
+        let Ok(ChangeResult::SuccessWithRemap(pairs)) =
+            changes.revert(app.world_mut(), &entity_remap)
+        else {
+            panic!("the removed entity should be respawned");
+        };
+        assert_eq!(pairs.len(), 1);
+        assert_eq!(pairs[0].0, removed);
+
+        let added = app.world_mut().spawn_empty().id();
+        let changes = ManyChanges::new(vec![Arc::new(AddedEntity::new(added))]);
+        assert!(matches!(
+            changes.revert(app.world_mut(), &entity_remap),
+            Ok(ChangeResult::Success)
+        ));
+    }
+
+    #[test]
+    fn test_undo_hierarchy_change() {
+        let mut app = configure_app();
+
+        let parent_1 = app.world_mut().spawn_empty().id();
+        let parent_2 = app.world_mut().spawn_empty().id();
+        let sibling = app.world_mut().spawn(ChildOf(parent_2)).id();
+        let child = app.world_mut().spawn(ChildOf(parent_1)).id();
+
+        app.world_mut()
+            .entity_mut(parent_2)
+            .insert_children(0, &[child]);
+        app.world_mut().write_event(NewChange::new(HierarchyChange {
+            entity: child,
+            old_parent: Some(parent_1),
+            old_index: 0,
+            new_parent: Some(parent_2),
+            new_index: 0,
+            old_transform: None,
+            new_transform: None,
+        }));
+
+        app.update();
+        app.update();
+
+        app.world_mut().write_event(UndoRedo::Undo);
+        app.update();
+
+        assert_eq!(
+            app.world().get::<ChildOf>(child).unwrap().parent(),
+            parent_1
+        );
+        assert_eq!(
+            app.world().get::<Children>(parent_2).unwrap().to_vec(),
+            vec![sibling]
+        );
+
+        app.world_mut().write_event(UndoRedo::Redo);
+        app.update();
+
+        assert_eq!(
+            app.world().get::<Children>(parent_2).unwrap().to_vec(),
+            vec![child, sibling]
+        );
+        assert!(app.world().get::<Children>(parent_1).is_none());
+    }
+
+    fn begin_transaction(app: &mut App, label: &str) {
+        app.world_mut()
+            .resource_scope(|world, mut change_chain: Mut<ChangeChain>| {
+                change_chain.begin_transaction(label, world.resource::<Events<NewChange>>());
+            });
+    }
+
+    #[test]
+    fn test_undo_transaction() {
+        let mut app = configure_app();
+        app.register_type::<Transform>();
+
+        let transform = Transform::from_xyz(1.0, 2.0, 3.0);
+        let entities: Vec<Entity> = (0..3)
+            .map(|_| app.world_mut().spawn(transform).id())
+            .collect();
+
+        begin_transaction(&mut app, "Add transforms");
+        for &entity in &entities {
+            app.world_mut()
+                .write_event(NewChange::new(DynamicAddedComponent {
+                    entity,
+                    component_type: TypeId::of::<Transform>(),
+                    new_value: transform.to_dynamic(),
+                }));
+            // Changes in frames far apart are still grouped
+            app.update();
+            app.update();
+        }
+        app.world_mut()
+            .resource_mut::<ChangeChain>()
+            .commit_transaction();
+        app.update();
+
+        let change_chain = app.world().resource::<ChangeChain>();
+        assert!(!change_chain.in_transaction());
+        assert_eq!(change_chain.changes.len(), 1);
+        assert_eq!(change_chain.changes[0].debug_text(), "Add transforms");
+
+        app.world_mut().write_event(UndoRedo::Undo);
+        app.update();
+
+        for &entity in &entities {
+            assert!(app.world().get::<Transform>(entity).is_none());
+        }
+
+        let entity = entities[0];
+        begin_transaction(&mut app, "Cancelled");
+        app.world_mut().entity_mut(entity).insert(transform);
+        app.world_mut()
+            .write_event(NewChange::new(DynamicAddedComponent {
+                entity,
+                component_type: TypeId::of::<Transform>(),
+                new_value: transform.to_dynamic(),
+            }));
+        app.world_mut()
+            .resource_mut::<ChangeChain>()
+            .cancel_transaction();
+        app.update();
+        app.update();
+
+        // The changes of the cancelled transaction are reverted, and not recorded
+        assert!(app.world().get::<Transform>(entity).is_none());
+        let change_chain = app.world().resource::<ChangeChain>();
+        assert!(change_chain.changes.is_empty());
+        assert_eq!(change_chain.changes_for_redo.len(), 1);
+    }
+
+    #[test]
+    fn test_undo_transactions_in_one_frame() {
+        let mut app = configure_app();
+        app.register_type::<Transform>();
+
+        let transform = Transform::from_xyz(1.0, 2.0, 3.0);
+        let entities = [
+            app.world_mut().spawn_empty().id(),
+            app.world_mut().spawn_empty().id(),
+        ];
+
+        let mut commands = app.world_mut().commands();
+        for (label, entity) in ["A", "B"].into_iter().zip(entities) {
+            commands.undo_transaction(label, move |world| {
+                world.entity_mut(entity).insert(transform);
+                world.write_event(NewChange::new(DynamicAddedComponent {
+                    entity,
+                    component_type: TypeId::of::<Transform>(),
+                    new_value: transform.to_dynamic(),
+                }));
             });
         }
+        app.world_mut().flush();
+        app.update();
+
+        let change_chain = app.world().resource::<ChangeChain>();
+        let labels: Vec<String> = change_chain
+            .changes
+            .iter()
+            .map(|change| change.debug_text())
+            .collect();
+        assert_eq!(labels, ["A", "B"]);
+
+        app.world_mut().write_event(UndoRedo::Undo);
+        app.update();
+
+        assert!(app.world().get::<Transform>(entities[0]).is_some());
+        assert!(app.world().get::<Transform>(entities[1]).is_none());
+    }
+
+    #[test]
+    fn test_changes_sent_before_transaction() {
+        let mut app = configure_app();
+        app.register_type::<Transform>();
+
+        let transform = Transform::from_xyz(1.0, 2.0, 3.0);
+        let entities = [
+            app.world_mut().spawn(transform).id(),
+            app.world_mut().spawn(transform).id(),
+        ];
+        let added = |entity: Entity| {
+            NewChange::new(DynamicAddedComponent {
+                entity,
+                component_type: TypeId::of::<Transform>(),
+                new_value: transform.to_dynamic(),
+            })
+        };
+
+        app.world_mut().write_event(added(entities[0]));
+        begin_transaction(&mut app, "Transaction");
+        app.world_mut().write_event(added(entities[1]));
+        app.world_mut()
+            .resource_mut::<ChangeChain>()
+            .commit_transaction();
+        app.update();
+        app.update();
+
+        let change_chain = app.world().resource::<ChangeChain>();
+        assert_eq!(change_chain.changes.len(), 2);
+        assert_eq!(change_chain.changes[1].debug_text(), "Transaction");
+
+        app.world_mut().write_event(UndoRedo::Undo);
+        app.update();
+
+        assert!(app.world().get::<Transform>(entities[0]).is_some());
+        assert!(app.world().get::<Transform>(entities[1]).is_none());
     }
-}
 
-fn auto_undo_system_changed<T: Component>(
-    mut commands: Commands,
-    query: Query<Entity, (With<UndoMarker>, Changed<T>, Without<OneFrameUndoIgnore>)>,
-) {
-    for entity in query.iter() {
-        commands
-            .entity(entity)
-            .insert(ChangedMarker::<T>::default());
+    #[test]
+    fn test_undo_failure() {
+        let mut app = configure_app();
+
+        let parent = app.world_mut().spawn_empty().id();
+        let child = app.world_mut().spawn(ChildOf(parent)).id();
+        app.world_mut().write_event(NewChange::new(HierarchyChange {
+            entity: child,
+            old_parent: None,
+            old_index: 0,
+            new_parent: Some(parent),
+            new_index: 0,
+            old_transform: None,
+            new_transform: None,
+        }));
+
+        app.update();
+        app.update();
+
+        app.world_mut().entity_mut(child).despawn();
+        app.world_mut().write_event(UndoRedo::Undo);
+        app.update();
+
+        let events = app.world().resource::<Events<UndoRedoFailed>>();
+        let failures: Vec<_> = events.get_cursor().read(events).collect();
+        assert_eq!(failures.len(), 1);
+        assert_eq!(failures[0].operation, UndoRedo::Undo);
+
+        let change_chain = app.world().resource::<ChangeChain>();
+        assert!(change_chain.changes.is_empty());
+        assert!(change_chain.changes_for_redo.is_empty());
     }
-}
 
-fn auto_undo_system<T: Component + Clone>(
-    mut commands: Commands,
-    mut storage: ResMut<AutoUndoStorage<T>>,
-    mut query: Query<(Entity, Ref<T>), With<ChangedMarker<T>>>,
-    mut new_change: EventWriter<NewChange>,
-) {
-    for (e, data) in query.iter_mut() {
-        if !data.is_changed() {
-            commands.entity(e).remove::<ChangedMarker<T>>();
+    #[test]
+    fn test_undo_change_of_despawned_entity() {
+        let mut app = configure_app();
+        app.auto_reflected_undo::<Transform>();
 
-            if let Some(prev_value) = storage.storage.get(&e) {
-                new_change.write(NewChange {
-                    change: Arc::new(ComponentChange {
-                        old_value: prev_value.clone(),
-                        new_value: data.clone(),
-                        entity: e,
-                    }),
-                });
-                info!("Auto undo change for entity {:?}", e);
-            }
+        let entity = app
+            .world_mut()
+            .spawn((Transform::default(), UndoMarker))
+            .id();
+        app.update();
+        app.update();
 
-            storage.storage.insert(e, data.clone());
+        app.world_mut()
+            .get_mut::<Transform>(entity)
+            .unwrap()
+            .translation = Vec3::X;
+        for _ in 0..5 {
+            app.update();
         }
+        assert_eq!(app.world().resource::<ChangeChain>().changes.len(), 1);
+
+        app.world_mut().entity_mut(entity).despawn();
+        app.world_mut().write_event(UndoRedo::Undo);
+        app.update();
+
+        let events = app.world().resource::<Events<UndoRedoFailed>>();
+        let failures: Vec<_> = events.get_cursor().read(events).collect();
+        assert_eq!(failures.len(), 1);
+        assert_eq!(failures[0].operation, UndoRedo::Undo);
+        assert!(app.world().resource::<ChangeChain>().changes.is_empty());
     }
-}
 
-fn auto_undo_reflected_system<T: Component + Reflect + FromReflect>(
-    mut commands: Commands,
-    mut storage: ResMut<AutoUndoStorage<T>>,
-    mut query: Query<(Entity, Ref<T>, &mut ChangedMarker<T>)>,
-    mut new_change: EventWriter<NewChange>,
-) {
-    for (e, data, mut marker) in query.iter_mut() {
-        if !data.is_changed() {
-            marker.latency -= 1;
-            if marker.latency > 0 {
-                continue;
-            }
+    #[test]
+    fn test_many_changes_failure_reapplies_reverted_changes() {
+        let mut app = configure_app();
+        app.auto_reflected_undo::<Transform>();
+
+        let old = Transform::default();
+        let new = Transform::from_xyz(1.0, 2.0, 3.0);
+        let entities: Vec<Entity> = (0..3).map(|_| app.world_mut().spawn(new).id()).collect();
+        let changes = ManyChanges::new(
+            entities
+                .iter()
+                .map(|&entity| {
+                    Arc::new(ReflectedComponentChange {
+                        old_value: old,
+                        new_value: new,
+                        entity,
+                    }) as Arc<dyn EditorChange + Send + Sync>
+                })
+                .collect(),
+        );
 
-            commands.entity(e).remove::<ChangedMarker<T>>();
+        app.world_mut().entity_mut(entities[1]).despawn();
+        assert!(
+            changes
+                .revert(app.world_mut(), &HashMap::default())
+                .is_err()
+        );
 
-            if let Some(prev_value) = storage.storage.get(&e) {
-                new_change.write(NewChange {
-                    change: Arc::new(ReflectedComponentChange {
-                        old_value: <T as FromReflect>::from_reflect(prev_value).unwrap(),
-                        new_value: <T as FromReflect>::from_reflect(data.as_ref()).unwrap(),
-                        entity: e,
-                    }),
-                });
-                info!("Auto undo change for entity {:?}", e);
-            }
+        assert_eq!(*app.world().get::<Transform>(entities[0]).unwrap(), new);
+        assert_eq!(*app.world().get::<Transform>(entities[2]).unwrap(), new);
+    }
 
-            storage
-                .storage
-                .insert(e, <T as FromReflect>::from_reflect(data.as_ref()).unwrap());
-        } else {
-            marker.latency = AUTO_UNDO_LATENCY;
+    #[test]
+    fn test_jump_to_stops_at_failure() {
+        let mut app = configure_app();
+        app.register_type::<Transform>();
+
+        let transform = Transform::from_xyz(1.0, 2.0, 3.0);
+        let entities: Vec<Entity> = (0..3)
+            .map(|_| app.world_mut().spawn(transform).id())
+            .collect();
+        // Each change in its own step
+        for &entity in &entities {
+            app.world_mut()
+                .write_event(NewChange::new(DynamicAddedComponent {
+                    entity,
+                    component_type: TypeId::of::<Transform>(),
+                    new_value: transform.to_dynamic(),
+                }));
+            app.update();
+            app.update();
         }
+
+        // The second change can't be undone
+        app.world_mut().entity_mut(entities[1]).despawn();
+        app.world_mut().write_event(UndoRedo::JumpTo(0));
+        app.update();
+
+        assert!(app.world().get::<Transform>(entities[2]).is_none());
+        assert!(app.world().get::<Transform>(entities[0]).is_some());
+        assert_eq!(app.world().resource::<ChangeChain>().changes.len(), 1);
     }
-}
 
-#[cfg(test)]
-mod tests {
+    #[test]
+    fn test_undo_dynamic_component_change() {
+        let mut app = configure_app();
+        app.register_type::<Transform>();
 
-    use super::*;
+        let old_transform = Transform::default();
+        let new_transform = Transform::from_xyz(1.0, 2.0, 3.0);
+        let test_id = app.world_mut().spawn(new_transform).id();
 
-    fn configure_app() -> App {
-        let mut app = App::new();
-        app.add_plugins(MinimalPlugins).add_plugins(UndoPlugin);
-        app
+        app.world_mut()
+            .write_event(NewChange::new(DynamicComponentChange {
+                entity: test_id,
+                component_type: TypeId::of::<Transform>(),
+                old_value: old_transform.to_dynamic(),
+                new_value: new_transform.to_dynamic(),
+            }));
+
+        app.update();
+        app.update();
+
+        app.world_mut().write_event(UndoRedo::Undo);
+        app.update();
+
+        assert_eq!(
+            *app.world().get::<Transform>(test_id).unwrap(),
+            old_transform
+        );
+
+        app.world_mut().write_event(UndoRedo::Redo);
+        app.update();
+
+        assert_eq!(
+            *app.world().get::<Transform>(test_id).unwrap(),
+            new_transform
+        );
     }
 
     #[test]
-    fn test_undo() {
+    fn test_merge_continuous_changes() {
         let mut app = configure_app();
-        app.auto_undo::<Name>();
+        app.register_type::<Transform>();
+
+        let transforms: Vec<Transform> = (0..4)
+            .map(|x| Transform::from_xyz(x as f32, 0.0, 0.0))
+            .collect();
+        let test_id = app.world_mut().spawn(transforms[3]).id();
+
+        // Like typing in a field, each edit is sent in its own step
+        for edit in transforms.windows(2) {
+            app.world_mut()
+                .write_event(NewChange::new(DynamicComponentChange {
+                    entity: test_id,
+                    component_type: TypeId::of::<Transform>(),
+                    old_value: edit[0].to_dynamic(),
+                    new_value: edit[1].to_dynamic(),
+                }));
+            app.update();
+            app.update();
+        }
+
+        assert_eq!(app.world().resource::<ChangeChain>().changes.len(), 1);
 
+        app.world_mut().write_event(UndoRedo::Undo);
         app.update();
 
-        let test_id = app.world_mut().spawn_empty().id();
-        app.world_mut().write_event(NewChange {
-            change: Arc::new(AddedEntity { entity: test_id }),
-        });
+        assert_eq!(
+            *app.world().get::<Transform>(test_id).unwrap(),
+            transforms[0]
+        );
 
-        app.update();
+        app.world_mut().write_event(UndoRedo::Redo);
         app.update();
 
-        app.world_mut()
-            .entity_mut(test_id)
-            .insert(Name::default())
-            .insert(UndoMarker);
-        app.world_mut()
-            .get_mut::<Name>(test_id)
-            .unwrap()
-            .set_changed();
+        assert_eq!(
+            *app.world().get::<Transform>(test_id).unwrap(),
+            transforms[3]
+        );
+    }
 
+    #[test]
+    fn test_memory_budget() {
+        let mut app = configure_app();
+        app.register_type::<Transform>();
+
+        let change = |entity| DynamicComponentChange {
+            entity,
+            component_type: TypeId::of::<Transform>(),
+            old_value: Transform::default().to_dynamic(),
+            new_value: Transform::from_xyz(1.0, 0.0, 0.0).to_dynamic(),
+        };
+        let step_size = change(Entity::PLACEHOLDER).memory_size();
+        app.insert_resource(ChangeChainSettings {
+            max_memory: Some(2 * step_size),
+            ..default()
+        });
+
+        // Changes of different entities can't be merged, each is a step of its own
+        for _ in 0..4 {
+            let entity = app.world_mut().spawn(Transform::default()).id();
+            app.world_mut().write_event(NewChange::new(change(entity)));
+            app.update();
+            app.update();
+        }
+
+        let change_chain = app.world().resource::<ChangeChain>();
+        assert_eq!(change_chain.changes.len(), 2);
+        assert_eq!(change_chain.memory_size(), 2 * step_size);
+
+        let measured = app
+            .world()
+            .resource::<DiagnosticsStore>()
+            .get_measurement(&UndoPlugin::HISTORY_MEMORY)
+            .map(|measurement| measurement.value);
+        assert_eq!(measured, Some((2 * step_size) as f64));
+
+        // The chain isn't changed, nor measured again, while nothing happens
+        let last_changed = app
+            .world()
+            .get_resource_change_ticks::<ChangeChain>()
+            .unwrap()
+            .changed;
         app.update();
+        let change_ticks = app
+            .world()
+            .get_resource_change_ticks::<ChangeChain>()
+            .unwrap();
+        assert_eq!(change_ticks.changed, last_changed);
+
+        app.world_mut().write_event(UndoRedo::Undo);
         app.update();
+        let change_chain = app.world().resource::<ChangeChain>();
+        assert_eq!(change_chain.changes_for_redo.len(), 1);
+        assert_eq!(change_chain.memory_size(), 2 * step_size);
+
+        let entity = app.world_mut().spawn(Transform::default()).id();
+        app.world_mut().write_event(NewChange::new(change(entity)));
         app.update();
         app.update();
+        let change_chain = app.world().resource::<ChangeChain>();
+        assert!(change_chain.changes_for_redo.is_empty());
+        assert_eq!(change_chain.memory_size(), 2 * step_size);
+    }
+
+    #[test]
+    fn test_persist_history() {
+        let path = std::env::temp_dir().join("bevy_undo_test_persist_history.undo.ron");
+        let old_transform = Transform::default();
+        let new_transform = Transform::from_xyz(1.0, 2.0, 3.0);
+
+        let mut app = configure_app();
+        app.register_type::<Transform>();
+        let test_id = app
+            .world_mut()
+            .spawn((StableEntityId(1), new_transform))
+            .id();
+        app.world_mut()
+            .write_event(NewChange::new(DynamicComponentChange {
+                entity: test_id,
+                component_type: TypeId::of::<Transform>(),
+                old_value: old_transform.to_dynamic(),
+                new_value: new_transform.to_dynamic(),
+            }));
         app.update();
         app.update();
+        save_history(app.world(), &path).unwrap();
 
-        assert!(app.world_mut().get_entity(test_id).is_ok());
+        // A new session, where the entity has another ID
+        let mut app = configure_app();
+        app.register_type::<Transform>();
+        app.world_mut().spawn_empty();
+        let test_id = app
+            .world_mut()
+            .spawn((StableEntityId(1), new_transform))
+            .id();
+        load_history(app.world_mut(), &path).unwrap();
+        std::fs::remove_file(&path).unwrap();
 
         app.world_mut().write_event(UndoRedo::Undo);
+        app.update();
+
+        assert_eq!(
+            *app.world().get::<Transform>(test_id).unwrap(),
+            old_transform
+        );
+    }
+
+    #[test]
+    fn test_persist_entity_hierarchy() {
+        let path = std::env::temp_dir().join("bevy_undo_test_persist_entity_hierarchy.undo.ron");
+
+        let mut app = configure_app();
+        app.register_type::<Name>();
+        let parent = app.world_mut().spawn(StableEntityId(1)).id();
+        let test_id = app
+            .world_mut()
+            .spawn((StableEntityId(2), Name::new("Entity"), ChildOf(parent)))
+            .id();
+        app.world_mut()
+            .spawn((StableEntityId(3), Name::new("Child"), ChildOf(test_id)));
 
+        let change = RemovedEntityHierarchy::capture(app.world(), test_id).unwrap();
+        app.world_mut().entity_mut(test_id).despawn();
+        app.world_mut().write_event(NewChange::new(change));
         app.update();
         app.update();
+        assert_eq!(save_history(app.world(), &path), Ok(0));
 
+        // A new session, where the entities have other IDs
+        let mut app = configure_app();
+        app.register_type::<Name>();
+        app.world_mut().spawn_empty();
+        let parent = app.world_mut().spawn(StableEntityId(1)).id();
+        load_history(app.world_mut(), &path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        app.world_mut().write_event(UndoRedo::Undo);
         app.update();
+
+        let children = app.world().get::<Children>(parent).unwrap();
+        assert_eq!(children.len(), 1);
+        let restored = children[0];
+        assert_eq!(
+            app.world().get::<Name>(restored).unwrap().as_str(),
+            "Entity"
+        );
+        let restored_children = app.world().get::<Children>(restored).unwrap();
+        assert_eq!(
+            app.world()
+                .get::<Name>(restored_children[0])
+                .unwrap()
+                .as_str(),
+            "Child"
+        );
+    }
+
+    #[test]
+    fn test_undo_dynamic_added_component() {
+        let mut app = configure_app();
+        app.register_type::<Transform>();
+
+        let transform = Transform::from_xyz(1.0, 2.0, 3.0);
+        let test_id = app.world_mut().spawn(transform).id();
+
+        app.world_mut()
+            .write_event(NewChange::new(DynamicAddedComponent {
+                entity: test_id,
+                component_type: TypeId::of::<Transform>(),
+                new_value: transform.to_dynamic(),
+            }));
+
         app.update();
         app.update();
 
-        assert!(app.world_mut().get::<Name>(test_id).is_none());
-        assert!(app.world_mut().get_entity(test_id).is_ok());
-
         app.world_mut().write_event(UndoRedo::Undo);
         app.update();
+
+        assert!(app.world().get::<Transform>(test_id).is_none());
+
+        app.world_mut().write_event(UndoRedo::Redo);
         app.update();
 
-        assert!(app.world_mut().get_entity(test_id).is_err());
+        assert_eq!(*app.world().get::<Transform>(test_id).unwrap(), transform);
     }
 
     #[test]
-    fn test_undo_with_remap() {
+    fn test_undo_removed_entity_hierarchy() {
         let mut app = configure_app();
+        app.register_type::<Transform>().register_type::<Name>();
+
+        let transform = Transform::from_xyz(1.0, 2.0, 3.0);
+        let parent = app.world_mut().spawn(Name::new("Parent")).id();
+        app.world_mut().spawn(ChildOf(parent));
+        let test_id = app
+            .world_mut()
+            .spawn((Name::new("Entity"), transform, ChildOf(parent)))
+            .id();
+        app.world_mut()
+            .spawn((Name::new("Child"), ChildOf(test_id)));
 
-        app.auto_reflected_undo::<ChildOf>();
-        app.auto_reflected_undo::<Children>();
+        let change = RemovedEntityHierarchy::capture(app.world(), test_id).unwrap();
+        assert_eq!(change.index, Some(1));
+        app.world_mut().entity_mut(test_id).despawn();
+        app.world_mut().write_event(NewChange::new(change));
 
-        let test_id_1 = app.world_mut().spawn(UndoMarker).id();
-        let test_id_2 = app.world_mut().spawn(UndoMarker).id();
+        app.update();
+        app.update();
 
-        app.world_mut().write_event(NewChange {
-            change: Arc::new(AddedEntity { entity: test_id_1 }),
-        });
-        app.world_mut().write_event(NewChange {
-            change: Arc::new(AddedEntity { entity: test_id_2 }),
-        });
+        app.world_mut().write_event(UndoRedo::Undo);
+        app.update();
+
+        let children = app.world().get::<Children>(parent).unwrap();
+        assert_eq!(children.len(), 2);
+        let restored = children[1];
+        assert_ne!(restored, test_id);
+        assert_eq!(
+            app.world().get::<Name>(restored).unwrap().as_str(),
+            "Entity"
+        );
+        assert_eq!(*app.world().get::<Transform>(restored).unwrap(), transform);
+
+        let restored_children = app.world().get::<Children>(restored).unwrap();
+        assert_eq!(restored_children.len(), 1);
+        assert_eq!(
+            app.world()
+                .get::<Name>(restored_children[0])
+                .unwrap()
+                .as_str(),
+            "Child"
+        );
+
+        app.world_mut().write_event(UndoRedo::Redo);
+        app.update();
+
+        assert!(app.world().get_entity(restored).is_err());
+        assert_eq!(app.world().get::<Children>(parent).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_redo_added_entity() {
+        let mut app = configure_app();
+        app.register_type::<Name>();
 
+        let test_id = app.world_mut().spawn(Name::new("Entity")).id();
+        app.world_mut()
+            .spawn((Name::new("Child"), ChildOf(test_id)));
+        app.world_mut()
+            .write_event(NewChange::new(AddedEntity::new(test_id)));
         app.update();
         app.update();
 
-        app.world_mut().entity_mut(test_id_1).add_child(test_id_2);
+        app.world_mut().write_event(UndoRedo::Undo);
+        app.update();
+        assert!(app.world().get_entity(test_id).is_err());
 
+        app.world_mut().write_event(UndoRedo::Redo);
         app.update();
+
+        let mut query = app
+            .world_mut()
+            .query_filtered::<(Entity, &Name), Without<ChildOf>>();
+        let (restored, name) = query.single(app.world()).unwrap();
+        assert_eq!(name.as_str(), "Entity");
+        let children = app.world().get::<Children>(restored).unwrap();
+        assert_eq!(children.len(), 1);
+        assert_eq!(
+            app.world().get::<Name>(children[0]).unwrap().as_str(),
+            "Child"
+        );
+
+        // Undoing again despawns the respawned entity
+        app.world_mut().write_event(UndoRedo::Undo);
         app.update();
-        app.cleanup();
+        assert!(app.world().get_entity(restored).is_err());
+    }
 
-        app.world_mut().entity_mut(test_id_1).despawn();
-        app.world_mut().write_event(NewChange {
-            change: Arc::new(RemovedEntity { entity: test_id_1 }),
-        });
+    #[test]
+    fn test_despawn_with_undo() {
+        let mut app = configure_app();
+        app.register_type::<Name>();
 
+        let test_id = app.world_mut().spawn(Name::new("Entity")).id();
+        app.world_mut()
+            .spawn((Name::new("Child"), ChildOf(test_id)));
+        app.world_mut()
+            .commands()
+            .entity(test_id)
+            .despawn_with_undo();
+        app.world_mut().flush();
         app.update();
         app.update();
+        assert!(app.world().get_entity(test_id).is_err());
 
         app.world_mut().write_event(UndoRedo::Undo);
+        app.update();
+
+        let mut query = app
+            .world_mut()
+            .query_filtered::<(Entity, &Name), Without<ChildOf>>();
+        let (restored, name) = query.single(app.world()).unwrap();
+        assert_eq!(name.as_str(), "Entity");
+        assert_eq!(app.world().get::<Children>(restored).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_undo_removed_entity_hierarchy_unregistered() {
+        #[derive(Component, Reflect)]
+        struct Unregistered;
+
+        let mut app = configure_app();
+        let entity = app.world_mut().spawn_empty().id();
+        let change = RemovedEntityHierarchy {
+            snapshot: Arc::new(EntitySnapshot {
+                entity,
+                components: vec![],
+                children: vec![EntitySnapshot {
+                    entity: Entity::PLACEHOLDER,
+                    components: vec![(
+                        TypeId::of::<Unregistered>(),
+                        Box::new(Unregistered) as Box<dyn PartialReflect>,
+                    )],
+                    children: vec![],
+                }],
+            }),
+            index: None,
+        };
+        app.world_mut().entity_mut(entity).despawn();
+        let entity_count = app.world().entities().len();
+
+        assert!(change.revert(app.world_mut(), &HashMap::default()).is_err());
+        assert_eq!(app.world().entities().len(), entity_count);
+    }
+
+    #[test]
+    fn test_undo_dynamic_resource_change() {
+        let mut app = configure_app();
+        app.register_type::<ChangeChainSettings>();
+
+        let old_settings = ChangeChainSettings::default();
+        let new_settings = ChangeChainSettings {
+            max_change_chain_size: 10,
+            ..default()
+        };
+        app.insert_resource(ChangeChainSettings {
+            max_change_chain_size: 10,
+            ..default()
+        });
+
+        app.world_mut()
+            .write_event(NewChange::new(DynamicResourceChange {
+                resource_type: TypeId::of::<ChangeChainSettings>(),
+                old_value: old_settings.to_dynamic(),
+                new_value: new_settings.to_dynamic(),
+            }));
 
         app.update();
         app.update();
+
+        app.world_mut().write_event(UndoRedo::Undo);
         app.update();
 
-        assert!(app.world_mut().get_entity(test_id_1).is_err());
-        assert!(app.world_mut().get_entity(test_id_2).is_err());
-        assert_eq!(app.world_mut().entities().len(), 2);
+        assert_eq!(
+            app.world()
+                .resource::<ChangeChainSettings>()
+                .max_change_chain_size,
+            200
+        );
 
-        let mut query = app.world_mut().query::<&Children>();
-        assert!(query.single(app.world_mut()).is_ok());
+        app.world_mut().write_event(UndoRedo::Redo);
+        app.update();
+
+        assert_eq!(
+            app.world()
+                .resource::<ChangeChainSettings>()
+                .max_change_chain_size,
+            10
+        );
     }
 }
@@ -0,0 +1,661 @@
+//! Saving the undo history to disk, so that the changes of a previous session can be undone after
+//! reopening a scene, including after a crash.
+//!
+//! On disk, changes refer to entities by their [`StableEntityId`], as [`Entity`] IDs don't outlive
+//! a session. Component values are serialized through reflection, so their types have to be
+//! registered in the [`AppTypeRegistry`]. Only the most recent changes which can all be persisted
+//! are saved, as the older ones couldn't be undone without undoing those first, and the changes to
+//! redo are not saved. The changes of components which are not reflected, such as
+//! [`ComponentChange`](crate::ComponentChange), can't be persisted.
+
+use std::{
+    any::TypeId,
+    hash::{BuildHasher, RandomState},
+    path::{Path, PathBuf},
+    sync::{
+        Arc,
+        atomic::{AtomicU64, Ordering},
+    },
+};
+
+use bevy::{
+    ecs::entity::EntityMapper,
+    platform::collections::HashMap,
+    prelude::*,
+    reflect::{
+        ReflectFromReflect, TypeRegistry,
+        serde::{ReflectDeserializer, ReflectSerializer},
+    },
+};
+use serde::{Deserialize, Serialize, de::DeserializeSeed};
+
+use crate::{
+    AddedEntity, AddedEntityHierarchy, ChangeChain, ChangeChainSettings, DynamicAddedComponent,
+    DynamicComponentChange, DynamicRemovedComponent, EditorChange, EntitySnapshot, HierarchyChange,
+    ManyChanges, ReflectNoAutoUndo, RemovedEntity, RemovedEntityHierarchy,
+};
+
+/// An identifier of an entity which is kept across sessions, unlike its [`Entity`] ID, as it is
+/// saved along with the scene.
+///
+/// The default value is a new random identifier. [`UndoMarker`](crate::UndoMarker) requires it, so
+/// that the changes recorded by automatic undo can be persisted.
+#[derive(Component, Reflect, Clone, Copy, PartialEq, Eq, Hash, Debug, Serialize, Deserialize)]
+#[reflect(Component, Default, NoAutoUndo)]
+pub struct StableEntityId(pub u64);
+
+impl Default for StableEntityId {
+    fn default() -> Self {
+        static COUNTER: AtomicU64 = AtomicU64::new(0);
+        // Randomly seeded, so that the identifiers of different sessions don't collide
+        Self(RandomState::new().hash_one(COUNTER.fetch_add(1, Ordering::Relaxed)))
+    }
+}
+
+/// The [`StableEntityId`]s of the entities the recorded changes refer to, including the entities
+/// which were despawned since.
+#[derive(Resource, Default)]
+pub(crate) struct StableEntityIds {
+    ids: HashMap<Entity, StableEntityId>,
+    /// Entities standing for the entities of a loaded history which didn't exist yet, such as the
+    /// entities of a scene which is still being spawned. They are remapped to those entities once
+    /// they get their [`StableEntityId`].
+    placeholders: HashMap<StableEntityId, Entity>,
+}
+
+pub(crate) fn cache_stable_entity_id(
+    trigger: On<Insert, StableEntityId>,
+    stable_ids: Query<&StableEntityId>,
+    mut cache: ResMut<StableEntityIds>,
+    mut change_chain: ResMut<ChangeChain>,
+) {
+    let entity = trigger.target();
+    let Ok(&id) = stable_ids.get(entity) else {
+        return;
+    };
+    cache.ids.insert(entity, id);
+    if let Some(placeholder) = cache.placeholders.remove(&id) {
+        change_chain.entity_remap.insert(placeholder, entity);
+    }
+}
+
+/// A change as saved to disk, see [`EditorChange::persist`].
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub enum PersistedChange {
+    /// A component of an entity changed. Values are serialized with [`PersistContext::serialize`].
+    ComponentChanged {
+        /// The entity whose component changed.
+        entity: StableEntityId,
+        /// The value of the component before the change.
+        old_value: String,
+        /// The value of the component after the change.
+        new_value: String,
+    },
+    /// A component was added to an entity.
+    ComponentAdded {
+        /// The entity to which the component was added.
+        entity: StableEntityId,
+        /// The value of the added component.
+        value: String,
+    },
+    /// A component was removed from an entity.
+    ComponentRemoved {
+        /// The entity from which the component was removed.
+        entity: StableEntityId,
+        /// The value of the removed component.
+        value: String,
+    },
+    /// An entity was added to the world.
+    EntityAdded(StableEntityId),
+    /// An entity was removed from the world.
+    EntityRemoved(StableEntityId),
+    /// An entity was added to the world along with its descendants.
+    EntityHierarchyAdded(PersistedHierarchy),
+    /// An entity was removed from the world along with its descendants.
+    EntityHierarchyRemoved(PersistedHierarchy),
+    /// An entity was moved in the hierarchy, see [`HierarchyChange`].
+    HierarchyChanged {
+        /// The entity which was moved.
+        entity: StableEntityId,
+        /// The parent of the entity before the move, `None` if it was a root entity.
+        old_parent: Option<StableEntityId>,
+        /// The index of the entity among the children of `old_parent` before the move.
+        old_index: usize,
+        /// The parent of the entity after the move, `None` if it became a root entity.
+        new_parent: Option<StableEntityId>,
+        /// The index of the entity among the children of `new_parent` after the move.
+        new_index: usize,
+        /// The [`Transform`] of the entity before the move, if it was adjusted by the move.
+        old_transform: Option<String>,
+        /// The [`Transform`] of the entity after the move, if it was adjusted by the move.
+        new_transform: Option<String>,
+    },
+    /// Changes made together, see [`ManyChanges`].
+    Many {
+        /// The label of the changes, if they were grouped by a transaction.
+        label: Option<String>,
+        /// The changes, in the order they were made.
+        changes: Vec<PersistedChange>,
+    },
+}
+
+/// An entity and its descendants as saved to disk, see [`EntitySnapshot`].
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct PersistedHierarchy {
+    root: PersistedEntitySnapshot,
+    /// The index of the entity among the children of its parent, if it has one.
+    index: Option<usize>,
+    /// The [`StableEntityId`]s of the entities referenced by the components, by the bits of their
+    /// [`Entity`] ID when the history was saved, to map them to the entities of the next session.
+    references: Vec<(u64, StableEntityId)>,
+}
+
+/// The state of an entity in a [`PersistedHierarchy`].
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+struct PersistedEntitySnapshot {
+    entity: StableEntityId,
+    /// The components of the entity, serialized with [`PersistContext::serialize`].
+    components: Vec<String>,
+    children: Vec<PersistedEntitySnapshot>,
+}
+
+/// The undo history as saved to disk.
+#[derive(Serialize, Deserialize)]
+struct PersistedHistory {
+    changes: Vec<PersistedChange>,
+}
+
+/// Gives what changes need to persist themselves, see [`EditorChange::persist`].
+pub struct PersistContext<'a> {
+    stable_ids: &'a StableEntityIds,
+    type_registry: &'a TypeRegistry,
+}
+
+impl PersistContext<'_> {
+    /// The [`StableEntityId`] of an entity, `None` if it has none.
+    pub fn stable_id(&self, entity: Entity) -> Option<StableEntityId> {
+        self.stable_ids.ids.get(&entity).copied()
+    }
+
+    /// Serialize a reflected value along with its type, `None` if it can't be serialized.
+    pub fn serialize(&self, value: &dyn PartialReflect) -> Option<String> {
+        ron::to_string(&ReflectSerializer::new(value, self.type_registry)).ok()
+    }
+
+    /// Persist an entity hierarchy, `None` if one of its entities has no [`StableEntityId`] or one
+    /// of its components can't be serialized.
+    pub(crate) fn persist_hierarchy(
+        &self,
+        snapshot: &EntitySnapshot,
+        index: Option<usize>,
+    ) -> Option<PersistedHierarchy> {
+        let mut references = HashMap::default();
+        let root = self.persist_snapshot(snapshot, &mut references)?;
+        Some(PersistedHierarchy {
+            root,
+            index,
+            references: references.into_iter().collect(),
+        })
+    }
+
+    fn persist_snapshot(
+        &self,
+        snapshot: &EntitySnapshot,
+        references: &mut HashMap<u64, StableEntityId>,
+    ) -> Option<PersistedEntitySnapshot> {
+        let mut components = Vec::with_capacity(snapshot.components.len());
+        for (type_id, value) in &snapshot.components {
+            // Entities can only be found in concrete values
+            if let Some(reflect_component) = self
+                .type_registry
+                .get_type_data::<ReflectComponent>(*type_id)
+                && let Ok(mut value) = value.reflect_clone()
+            {
+                let mut recorder = ReferenceRecorder {
+                    context: self,
+                    references: &mut *references,
+                };
+                reflect_component.map_entities(value.as_mut(), &mut recorder);
+            }
+            components.push(self.serialize(value.as_ref())?);
+        }
+        let children = snapshot
+            .children
+            .iter()
+            .map(|child| self.persist_snapshot(child, references))
+            .collect::<Option<_>>()?;
+
+        Some(PersistedEntitySnapshot {
+            entity: self.stable_id(snapshot.entity)?,
+            components,
+            children,
+        })
+    }
+
+    /// The [`StableEntityId`] of an optional entity, `None` if the entity has none.
+    fn optional_stable_id(&self, entity: Option<Entity>) -> Option<Option<StableEntityId>> {
+        match entity {
+            Some(entity) => self.stable_id(entity).map(Some),
+            None => Some(None),
+        }
+    }
+
+    /// Persist a [`HierarchyChange`], `None` if its entities have no [`StableEntityId`].
+    pub(crate) fn persist_hierarchy_change(
+        &self,
+        change: &HierarchyChange,
+    ) -> Option<PersistedChange> {
+        let serialize_transform = |transform: Option<Transform>| match transform {
+            Some(transform) => self.serialize(&transform).map(Some),
+            None => Some(None),
+        };
+        Some(PersistedChange::HierarchyChanged {
+            entity: self.stable_id(change.entity)?,
+            old_parent: self.optional_stable_id(change.old_parent)?,
+            old_index: change.old_index,
+            new_parent: self.optional_stable_id(change.new_parent)?,
+            new_index: change.new_index,
+            old_transform: serialize_transform(change.old_transform)?,
+            new_transform: serialize_transform(change.new_transform)?,
+        })
+    }
+}
+
+/// Records the [`StableEntityId`]s of the entities referenced by a component.
+struct ReferenceRecorder<'a, 'w> {
+    context: &'a PersistContext<'w>,
+    references: &'a mut HashMap<u64, StableEntityId>,
+}
+
+impl EntityMapper for ReferenceRecorder<'_, '_> {
+    fn get_mapped(&mut self, source: Entity) -> Entity {
+        if let Some(id) = self.context.stable_id(source) {
+            self.references.insert(source.to_bits(), id);
+        }
+        source
+    }
+
+    fn set_mapped(&mut self, _source: Entity, _target: Entity) {}
+}
+
+/// Maps the entities referenced by the components of a [`PersistedHierarchy`] to the entities of
+/// this session, by the bits of their [`Entity`] ID when the history was saved.
+struct ReferenceMapper<'a> {
+    references: &'a HashMap<u64, Entity>,
+}
+
+impl EntityMapper for ReferenceMapper<'_> {
+    fn get_mapped(&mut self, source: Entity) -> Entity {
+        self.references
+            .get(&source.to_bits())
+            .copied()
+            .unwrap_or(source)
+    }
+
+    fn set_mapped(&mut self, _source: Entity, _target: Entity) {}
+}
+
+impl PersistedHierarchy {
+    /// Turn the persisted hierarchy back into a snapshot and the index of its root.
+    fn restore(
+        self,
+        world: &mut World,
+        type_registry: &TypeRegistry,
+        entities: &mut HashMap<StableEntityId, Entity>,
+    ) -> Result<(Arc<EntitySnapshot>, Option<usize>), String> {
+        let references: HashMap<u64, Entity> = self
+            .references
+            .into_iter()
+            .map(|(bits, id)| (bits, resolve(world, entities, id)))
+            .collect();
+        let snapshot = self
+            .root
+            .restore(world, type_registry, entities, &references)?;
+        Ok((Arc::new(snapshot), self.index))
+    }
+}
+
+impl PersistedEntitySnapshot {
+    fn restore(
+        self,
+        world: &mut World,
+        type_registry: &TypeRegistry,
+        entities: &mut HashMap<StableEntityId, Entity>,
+        references: &HashMap<u64, Entity>,
+    ) -> Result<EntitySnapshot, String> {
+        let mut components = Vec::with_capacity(self.components.len());
+        for value in &self.components {
+            let (type_id, value) = deserialize(value, type_registry)?;
+            // Entities can only be mapped in concrete values
+            let concrete = type_registry
+                .get_type_data::<ReflectFromReflect>(type_id)
+                .and_then(|from_reflect| from_reflect.from_reflect(value.as_ref()));
+            let Some(mut concrete) = concrete else {
+                components.push((type_id, value));
+                continue;
+            };
+            if let Some(reflect_component) =
+                type_registry.get_type_data::<ReflectComponent>(type_id)
+            {
+                reflect_component
+                    .map_entities(concrete.as_mut(), &mut ReferenceMapper { references });
+            }
+            components.push((type_id, concrete.into_partial_reflect()));
+        }
+        let children = self
+            .children
+            .into_iter()
+            .map(|child| child.restore(world, type_registry, entities, references))
+            .collect::<Result<_, _>>()?;
+
+        Ok(EntitySnapshot {
+            entity: resolve(world, entities, self.entity),
+            components,
+            children,
+        })
+    }
+}
+
+impl PersistedChange {
+    /// Turn the persisted change back into a change, mapping its entities to the entities with the
+    /// same [`StableEntityId`].
+    fn restore(
+        self,
+        world: &mut World,
+        type_registry: &TypeRegistry,
+        entities: &mut HashMap<StableEntityId, Entity>,
+    ) -> Result<Arc<dyn EditorChange + Send + Sync>, String> {
+        let change: Arc<dyn EditorChange + Send + Sync> = match self {
+            PersistedChange::ComponentChanged {
+                entity,
+                old_value,
+                new_value,
+            } => {
+                let (component_type, old_value) = deserialize(&old_value, type_registry)?;
+                let (_, new_value) = deserialize(&new_value, type_registry)?;
+                Arc::new(DynamicComponentChange {
+                    entity: resolve(world, entities, entity),
+                    component_type,
+                    old_value,
+                    new_value,
+                })
+            }
+            PersistedChange::ComponentAdded { entity, value } => {
+                let (component_type, new_value) = deserialize(&value, type_registry)?;
+                Arc::new(DynamicAddedComponent {
+                    entity: resolve(world, entities, entity),
+                    component_type,
+                    new_value,
+                })
+            }
+            PersistedChange::ComponentRemoved { entity, value } => {
+                let (component_type, old_value) = deserialize(&value, type_registry)?;
+                Arc::new(DynamicRemovedComponent {
+                    entity: resolve(world, entities, entity),
+                    component_type,
+                    old_value,
+                })
+            }
+            PersistedChange::EntityAdded(entity) => {
+                Arc::new(AddedEntity::new(resolve(world, entities, entity)))
+            }
+            PersistedChange::EntityRemoved(entity) => Arc::new(RemovedEntity {
+                entity: resolve(world, entities, entity),
+            }),
+            PersistedChange::EntityHierarchyAdded(hierarchy) => {
+                let (snapshot, index) = hierarchy.restore(world, type_registry, entities)?;
+                Arc::new(AddedEntityHierarchy { snapshot, index })
+            }
+            PersistedChange::EntityHierarchyRemoved(hierarchy) => {
+                let (snapshot, index) = hierarchy.restore(world, type_registry, entities)?;
+                Arc::new(RemovedEntityHierarchy { snapshot, index })
+            }
+            PersistedChange::HierarchyChanged {
+                entity,
+                old_parent,
+                old_index,
+                new_parent,
+                new_index,
+                old_transform,
+                new_transform,
+            } => {
+                let mut resolve_parent =
+                    |parent: Option<StableEntityId>| parent.map(|id| resolve(world, entities, id));
+                let old_parent = resolve_parent(old_parent);
+                let new_parent = resolve_parent(new_parent);
+                let deserialize_transform = |transform: Option<String>| {
+                    transform
+                        .map(|transform| {
+                            let (_, value) = deserialize(&transform, type_registry)?;
+                            Transform::from_reflect(value.as_ref())
+                                .ok_or_else(|| format!("Invalid transform {transform}"))
+                        })
+                        .transpose()
+                };
+                Arc::new(HierarchyChange {
+                    entity: resolve(world, entities, entity),
+                    old_parent,
+                    old_index,
+                    new_parent,
+                    new_index,
+                    old_transform: deserialize_transform(old_transform)?,
+                    new_transform: deserialize_transform(new_transform)?,
+                })
+            }
+            PersistedChange::Many { label, changes } => {
+                let changes = changes
+                    .into_iter()
+                    .map(|change| change.restore(world, type_registry, entities))
+                    .collect::<Result<Vec<_>, _>>()?;
+                match label {
+                    Some(label) => Arc::new(ManyChanges::with_label(changes, label)),
+                    None => Arc::new(ManyChanges::new(changes)),
+                }
+            }
+        };
+        Ok(change)
+    }
+}
+
+/// The entity with a [`StableEntityId`], or a placeholder if there is none yet.
+fn resolve(
+    world: &mut World,
+    entities: &mut HashMap<StableEntityId, Entity>,
+    id: StableEntityId,
+) -> Entity {
+    *entities.entry(id).or_insert_with(|| {
+        // The ID of a despawned entity, which no live entity shares
+        let placeholder = world.spawn_empty().id();
+        world.despawn(placeholder);
+
+        let mut stable_ids = world.resource_mut::<StableEntityIds>();
+        stable_ids.ids.insert(placeholder, id);
+        stable_ids.placeholders.insert(id, placeholder);
+        placeholder
+    })
+}
+
+/// Deserialize a value serialized with [`PersistContext::serialize`], along with its type.
+fn deserialize(
+    value: &str,
+    type_registry: &TypeRegistry,
+) -> Result<(TypeId, Box<dyn PartialReflect>), String> {
+    let mut deserializer = ron::Deserializer::from_str(value).map_err(|error| error.to_string())?;
+    let value = ReflectDeserializer::new(type_registry)
+        .deserialize(&mut deserializer)
+        .map_err(|error| error.to_string())?;
+    let type_id = value
+        .get_represented_type_info()
+        .map(|type_info| type_info.type_id())
+        .ok_or_else(|| format!("{} is not a registered type", value.reflect_type_path()))?;
+    Ok((type_id, value))
+}
+
+/// Save the most recent changes of the [`ChangeChain`] which can all be persisted to `path`.
+///
+/// Returns how many of the older changes couldn't be saved, as one of the changes following them
+/// can't be persisted.
+pub fn save_history(world: &World, path: &Path) -> Result<usize, String> {
+    let type_registry = world.resource::<AppTypeRegistry>().read();
+    let context = PersistContext {
+        stable_ids: world.resource::<StableEntityIds>(),
+        type_registry: &type_registry,
+    };
+    let all_changes = &world.resource::<ChangeChain>().changes;
+    let mut changes: Vec<PersistedChange> = all_changes
+        .iter()
+        .rev()
+        .map_while(|change| change.persist(&context))
+        .collect();
+    changes.reverse();
+    let dropped = all_changes.len() - changes.len();
+
+    let text = ron::ser::to_string_pretty(
+        &PersistedHistory { changes },
+        ron::ser::PrettyConfig::default(),
+    )
+    .map_err(|error| error.to_string())?;
+
+    // Write to a temporary file first, so that a crash while saving doesn't lose the history
+    let mut temporary_path = path.as_os_str().to_owned();
+    temporary_path.push(".tmp");
+    std::fs::write(&temporary_path, text).map_err(|error| error.to_string())?;
+    std::fs::rename(&temporary_path, path).map_err(|error| error.to_string())?;
+    Ok(dropped)
+}
+
+/// Load the undo history saved to `path` with [`save_history`], placing it before the changes of
+/// the [`ChangeChain`].
+///
+/// The entities of the history are mapped to the entities with the same [`StableEntityId`], even
+/// if they are only spawned later on.
+pub fn load_history(world: &mut World, path: &Path) -> Result<(), String> {
+    let text = std::fs::read_to_string(path).map_err(|error| error.to_string())?;
+    let history: PersistedHistory = ron::from_str(&text).map_err(|error| error.to_string())?;
+
+    let mut query = world.query::<(Entity, &StableEntityId)>();
+    let mut entities: HashMap<StableEntityId, Entity> = query
+        .iter(world)
+        .map(|(entity, &id)| (id, entity))
+        .collect();
+
+    let type_registry = world.resource::<AppTypeRegistry>().clone();
+    let type_registry = type_registry.read();
+    let changes = history
+        .changes
+        .into_iter()
+        .map(|change| change.restore(world, &type_registry, &mut entities))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut change_chain = world.resource_mut::<ChangeChain>();
+    change_chain.memory += changes
+        .iter()
+        .map(|change| change.memory_size())
+        .sum::<usize>();
+    let current_changes = std::mem::replace(&mut change_chain.changes, changes);
+    change_chain.changes.extend(current_changes);
+    Ok(())
+}
+
+/// Persists the undo history of the edited scene next to the scene file.
+///
+/// Inserting this resource loads the history saved for the scene, so it should be inserted when
+/// opening the scene. The history is then saved whenever it changes.
+#[derive(Resource)]
+pub struct PersistentHistory {
+    scene: PathBuf,
+}
+
+impl PersistentHistory {
+    /// Persist the undo history of the scene saved at `scene`.
+    pub fn new(scene: impl Into<PathBuf>) -> Self {
+        Self {
+            scene: scene.into(),
+        }
+    }
+
+    /// The file the history is saved to, next to the scene file.
+    pub fn history_path(&self) -> PathBuf {
+        let mut path = self.scene.as_os_str().to_owned();
+        path.push(".undo.ron");
+        path.into()
+    }
+}
+
+/// The state of the history when it was last saved by [`persist_history`].
+#[derive(Default)]
+pub(crate) struct SavedHistory {
+    /// The number of changes and the last change, `None` if the history wasn't saved yet.
+    state: Option<(usize, Option<Arc<dyn EditorChange + Send + Sync>>)>,
+    /// How many of the older changes couldn't be saved.
+    dropped: usize,
+}
+
+/// Load the history when [`PersistentHistory`] is inserted, and save it when it changes.
+///
+/// The history isn't saved while the last step can still merge the following changes, such as
+/// while a value is dragged, so that the file isn't rewritten every frame. It is saved when the
+/// app exits in any case.
+pub(crate) fn persist_history(world: &mut World, mut saved: Local<SavedHistory>) {
+    let Some(persistent_history) = world.get_resource_ref::<PersistentHistory>() else {
+        return;
+    };
+    let path = persistent_history.history_path();
+    let scene_changed = persistent_history.is_changed();
+
+    if scene_changed {
+        *saved = SavedHistory::default();
+        if path.exists()
+            && let Err(error) = load_history(world, &path)
+        {
+            warn!(
+                "Failed to load the undo history from {}: {error}",
+                path.display()
+            );
+        }
+    }
+
+    let change_chain = world.resource::<ChangeChain>();
+    let changes = &change_chain.changes;
+    let state = (changes.len(), changes.last().cloned());
+    let last_ptr = |last: &Option<Arc<dyn EditorChange + Send + Sync>>| {
+        last.as_ref().map(|change| Arc::as_ptr(change).cast::<()>())
+    };
+    let unchanged = saved
+        .state
+        .as_ref()
+        .is_some_and(|(len, last)| *len == state.0 && last_ptr(last) == last_ptr(&state.1));
+    if unchanged {
+        return;
+    }
+
+    let exiting = world
+        .get_resource::<Events<AppExit>>()
+        .is_some_and(|events| !events.is_empty());
+    let now = world.resource::<Time<Real>>().elapsed();
+    let merge_window = world.resource::<ChangeChainSettings>().merge_window;
+    let merging = change_chain
+        .last_step_time
+        .is_some_and(|time| now.saturating_sub(time) <= merge_window);
+    if merging && !exiting {
+        return;
+    }
+
+    match save_history(world, &path) {
+        Ok(dropped) => {
+            if dropped > saved.dropped {
+                warn!(
+                    "{dropped} older undo steps can't be saved to {}, as a step following them \
+                     can't be persisted",
+                    path.display()
+                );
+            }
+            saved.dropped = dropped;
+        }
+        Err(error) => warn!(
+            "Failed to save the undo history to {}: {error}",
+            path.display()
+        ),
+    }
+    saved.state = Some(state);
+}
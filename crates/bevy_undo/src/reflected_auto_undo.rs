@@ -0,0 +1,177 @@
+//! Automatic undo of every reflected component, see
+//! [`AppAutoUndo::auto_reflected_undo_all`](crate::AppAutoUndo::auto_reflected_undo_all).
+//!
+//! Instead of a set of systems per component type, a single system goes through the components of
+//! the [`UndoMarker`] entities and looks up their [`ReflectComponent`] in the type registry. The
+//! last recorded value of each component is kept as a dynamic value, to record the changes with
+//! [`DynamicComponentChange`], [`DynamicAddedComponent`] and [`DynamicRemovedComponent`].
+
+use std::any::TypeId;
+
+use bevy::{
+    ecs::{component::Tick, entity::EntityHashMap},
+    platform::collections::{HashMap, HashSet},
+    prelude::*,
+    reflect::FromType,
+};
+
+use crate::{
+    AUTO_UNDO_LATENCY, DynamicAddedComponent, DynamicComponentChange, DynamicRemovedComponent,
+    NewChange, OneFrameUndoIgnore, UndoIgnoreStorage, UndoMarker,
+};
+
+/// Type data opting a component type out of
+/// [`AppAutoUndo::auto_reflected_undo_all`](crate::AppAutoUndo::auto_reflected_undo_all).
+///
+/// Components computed from other components, such as [`GlobalTransform`], should opt out, so
+/// that their updates aren't recorded along with the changes they follow from.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// use bevy_undo::ReflectNoAutoUndo;
+///
+/// #[derive(Component, Reflect)]
+/// #[reflect(Component, NoAutoUndo)]
+/// struct Velocity(Vec3);
+/// ```
+#[derive(Clone)]
+pub struct ReflectNoAutoUndo;
+
+impl<T> FromType<T> for ReflectNoAutoUndo {
+    fn from_type() -> Self {
+        Self
+    }
+}
+
+/// The last recorded values of the reflected components of the [`UndoMarker`] entities.
+#[derive(Resource, Default)]
+pub(crate) struct ReflectedAutoUndoStorage {
+    values: EntityHashMap<HashMap<TypeId, Box<dyn PartialReflect>>>,
+    /// The components which changed recently, with the number of frames left before their change
+    /// is recorded, so that a change is recorded once the component stops changing.
+    pending: HashMap<(Entity, TypeId), i32>,
+    /// The change tick of the last run, to detect the components changed since.
+    last_run: Tick,
+}
+
+pub(crate) fn auto_undo_reflected_components(world: &mut World) {
+    let this_run = world.change_tick();
+    let mut marked =
+        world.query_filtered::<(EntityRef, Has<OneFrameUndoIgnore>), With<UndoMarker>>();
+    let type_registry = world.resource::<AppTypeRegistry>().clone();
+    let type_registry = type_registry.read();
+
+    let new_changes = world.resource_scope(|world, mut storage: Mut<ReflectedAutoUndoStorage>| {
+        let storage = &mut *storage;
+        let last_run = std::mem::replace(&mut storage.last_run, this_run);
+        let ignore_storage = world.resource::<UndoIgnoreStorage>();
+
+        let mut new_changes = Vec::new();
+        let mut tracked_entities = HashSet::new();
+        for (entity_ref, ignored) in marked.iter(world) {
+            let entity = entity_ref.id();
+            tracked_entities.insert(entity);
+            // Entities which were just marked, or changed by undo or redo, only update the values
+            let refresh = ignored
+                || ignore_storage.storage.contains_key(&entity)
+                || entity_ref
+                    .get_change_ticks::<UndoMarker>()
+                    .is_some_and(|ticks| ticks.is_added(last_run, this_run));
+            let values = storage.values.entry(entity).or_default();
+
+            let mut present = HashSet::new();
+            let Ok(components) = world.inspect_entity(entity) else {
+                continue;
+            };
+            for info in components {
+                let Some(type_id) = info.type_id() else {
+                    continue;
+                };
+                let Some(registration) = type_registry.get(type_id) else {
+                    continue;
+                };
+                if registration.contains::<ReflectNoAutoUndo>() {
+                    continue;
+                }
+                let Some(value) = registration
+                    .data::<ReflectComponent>()
+                    .and_then(|reflect_component| reflect_component.reflect(entity_ref))
+                else {
+                    continue;
+                };
+                present.insert(type_id);
+
+                let key = (entity, type_id);
+                if refresh {
+                    values.insert(type_id, value.to_dynamic());
+                    storage.pending.remove(&key);
+                    continue;
+                }
+                if !values.contains_key(&type_id) {
+                    values.insert(type_id, value.to_dynamic());
+                    new_changes.push(NewChange::new(DynamicAddedComponent {
+                        entity,
+                        component_type: type_id,
+                        new_value: value.to_dynamic(),
+                    }));
+                    continue;
+                }
+
+                let changed = entity_ref
+                    .get_change_ticks_by_id(info.id())
+                    .is_some_and(|ticks| ticks.is_changed(last_run, this_run));
+                if changed {
+                    storage.pending.insert(key, AUTO_UNDO_LATENCY);
+                    continue;
+                }
+                let Some(latency) = storage.pending.get_mut(&key) else {
+                    continue;
+                };
+                *latency -= 1;
+                if *latency > 0 {
+                    continue;
+                }
+
+                storage.pending.remove(&key);
+                let Some(old_value) = values.insert(type_id, value.to_dynamic()) else {
+                    continue;
+                };
+                if old_value.reflect_partial_eq(value.as_partial_reflect()) != Some(true) {
+                    new_changes.push(NewChange::new(DynamicComponentChange {
+                        entity,
+                        component_type: type_id,
+                        old_value,
+                        new_value: value.to_dynamic(),
+                    }));
+                }
+            }
+
+            values.retain(|&type_id, old_value| {
+                if present.contains(&type_id) {
+                    return true;
+                }
+                storage.pending.remove(&(entity, type_id));
+                if !refresh {
+                    new_changes.push(NewChange::new(DynamicRemovedComponent {
+                        entity,
+                        component_type: type_id,
+                        old_value: old_value.to_dynamic(),
+                    }));
+                }
+                false
+            });
+        }
+
+        // Forget the entities which were despawned or aren't marked anymore
+        storage
+            .values
+            .retain(|entity, _| tracked_entities.contains(entity));
+        storage
+            .pending
+            .retain(|(entity, _), _| tracked_entities.contains(entity));
+
+        new_changes
+    });
+
+    world.write_event_batch(new_changes);
+}